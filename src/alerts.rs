@@ -0,0 +1,277 @@
+use crate::config;
+use chrono::{DateTime, Local};
+use rusty_wx::element::{format_scale_value, WeatherElement};
+use rusty_wx::weather::WeatherDataPoint;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use zbus::zvariant::Value;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    Above,
+    Below,
+}
+
+// A user-defined threshold on a weather element, e.g. gusts above 40 mph
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub element: WeatherElement,
+    pub condition: Condition,
+    pub threshold: f64,
+    // Only look this far ahead; the whole forecast when unset
+    #[serde(default)]
+    pub within_hours: Option<u32>,
+}
+
+impl AlertRule {
+    pub fn description(&self) -> String {
+        let condition = match self.condition {
+            Condition::Above => "above",
+            Condition::Below => "below",
+        };
+        let mut description = format!(
+            "{} {} {}",
+            self.element.label(),
+            condition,
+            format_threshold(&self.element, self.threshold)
+        );
+        if let Some(hours) = self.within_hours {
+            description.push_str(&format!(" in the next {}h", hours));
+        }
+        description
+    }
+
+    fn matches(&self, value: f64) -> bool {
+        match self.condition {
+            Condition::Above => value > self.threshold,
+            Condition::Below => value < self.threshold,
+        }
+    }
+}
+
+// A run of consecutive forecast hours that meet a rule
+#[derive(Debug, Clone)]
+pub struct TriggeredAlert {
+    pub rule: AlertRule,
+    pub start: i64,
+    pub end: i64,
+    // Highest value for Above rules, lowest for Below
+    pub peak: f64,
+}
+
+impl TriggeredAlert {
+    pub fn message(&self) -> String {
+        let start = local_time(self.start).format("%a %-I %p");
+        let peak = match self.rule.condition {
+            Condition::Above => "peak",
+            Condition::Below => "lowest",
+        };
+        let peak = format!(
+            "{} {}",
+            peak,
+            format_threshold(&self.rule.element, self.peak)
+        );
+        if self.end > self.start {
+            format!(
+                "from {} to {} ({})",
+                start,
+                local_time(self.end).format("%a %-I %p"),
+                peak
+            )
+        } else {
+            format!("at {} ({})", start, peak)
+        }
+    }
+}
+
+fn local_time(timestamp: i64) -> DateTime<Local> {
+    DateTime::from_timestamp(timestamp, 0)
+        .unwrap_or_default()
+        .with_timezone(&Local)
+}
+
+fn format_threshold(element: &WeatherElement, value: f64) -> String {
+    if element.shares_temp_axis() {
+        format!("{:.0}°", value)
+    } else {
+        format_scale_value(element, value)
+    }
+}
+
+// Check each rule against the upcoming forecast hours
+pub fn evaluate(
+    rules: &[AlertRule],
+    timeline: &[WeatherDataPoint],
+    value_of: impl Fn(&WeatherDataPoint, &WeatherElement) -> Option<f64>,
+    now: i64,
+) -> Vec<TriggeredAlert> {
+    let mut triggered = Vec::new();
+
+    for rule in rules {
+        let horizon = rule
+            .within_hours
+            .map_or(i64::MAX, |hours| now + hours as i64 * 3600);
+        let mut current: Option<TriggeredAlert> = None;
+
+        for point in timeline {
+            let WeatherDataPoint::Forecast(_) = point else {
+                continue;
+            };
+            let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+                continue;
+            };
+            let time = dt.timestamp();
+            if time < now - 1800 || time > horizon {
+                continue;
+            }
+
+            match value_of(point, &rule.element).filter(|v| rule.matches(*v)) {
+                Some(value) => match current.as_mut() {
+                    Some(alert) => {
+                        alert.end = time;
+                        alert.peak = match rule.condition {
+                            Condition::Above => alert.peak.max(value),
+                            Condition::Below => alert.peak.min(value),
+                        };
+                    }
+                    None => {
+                        current = Some(TriggeredAlert {
+                            rule: rule.clone(),
+                            start: time,
+                            end: time,
+                            peak: value,
+                        })
+                    }
+                },
+                None => triggered.extend(current.take()),
+            }
+        }
+        triggered.extend(current);
+    }
+
+    triggered
+}
+
+// Alerts already notified, kept across runs so a refetch doesn't repeat them
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NotifiedAlerts {
+    events: Vec<NotifiedEvent>,
+    // Where `load` read them from; without one (as in tests) nothing is saved
+    #[serde(skip)]
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct NotifiedEvent {
+    location: String,
+    rule: String,
+    start: i64,
+    end: i64,
+}
+
+impl NotifiedAlerts {
+    pub fn load() -> Self {
+        let path = notified_path();
+        let notified: NotifiedAlerts = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        NotifiedAlerts {
+            path: Some(path),
+            ..notified
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    // The same event drifts a little between forecast cycles, so any overlap with a notified
+    // run of the same rule counts as already seen
+    fn seen(&self, location: &str, alert: &TriggeredAlert) -> bool {
+        let rule = alert.rule.description();
+        self.events.iter().any(|event| {
+            event.location == location
+                && event.rule == rule
+                && event.start <= alert.end
+                && alert.start <= event.end
+        })
+    }
+
+    // The alerts that haven't been notified yet, forgetting events over a day old
+    pub fn unseen<'a>(
+        &mut self,
+        location: &str,
+        alerts: &'a [TriggeredAlert],
+        now: i64,
+    ) -> Vec<&'a TriggeredAlert> {
+        self.events.retain(|event| event.end >= now - 86400);
+        alerts
+            .iter()
+            .filter(|alert| !self.seen(location, alert))
+            .collect()
+    }
+
+    // Remember an alert once its notification has gone out
+    pub fn record(&mut self, location: &str, alert: &TriggeredAlert) {
+        if !self.seen(location, alert) {
+            self.events.push(NotifiedEvent {
+                location: location.to_string(),
+                rule: alert.rule.description(),
+                start: alert.start,
+                end: alert.end,
+            });
+        }
+    }
+}
+
+fn notified_path() -> std::path::PathBuf {
+    config::config_dir().join("notified-alerts.json")
+}
+
+// Send a desktop notification through the freedesktop Notifications service
+pub async fn notify(summary: String, body: String) -> Result<(), String> {
+    let connection = zbus::Connection::session()
+        .await
+        .map_err(|e| e.to_string())?;
+    notify_on(&connection, &summary, &body).await
+}
+
+// The Notify call on a connection to the service, the session bus outside of tests
+pub async fn notify_on(
+    connection: &zbus::Connection,
+    summary: &str,
+    body: &str,
+) -> Result<(), String> {
+    let actions: Vec<&str> = Vec::new();
+    let hints: HashMap<&str, Value> = HashMap::from([("urgency", Value::U8(1))]);
+
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "rusty-wx",
+                0u32,
+                "weather-severe-alert",
+                summary,
+                body,
+                actions,
+                hints,
+                -1i32,
+            ),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,419 @@
+use crate::alerts::{self, AlertRule};
+use crate::cache::{CachedSite, SiteCache};
+use chrono::{DateTime, Local, Utc};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use rusty_wx::daily::{self, DailyRow};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::timeline::{self, element_value};
+use rusty_wx::weather::{ApiWeatherData, WeatherDataPoint};
+use serde_json::{json, Map, Value};
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+// What every request needs besides the cache
+#[derive(Clone)]
+struct ApiState {
+    cache: SiteCache,
+    alerts: Vec<AlertRule>,
+    interval: Duration,
+}
+
+// A JSON response, or a status with an error message
+type ApiResult = Result<Value, (StatusCode, String)>;
+
+fn not_found(what: &str) -> (StatusCode, String) {
+    (StatusCode::NOT_FOUND, format!("no {}", what))
+}
+
+fn site<'a>(sites: &'a [CachedSite], id: &str) -> Result<&'a CachedSite, (StatusCode, String)> {
+    sites
+        .iter()
+        .find(|site| site.id == id)
+        .ok_or_else(|| not_found(&format!("location '{}'", id)))
+}
+
+// The fetched data for a site, or why there is none yet
+fn site_data(site: &CachedSite) -> Result<&ApiWeatherData, (StatusCode, String)> {
+    site.data.as_ref().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            site.last_error
+                .clone()
+                .unwrap_or_else(|| "not fetched yet".to_string()),
+        )
+    })
+}
+
+fn location_json(site: &CachedSite) -> Value {
+    json!({
+        "id": site.id,
+        "name": site.location.name,
+        "lat": site.location.lat,
+        "lon": site.location.lon,
+        "fetched_at": site.fetched_at.map(|t| t.to_rfc3339()),
+        "forecast_cycle": site
+            .data
+            .as_ref()
+            .and_then(|data| data.forecasts_instant.first())
+            .map(|fc| fc.cycle_date.clone()),
+        "last_error": site.last_error,
+    })
+}
+
+// Comma-separated element keys from `elements=`; all elements when absent
+fn requested_elements(query: Option<&str>) -> Result<Vec<WeatherElement>, (StatusCode, String)> {
+    let Some(list) = query
+        .unwrap_or("")
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("elements="))
+    else {
+        return Ok(WeatherElement::ALL.to_vec());
+    };
+    list.replace("%2C", ",")
+        .replace("%2c", ",")
+        .split(',')
+        .filter(|key| !key.is_empty())
+        .map(|key| key.parse().map_err(|e| (StatusCode::BAD_REQUEST, e)))
+        .collect()
+}
+
+fn timeline_json(site: &CachedSite, elements: &[WeatherElement]) -> ApiResult {
+    let data = site_data(site)?;
+    let points: Vec<Value> = timeline::build_timeline(data)
+        .iter()
+        .map(|point| {
+            let (kind, cycle_date) = match point {
+                WeatherDataPoint::Observation(_) => ("observation", None),
+                WeatherDataPoint::Forecast(fc) => ("forecast", Some(fc.cycle_date.clone())),
+            };
+            let mut values = Map::new();
+            for element in elements {
+                values.insert(
+                    element.key().to_string(),
+                    json!(element_value(data, point, element)),
+                );
+            }
+            json!({
+                "valid_date": point.valid_date(),
+                "kind": kind,
+                "cycle_date": cycle_date,
+                "values": values,
+            })
+        })
+        .collect();
+    Ok(json!({
+        "id": site.id,
+        "name": site.location.name,
+        "points": points,
+    }))
+}
+
+fn daily_json(site: &CachedSite, now: DateTime<Utc>) -> ApiResult {
+    let data = site_data(site)?;
+    let today = now.with_timezone(&Local).date_naive();
+    let days: Vec<DailyRow> = daily::daily_rows(data, &Local)
+        .into_iter()
+        .filter(|day| day.date >= today)
+        .collect();
+    Ok(json!({
+        "id": site.id,
+        "name": site.location.name,
+        "days": days,
+    }))
+}
+
+// The configured alert rules checked against every location's forecast
+fn alerts_json(sites: &[CachedSite], rules: &[AlertRule], now: DateTime<Utc>) -> Value {
+    let mut triggered = Vec::new();
+    for site in sites {
+        let Some(data) = &site.data else {
+            continue;
+        };
+        let timeline = timeline::build_timeline(data);
+        for alert in alerts::evaluate(
+            rules,
+            &timeline,
+            |point, element| element_value(data, point, element),
+            now.timestamp(),
+        ) {
+            let time = |t: i64| DateTime::from_timestamp(t, 0).map(|t| t.to_rfc3339());
+            triggered.push(json!({
+                "id": site.id,
+                "name": site.location.name,
+                "element": alert.rule.element,
+                "rule": alert.rule.description(),
+                "start": time(alert.start),
+                "end": time(alert.end),
+                "peak": alert.peak,
+                "message": alert.message(),
+            }));
+        }
+    }
+    json!(triggered)
+}
+
+fn route(
+    state: &ApiState,
+    sites: &[CachedSite],
+    path: &str,
+    query: Option<&str>,
+    now: DateTime<Utc>,
+) -> ApiResult {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match segments.as_slice() {
+        ["locations"] => Ok(sites.iter().map(location_json).collect()),
+        ["locations", id] => Ok(location_json(site(sites, id)?)),
+        ["locations", id, "timeline"] => {
+            timeline_json(site(sites, id)?, &requested_elements(query)?)
+        }
+        ["locations", id, "daily"] => daily_json(site(sites, id)?, now),
+        ["alerts"] => Ok(alerts_json(sites, &state.alerts, now)),
+        _ => Err(not_found(&format!("route {}", path))),
+    }
+}
+
+// Responses stay valid until the next background refresh
+fn max_age(sites: &[CachedSite], interval: Duration) -> u64 {
+    let oldest = sites.iter().filter_map(|site| site.fetched_at).min();
+    let Some(oldest) = oldest else {
+        return 0;
+    };
+    let age = (Utc::now() - oldest).num_seconds().max(0) as u64;
+    interval.as_secs().saturating_sub(age)
+}
+
+async fn handle(state: ApiState, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let sites = state.cache.snapshot();
+    let result = if request.method() == Method::GET {
+        let uri = request.uri();
+        route(&state, &sites, uri.path(), uri.query(), Utc::now())
+    } else {
+        Err((
+            StatusCode::METHOD_NOT_ALLOWED,
+            "only GET is supported".to_string(),
+        ))
+    };
+    let (status, body) = match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err((status, message)) => (status, json!({ "error": message })),
+    };
+    let response = Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .header(
+            "Cache-Control",
+            format!("max-age={}", max_age(&sites, state.interval)),
+        )
+        .body(Body::from(body.to_string()));
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+// Serve the API until the process is stopped, refreshing the cache in the background
+pub async fn serve(
+    addr: SocketAddr,
+    cache: SiteCache,
+    alerts: Vec<AlertRule>,
+    interval: Duration,
+) -> Result<(), String> {
+    tokio::spawn(cache.clone().refresh_every(interval));
+
+    let state = ApiState {
+        cache,
+        alerts,
+        interval,
+    };
+    let make_service = make_service_fn(move |_connection| {
+        let state = state.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+    });
+    let server = Server::try_bind(&addr)
+        .map_err(|e| format!("{}: {}", addr, e))?
+        .serve(make_service);
+    eprintln!("Serving the API on http://{}/locations", addr);
+    server.await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::Condition;
+    use crate::config::SavedLocation;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    // Between the fixture's last observation and its first forecast
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-10-18T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn state(names: &[&str]) -> ApiState {
+        let locations: Vec<SavedLocation> = names
+            .iter()
+            .map(|name| SavedLocation {
+                name: name.to_string(),
+                lat: 0.0,
+                lon: 0.0,
+            })
+            .collect();
+        ApiState {
+            cache: SiteCache::new(&locations),
+            alerts: Vec::new(),
+            interval: Duration::from_secs(600),
+        }
+    }
+
+    #[test]
+    fn location_ids_are_unique() {
+        let state = state(&["Springfield", "Springfield", "東京"]);
+        let sites = state.cache.snapshot();
+        let listed = route(&state, &sites, "/locations", None, Utc::now()).unwrap();
+        let ids: Vec<&str> = listed
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|location| location["id"].as_str().unwrap())
+            .collect();
+        assert_eq!(ids, ["springfield", "springfield_2", "location_3"]);
+
+        // Each id leads to its own location
+        for (i, id) in ids.iter().enumerate() {
+            let path = format!("/locations/{}", id);
+            let location = route(&state, &sites, &path, None, Utc::now()).unwrap();
+            assert_eq!(location, listed[i]);
+        }
+        assert_eq!(
+            route(&state, &sites, "/locations//timeline", None, Utc::now())
+                .unwrap_err()
+                .0,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    // Denver fetched, Boulder not yet and Golden failing
+    fn fetched(state: &ApiState) -> Vec<CachedSite> {
+        let mut sites = state.cache.snapshot();
+        sites[0].data = Some(serde_json::from_str(FIXTURE).unwrap());
+        sites[2].last_error = Some("connection refused".to_string());
+        sites
+    }
+
+    fn get(state: &ApiState, sites: &[CachedSite], uri: &str) -> ApiResult {
+        let (path, query) = match uri.split_once('?') {
+            Some((path, query)) => (path, Some(query)),
+            None => (uri, None),
+        };
+        route(state, sites, path, query, now())
+    }
+
+    #[test]
+    fn timeline_with_selected_elements() {
+        let state = state(&["Denver", "Boulder", "Golden"]);
+        let sites = fetched(&state);
+        let timeline = get(
+            &state,
+            &sites,
+            "/locations/denver/timeline?elements=temperature%2Cgust",
+        )
+        .unwrap();
+        assert_eq!(timeline["id"], "denver");
+        let points = timeline["points"].as_array().unwrap();
+        assert_eq!(points.len(), 96);
+        assert_eq!(
+            points[0],
+            json!({
+                "valid_date": "2026-10-17T13:00:00Z",
+                "kind": "observation",
+                "cycle_date": null,
+                "values": {
+                    "temperature": points[0]["values"]["temperature"],
+                    "gust": points[0]["values"]["gust"],
+                },
+            })
+        );
+        assert_eq!(points[24]["kind"], "forecast");
+        assert_eq!(points[24]["cycle_date"], "2026-10-18T11:00:00Z");
+        assert_eq!(points[26]["values"]["temperature"], 72.0);
+
+        // Every element without a selection
+        let all = get(&state, &sites, "/locations/denver/timeline").unwrap();
+        let values = all["points"][0]["values"].as_object().unwrap();
+        assert_eq!(values.len(), WeatherElement::ALL.len());
+
+        let (status, message) =
+            get(&state, &sites, "/locations/denver/timeline?elements=rain").unwrap_err();
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(message.starts_with("unknown element 'rain'"));
+    }
+
+    #[test]
+    fn daily_from_today() {
+        let state = state(&["Denver", "Boulder", "Golden"]);
+        let sites = fetched(&state);
+        let daily = get(&state, &sites, "/locations/denver/daily").unwrap();
+        let days = daily["days"].as_array().unwrap();
+        let today = now().with_timezone(&Local).date_naive().to_string();
+        assert_eq!(days[0]["date"], today);
+        assert!(days[0]["high_f"].is_number());
+        assert!(days
+            .iter()
+            .all(|day| day["date"].as_str().unwrap() >= today.as_str()));
+    }
+
+    #[test]
+    fn errors() {
+        let state = state(&["Denver", "Boulder", "Golden"]);
+        let sites = fetched(&state);
+        let error = |uri| get(&state, &sites, uri).unwrap_err();
+
+        assert_eq!(
+            error("/locations/boulder/timeline"),
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "not fetched yet".to_string()
+            )
+        );
+        assert_eq!(
+            error("/locations/golden/daily"),
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "connection refused".to_string()
+            )
+        );
+        assert_eq!(
+            error("/locations/aspen"),
+            (StatusCode::NOT_FOUND, "no location 'aspen'".to_string())
+        );
+        assert_eq!(
+            error("/forecast"),
+            (StatusCode::NOT_FOUND, "no route /forecast".to_string())
+        );
+        assert_eq!(error("/locations/denver/hourly").0, StatusCode::NOT_FOUND);
+
+        // Listing works whether or not a location has been fetched
+        let golden = get(&state, &sites, "/locations/golden").unwrap();
+        assert_eq!(golden["last_error"], "connection refused");
+        assert_eq!(golden["forecast_cycle"], Value::Null);
+    }
+
+    #[test]
+    fn alerts_of_fetched_locations() {
+        let mut state = state(&["Denver", "Boulder", "Golden"]);
+        state.alerts = vec![AlertRule {
+            element: WeatherElement::WindGust,
+            condition: Condition::Above,
+            threshold: 30.0,
+            within_hours: None,
+        }];
+        let sites = fetched(&state);
+        let alerts = get(&state, &sites, "/alerts").unwrap();
+        let alerts = alerts.as_array().unwrap();
+        assert!(!alerts.is_empty());
+        assert!(alerts.iter().all(|alert| alert["id"] == "denver"));
+        assert_eq!(alerts[0]["element"], "gust");
+        assert!(alerts[0]["peak"].as_f64().unwrap() > 30.0);
+    }
+}
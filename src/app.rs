@@ -1,43 +1,86 @@
-use crate::weather::{self, ApiWeatherData, WeatherDataPoint};
+use crate::alerts::{self, NotifiedAlerts, TriggeredAlert};
+use crate::color_ramp::Palette;
+use crate::comparison::Comparison;
+use crate::config::{Config, MeteogramPanel};
+use crate::export::{self, ExportFormat};
+use crate::forecast_history;
+use crate::history::History;
+use crate::keys::{self, Action};
+use crate::meteogram::{self, Meteogram, OverlaySeries};
+use crate::panels::{PanelCanvas, PANEL_HEIGHT};
+use crate::table::{self, TableColumn, TableSort};
+use crate::theme::{self, ChartColors, ElementColor, ThemeMode};
 use chrono::{DateTime, Local, Utc};
 use iced::{
-    widget::{column, container, scrollable, text, Space},
-    Application, Color, Command, Element, Length, Theme,
+    keyboard,
+    widget::{column, container, row, scrollable, text, text_editor, text_input, Space},
+    Application, Command, Element, Length, Subscription, Theme,
 };
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum WeatherElement {
-    Temperature,
-    ApparentTemperature,
-    WBGT,
-    WindSpeed,
-    Pressure,
-    Humidity,
-    Dewpoint,
-    CloudCover,
-    Visibility,
-    SolarFlux,
-    ThunderstormProbability,
-    CAPE,
-    PrecipitationProbability,
-}
+use rusty_wx::archive::Archive;
+use rusty_wx::calendar;
+use rusty_wx::data_export::{self, DataFormat};
+use rusty_wx::element::{format_scale_value, WeatherElement};
+use rusty_wx::qc::{self, QcFlag};
+use rusty_wx::run_changes::{self, RunChange};
+use rusty_wx::verification::{ForecastRun, Scores};
+use rusty_wx::weather::{self, ApiWeatherData, ForecastPrecip, WeatherDataPoint};
+use rusty_wx::{summary, timeline};
+use std::future::Future;
+use std::path::PathBuf;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    WeatherFetched(Result<ApiWeatherData, String>),
+    WeatherFetched((String, f64, f64), Result<ApiWeatherData, String>), // Location fetched, result
     FetchWeather(String, f64, f64),
     LatInputChanged(String),
     LonInputChanged(String),
     FetchCustomLocation,
+    Refresh, // Refetch the current location, every refresh_minutes
+    Refreshed((String, f64, f64), Result<ApiWeatherData, String>), // Location fetched, result
     PreviousHour,
     NextHour,
     GoToNow,
-    MeteogramClicked(usize), // Index of the clicked time point
-    SelectWeatherElement(WeatherElement),
+    MeteogramClicked(usize),              // Index of the clicked time point
+    SelectWeatherElement(WeatherElement), // Toggles the element as a meteogram overlay
+    TogglePanel(MeteogramPanel),
+    MovePanelUp(MeteogramPanel),
+    SelectPalette(Palette),
+    SelectThemeMode(ThemeMode),
+    CheckSystemTheme,
+    SystemThemeChanged(bool),
+    KeyPressed(String),
+    ToggleHelp,
+    SummaryAction(text_editor::Action),
+    AlertNotified(String, TriggeredAlert, Result<(), String>), // Location, alert, result
+    ToggleComparison,
+    ComparisonFetched(usize, Result<ApiWeatherData, String>),
+    SelectComparisonElement(WeatherElement),
+    CopySummary,
+    ToggleTable,
+    ToggleTableColumn(TableColumn),
+    SortTable(TableColumn),
+    CopyTableCsv,
+    ToggleAccuracy,
+    TogglePreviousRuns,
+    ToggleHistory,
+    HistoryStartChanged(String),
+    HistoryEndChanged(String),
+    HistoryLastDays(i64), // Range of this many days up to today
+    HistoryShift(i64),    // Move the range by its length, back for negative steps
+    LoadHistory,
+    HistoryPointClicked(usize), // Index in the history's timeline
+    ExportMeteogram(ExportFormat),
+    ExportData(DataFormat),
+    ExportCalendar,
+    Exported(Result<PathBuf, String>),
+    CalendarAutoExported(Result<PathBuf, String>),
 }
 
 pub struct WeatherApp {
     pub current_city: String,
+    // Where the current location was fetched from, for the periodic refresh
+    pub coordinates: Option<(f64, f64)>,
     pub weather_data: Option<ApiWeatherData>,
     pub combined_timeline: Vec<WeatherDataPoint>,
     pub loading: bool,
@@ -48,13 +91,45 @@ pub struct WeatherApp {
     pub last_updated: Option<DateTime<Local>>,
     pub meteogram_scroll_id: scrollable::Id,
     pub should_scroll_to_now: bool,
-    pub selected_weather_element: WeatherElement,
+    pub selected_weather_elements: Vec<WeatherElement>,
+    pub config: Config,
+    pub export_status: Option<String>,
+    // Last color scheme reported by the desktop, used by ThemeMode::Auto
+    pub system_dark: bool,
+    pub search_input_id: text_input::Id,
+    pub show_help: bool,
+    // Read-only, but kept in an editor so it can be selected and copied
+    pub summary: text_editor::Content,
+    pub triggered_alerts: Vec<TriggeredAlert>,
+    pub notified_alerts: NotifiedAlerts,
+    // Observed values that failed the quality checks
+    pub qc_flags: Vec<QcFlag>,
+    // Side-by-side view of the saved locations, replacing the single-location display
+    pub comparison: Option<Comparison>,
+    // Hourly table under the meteogram
+    pub show_table: bool,
+    pub table_sort: TableSort,
+    // How the current location's archived runs have verified, and how many were scored
+    pub forecast_scores: Vec<Scores>,
+    pub scored_runs: usize,
+    pub show_accuracy: bool,
+    // Earlier runs for the current location, newest first, and what changed since the last one
+    pub previous_runs: Vec<ForecastRun>,
+    pub run_changes: Vec<RunChange>,
+    pub show_previous_runs: bool,
+    // Every fetch, for history; opened by the GUI only
+    pub archive: Option<Archive>,
+    // Archived observations of the current location over a chosen date range, when shown
+    pub history: Option<History>,
+    // Weather API base URL, normally weather::base_url(); tests point it at a mock server
+    pub api_url: String,
 }
 
 impl Default for WeatherApp {
     fn default() -> Self {
         Self {
             current_city: String::new(),
+            coordinates: None,
             weather_data: None,
             combined_timeline: Vec::new(),
             loading: false,
@@ -65,7 +140,28 @@ impl Default for WeatherApp {
             last_updated: None,
             meteogram_scroll_id: scrollable::Id::unique(),
             should_scroll_to_now: false,
-            selected_weather_element: WeatherElement::PrecipitationProbability, // Default to Precipitation Probability
+            selected_weather_elements: vec![WeatherElement::PrecipitationProbability], // Default to Precipitation Probability
+            config: Config::default(),
+            export_status: None,
+            system_dark: false,
+            search_input_id: text_input::Id::unique(),
+            show_help: false,
+            summary: text_editor::Content::new(),
+            triggered_alerts: Vec::new(),
+            notified_alerts: NotifiedAlerts::default(),
+            qc_flags: Vec::new(),
+            comparison: None,
+            show_table: false,
+            table_sort: None,
+            forecast_scores: Vec::new(),
+            scored_runs: 0,
+            show_accuracy: false,
+            previous_runs: Vec::new(),
+            run_changes: Vec::new(),
+            show_previous_runs: false,
+            archive: None,
+            history: None,
+            api_url: weather::base_url(),
         }
     }
 }
@@ -73,74 +169,381 @@ impl Default for WeatherApp {
 impl WeatherApp {
     pub fn get_precipitation_probability(&self, target_time: &str) -> Option<f64> {
         if let Some(weather) = &self.weather_data {
-            if let Ok(target_dt) = DateTime::parse_from_rfc3339(target_time) {
-                let target_timestamp = target_dt.timestamp();
-
-                // First try 1hr forecasts for more granular data
-                for precip in &weather.forecasts_precip_1hr {
-                    if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
-                        let valid_timestamp = valid_dt.timestamp();
-                        // Check if target time is within the forecast period (up to 1 hour before valid time)
-                        if target_timestamp <= valid_timestamp
-                            && target_timestamp > valid_timestamp - 3600
-                        {
-                            return Some(precip.prob_precip_pct);
-                        }
-                    }
-                }
+            timeline::precipitation_probability_from(
+                &weather.forecasts_precip_1hr,
+                &weather.forecasts_precip_6hr,
+                target_time,
+            )
+        } else {
+            None
+        }
+    }
 
-                // Fallback to 6hr forecasts
-                for precip in &weather.forecasts_precip_6hr {
-                    if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
-                        let valid_timestamp = valid_dt.timestamp();
-                        // Check if target time is within the forecast period (up to 6 hours before valid time)
-                        if target_timestamp <= valid_timestamp
-                            && target_timestamp > valid_timestamp - 21600
-                        {
-                            return Some(precip.prob_precip_pct);
-                        }
-                    }
+    // App state for a fetched location without the GUI around it, as used by the CLI
+    // and for each site in the comparison view
+    pub fn for_location(name: &str, data: ApiWeatherData) -> WeatherApp {
+        let mut app = WeatherApp {
+            current_city: name.to_string(),
+            weather_data: Some(data),
+            last_updated: Some(Local::now()),
+            ..WeatherApp::default()
+        };
+        app.build_timeline();
+        app
+    }
+
+    // Fetch a location from `api_url`
+    fn fetch(
+        &self,
+        lat: f64,
+        lon: f64,
+    ) -> impl Future<Output = Result<ApiWeatherData, String>> + 'static {
+        let url = self.api_url.clone();
+        async move { weather::fetch_weather_from(&url, lat, lon).await }
+    }
+
+    // Fetch a location, tagging the reply with it so a reply for a location switched away from
+    // in the meantime can be told apart
+    fn fetch_location(&self, name: &str, lat: f64, lon: f64) -> Command<Message> {
+        let location = (name.to_string(), lat, lon);
+        Command::perform(self.fetch(lat, lon), move |result| {
+            Message::WeatherFetched(location, result)
+        })
+    }
+
+    // Whether a reply tagged with `location` is for the location shown now
+    fn is_current(&self, (city, lat, lon): &(String, f64, f64)) -> bool {
+        *city == self.current_city && self.coordinates == Some((*lat, *lon))
+    }
+
+    // Show newly fetched data, archive and verify it, and check it for alerts
+    fn show_fetch(&mut self, data: ApiWeatherData) -> Command<Message> {
+        self.weather_data = Some(data);
+        self.error = None;
+        self.last_updated = Some(Local::now());
+        self.build_timeline();
+        self.archive_fetch();
+        self.verify_forecasts();
+        Command::batch([self.check_alerts(), self.auto_export_calendar()])
+    }
+
+    // Main meteogram for the current timeline and overlay selection
+    pub fn build_meteogram(&self) -> Meteogram {
+        let (unified_temp_min, unified_temp_max) =
+            self.get_unified_temp_range(&self.combined_timeline);
+        let overlays = self
+            .selected_weather_elements
+            .iter()
+            .map(|element| {
+                let (min, max) = self.get_element_range(&self.combined_timeline, element);
+                OverlaySeries {
+                    element: element.clone(),
+                    min,
+                    max,
                 }
-            }
+            })
+            .collect();
+
+        Meteogram::new(
+            self.combined_timeline.clone(),
+            self.timeline_index,
+            overlays,
+            self.weather_data.clone(),
+            unified_temp_min,
+            unified_temp_max,
+            self.config.temperature_ramp(),
+        )
+        .with_alerts(self.alert_spans())
+        .with_qc_flags(self.qc_flags.clone())
+        .with_previous_runs(self.previous_run_series())
+    }
+
+    // Temperatures of the earlier runs within the timeline, as (timestamp, °F), when shown
+    pub fn previous_run_series(&self) -> Vec<Vec<(i64, f64)>> {
+        let (true, Some((start, end))) = (
+            self.show_previous_runs,
+            meteogram::time_bounds(&self.combined_timeline),
+        ) else {
+            return Vec::new();
+        };
+        self.previous_runs
+            .iter()
+            .map(|run| {
+                run.weather
+                    .forecasts_instant
+                    .iter()
+                    .filter_map(|fc| {
+                        let time = DateTime::parse_from_rfc3339(&fc.valid_date)
+                            .ok()?
+                            .timestamp();
+                        Some((time, fc.temperature_2m_f?))
+                    })
+                    .filter(|(time, _)| (start..=end).contains(time))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn build_panel(&self, panel: MeteogramPanel) -> PanelCanvas {
+        let (precip_1hr, precip_6hr) = self.precip_forecasts();
+        PanelCanvas::new(
+            panel,
+            self.combined_timeline.clone(),
+            self.timeline_index,
+            precip_1hr,
+            precip_6hr,
+        )
+        .with_alerts(self.alert_spans())
+        .with_qc_flags(self.qc_flags.clone())
+    }
+
+    fn alert_spans(&self) -> Vec<(i64, i64)> {
+        self.triggered_alerts
+            .iter()
+            .map(|alert| (alert.start, alert.end))
+            .collect()
+    }
+
+    // Evaluate the alert rules against the current forecast and notify any new alerts
+    fn check_alerts(&mut self) -> Command<Message> {
+        let now = Utc::now().timestamp();
+        self.triggered_alerts = alerts::evaluate(
+            &self.config.alerts,
+            &self.combined_timeline,
+            |point, element| self.get_element_value_for_scale(point, element),
+            now,
+        );
+
+        // Recorded as notified only once the notification has gone out, so a failed one is
+        // tried again on the next fetch
+        let new_alerts =
+            self.notified_alerts
+                .unseen(&self.current_city, &self.triggered_alerts, now);
+        Command::batch(new_alerts.into_iter().map(|alert| {
+            let summary = format!("{}: {}", self.current_city, alert.rule.description());
+            let location = self.current_city.clone();
+            let alert = alert.clone();
+            Command::perform(alerts::notify(summary, alert.message()), move |result| {
+                Message::AlertNotified(location, alert, result)
+            })
+        }))
+    }
+
+    // Rescore the location's archived runs and read back the ones before the fetched run, once
+    // the fetch is archived
+    fn verify_forecasts(&mut self) {
+        self.forecast_scores.clear();
+        self.scored_runs = 0;
+        self.previous_runs.clear();
+        self.run_changes.clear();
+        let (Some(archive), Some(weather)) = (&self.archive, &self.weather_data) else {
+            return;
+        };
+        let Some(cycle) = weather
+            .forecasts_instant
+            .first()
+            .and_then(|fc| DateTime::parse_from_rfc3339(&fc.cycle_date).ok())
+            .map(|time| time.timestamp())
+        else {
+            return;
+        };
+        let rules = self.config.qc.enabled.then_some(&self.config.qc.rules);
+        match forecast_history::scores(archive, &self.current_city, cycle, rules) {
+            Ok((runs, scores)) => (self.scored_runs, self.forecast_scores) = (runs, scores),
+            Err(e) => eprintln!("Failed to score {}: {}", self.current_city, e),
+        }
+        match forecast_history::previous_runs(
+            archive,
+            &self.current_city,
+            cycle,
+            self.config.previous_runs,
+        ) {
+            Ok(runs) => self.previous_runs = runs,
+            Err(e) => eprintln!(
+                "Failed to read earlier runs of {}: {}",
+                self.current_city, e
+            ),
+        }
+        if let Some(run) = self.previous_runs.first() {
+            self.run_changes = run_changes::compare(&run.weather, weather, &Local);
         }
-        None
     }
 
-    pub fn build_timeline(&mut self) {
+    fn archive_fetch(&mut self) {
+        if let (Some(archive), Some(weather)) = (&mut self.archive, &self.weather_data) {
+            archive_fetch(archive, &self.current_city, weather);
+        }
+    }
+
+    // Read the history's date range back from the archive
+    fn load_history(&mut self) {
+        if let (Some(history), Some(archive)) = (&mut self.history, &self.archive) {
+            history.load(archive, &self.config);
+        }
+    }
+
+    // Rewrite the subscribed calendar file with the new forecast, when enabled
+    fn auto_export_calendar(&self) -> Command<Message> {
+        let (true, Some(weather)) = (self.config.calendar.auto_export, &self.weather_data) else {
+            return Command::none();
+        };
+        let ics = calendar::export(&self.current_city, weather, &self.config.calendar.rules);
+        Command::perform(
+            export::write_data_export(ics, self.config.calendar.auto_export_path()),
+            Message::CalendarAutoExported,
+        )
+    }
+
+    fn precip_forecasts(&self) -> (Vec<ForecastPrecip>, Vec<ForecastPrecip>) {
         if let Some(weather) = &self.weather_data {
-            self.combined_timeline.clear();
+            (
+                weather.forecasts_precip_1hr.clone(),
+                weather.forecasts_precip_6hr.clone(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        }
+    }
 
-            // Add observations in reverse order (oldest to newest)
-            for obs in weather.observations_instant.iter().rev() {
-                self.combined_timeline
-                    .push(WeatherDataPoint::Observation(obs.clone()));
+    // Carry out a key binding, mostly by reusing the matching button's message
+    fn perform_action(&mut self, action: Action) -> Command<Message> {
+        let last_index = self.combined_timeline.len().saturating_sub(1);
+        match action {
+            Action::PreviousHour => self.update(Message::PreviousHour),
+            Action::NextHour => self.update(Message::NextHour),
+            Action::PreviousDay => {
+                self.jump_hours(-24);
+                Command::none()
             }
-
-            // Add forecasts (already in chronological order)
-            for fc in &weather.forecasts_instant {
-                self.combined_timeline
-                    .push(WeatherDataPoint::Forecast(fc.clone()));
+            Action::NextDay => {
+                self.jump_hours(24);
+                Command::none()
+            }
+            Action::Start => {
+                self.timeline_index = 0;
+                Command::none()
             }
+            Action::End => {
+                self.timeline_index = last_index;
+                Command::none()
+            }
+            Action::Now => self.update(Message::GoToNow),
+            Action::NextElement => {
+                self.cycle_weather_element(true);
+                Command::none()
+            }
+            Action::PreviousElement => {
+                self.cycle_weather_element(false);
+                Command::none()
+            }
+            Action::FocusSearch => text_input::focus(self.search_input_id.clone()),
+            Action::ToggleHelp => self.update(Message::ToggleHelp),
+            Action::CloseHelp => {
+                self.show_help = false;
+                Command::none()
+            }
+            Action::SelectLocation(i) => match self.config.locations.get(i) {
+                Some(location) => self.update(Message::FetchWeather(
+                    location.name.clone(),
+                    location.lat,
+                    location.lon,
+                )),
+                None => Command::none(),
+            },
+            Action::Unbound => Command::none(),
+        }
+    }
 
-            // Find the index closest to current time
-            let now = Utc::now();
-            let mut closest_index = 0;
-            let mut smallest_diff = i64::MAX;
+    // Move the selection to the point closest to `hours` from the selected one
+    fn jump_hours(&mut self, hours: i64) {
+        let Some(current) = self
+            .combined_timeline
+            .get(self.timeline_index)
+            .and_then(|p| DateTime::parse_from_rfc3339(p.valid_date()).ok())
+        else {
+            return;
+        };
+        let target = current.timestamp() + hours * 3600;
 
-            for (i, point) in self.combined_timeline.iter().enumerate() {
-                if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                    let diff = (dt.timestamp() - now.timestamp()).abs();
-                    if diff < smallest_diff {
-                        smallest_diff = diff;
-                        closest_index = i;
-                    }
-                }
+        let closest = self
+            .combined_timeline
+            .iter()
+            .enumerate()
+            .filter_map(|(i, p)| {
+                let dt = DateTime::parse_from_rfc3339(p.valid_date()).ok()?;
+                Some((i, (dt.timestamp() - target).abs()))
+            })
+            .min_by_key(|(_, diff)| *diff);
+        if let Some((i, _)) = closest {
+            self.timeline_index = i;
+        }
+    }
+
+    // Move the last overlay selected on to the next (or previous) element not already shown,
+    // leaving the others in place; with none selected, start one
+    fn cycle_weather_element(&mut self, forward: bool) {
+        let overlays: Vec<&WeatherElement> = WeatherElement::ALL
+            .iter()
+            .filter(|e| e.is_overlay())
+            .collect();
+        let count = overlays.len();
+        let mut pos = self
+            .selected_weather_elements
+            .last()
+            .and_then(|current| overlays.iter().position(|e| *e == current));
+        let kept = match pos {
+            Some(_) => self.selected_weather_elements.len() - 1,
+            None => self.selected_weather_elements.len(),
+        };
+
+        for _ in 0..count {
+            let next = match pos {
+                Some(p) if forward => (p + 1) % count,
+                Some(p) => (p + count - 1) % count,
+                None if forward => 0,
+                None => count - 1,
+            };
+            pos = Some(next);
+            if !self.selected_weather_elements[..kept].contains(overlays[next]) {
+                self.selected_weather_elements.truncate(kept);
+                self.selected_weather_elements.push(overlays[next].clone());
+                return;
             }
+        }
+    }
+
+    pub fn active_theme(&self) -> Theme {
+        self.config.theme.theme(self.system_dark)
+    }
+
+    pub fn save_config(&mut self) {
+        if let Err(e) = self.config.save() {
+            eprintln!("Failed to save config: {}", e);
+        }
+    }
+
+    pub fn build_timeline(&mut self) {
+        if let Some(weather) = &self.weather_data {
+            self.combined_timeline = timeline::build_timeline(weather);
+            self.timeline_index = timeline::closest_index(&self.combined_timeline, Utc::now());
+            self.qc_flags = if self.config.qc.enabled {
+                qc::check_observations(weather, &self.config.qc.rules)
+            } else {
+                Vec::new()
+            };
 
-            self.timeline_index = closest_index;
+            let (precip_1hr, precip_6hr) = self.precip_forecasts();
+            let lines = summary::forecast_summary(
+                &self.combined_timeline,
+                &precip_1hr,
+                &precip_6hr,
+                Utc::now(),
+                &Local,
+            );
+            self.summary = text_editor::Content::with_text(&lines.join("\n"));
         }
     }
-    pub fn create_temp_scale(&self, timeline: &[WeatherDataPoint]) -> Element<Message> {
+    pub fn create_temp_scale(&self, timeline: &[WeatherDataPoint]) -> Element<'_, Message> {
         if timeline.is_empty() {
             return container(text(""))
                 .width(Length::Fixed(40.0))
@@ -170,11 +573,7 @@ impl WeatherApp {
                 label_height - 16.0
             };
 
-            if i > 0 {
-                temp_labels = temp_labels.push(Space::with_height(Length::Fixed(spacing_height)));
-            } else {
-                temp_labels = temp_labels.push(Space::with_height(Length::Fixed(spacing_height)));
-            }
+            temp_labels = temp_labels.push(Space::with_height(Length::Fixed(spacing_height)));
 
             temp_labels = temp_labels.push(
                 text(format!("{:.0}°", temp))
@@ -190,47 +589,81 @@ impl WeatherApp {
             .into()
     }
 
-    pub fn create_element_scale(
+    pub fn create_panel_scale(
         &self,
         timeline: &[WeatherDataPoint],
-        element: &WeatherElement,
-    ) -> Element<Message> {
-        if timeline.is_empty()
-            || *element == WeatherElement::Temperature
-            || *element == WeatherElement::WBGT
-        {
-            return container(text(""))
-                .width(Length::Fixed(50.0))
-                .height(Length::Fixed(230.0))
-                .into();
+        panel: MeteogramPanel,
+    ) -> Element<'_, Message> {
+        let (min_value, max_value) = PanelCanvas::value_range(panel, timeline);
+        let unit = match panel {
+            MeteogramPanel::Precipitation | MeteogramPanel::CloudCover => "%",
+            _ => "",
+        };
+
+        // Top, middle and bottom labels spread over the panel's plot area
+        let mut labels = column![].spacing(0).width(Length::Fixed(40.0));
+        let label_count = 2;
+        let available_height = PANEL_HEIGHT - 20.0;
+
+        for i in 0..=label_count {
+            let value = max_value - (i as f64 / label_count as f64) * (max_value - min_value);
+            let spacing_height = if i == 0 {
+                4.0
+            } else {
+                available_height / label_count as f32 - 16.0
+            };
+            labels = labels.push(Space::with_height(Length::Fixed(spacing_height)));
+            labels = labels.push(
+                text(format!("{:.0}{}", value, unit))
+                    .size(12)
+                    .horizontal_alignment(iced::alignment::Horizontal::Right),
+            );
         }
 
-        // Get the range for the selected weather element
-        let element_values: Vec<Option<f64>> = timeline
-            .iter()
-            .map(|point| self.get_element_value_for_scale(point, element))
-            .collect();
+        container(labels)
+            .width(Length::Fixed(40.0))
+            .height(Length::Fixed(PANEL_HEIGHT))
+            .padding([0, 5, 0, 0])
+            .into()
+    }
 
-        let mut min_element = f64::MAX;
-        let mut max_element = f64::MIN;
-        for value in &element_values {
-            if let Some(v) = value {
-                min_element = min_element.min(*v);
-                max_element = max_element.max(*v);
+    pub fn is_element_selected(&self, element: &WeatherElement) -> bool {
+        self.selected_weather_elements.contains(element)
+    }
+
+    pub fn create_element_scales(&self, timeline: &[WeatherDataPoint]) -> Element<'_, Message> {
+        // One stacked secondary axis per selected element that doesn't share the temperature axis
+        let mut scales = row![].spacing(0);
+        let mut scale_count = 0;
+
+        if !timeline.is_empty() {
+            for element in &self.selected_weather_elements {
+                if element.shares_temp_axis() {
+                    continue;
+                }
+                scales = scales.push(self.create_element_scale(timeline, element));
+                scale_count += 1;
             }
         }
 
-        // Add padding to the element range
-        let element_range = max_element - min_element;
-        if element_range > 0.0 {
-            min_element -= element_range * 0.1;
-            max_element += element_range * 0.1;
-        } else {
-            // Handle case where all values are the same
-            min_element -= 1.0;
-            max_element += 1.0;
+        if scale_count == 0 {
+            return container(text(""))
+                .width(Length::Fixed(50.0))
+                .height(Length::Fixed(230.0))
+                .into();
         }
 
+        scales.into()
+    }
+
+    fn create_element_scale(
+        &self,
+        timeline: &[WeatherDataPoint],
+        element: &WeatherElement,
+    ) -> Element<'_, Message> {
+        let (min_element, max_element) = self.get_element_range(timeline, element);
+        let color = ChartColors::from_theme(&self.active_theme()).series(element.color());
+
         // Create element labels
         let mut element_labels = column![]
             .spacing(0)
@@ -250,34 +683,12 @@ impl WeatherApp {
                 label_height - 16.0
             };
 
-            if i > 0 {
-                element_labels =
-                    element_labels.push(Space::with_height(Length::Fixed(spacing_height)));
-            } else {
-                element_labels =
-                    element_labels.push(Space::with_height(Length::Fixed(spacing_height)));
-            }
-
-            // Format the value based on element type
-            let label_text = match element {
-                WeatherElement::WindSpeed => format!("{:.0}", value),
-                WeatherElement::Pressure => format!("{:.0}", value),
-                WeatherElement::CloudCover => format!("{:.0}%", value),
-                WeatherElement::Visibility => format!("{:.1}", value),
-                WeatherElement::SolarFlux => format!("{:.0}", value),
-                WeatherElement::ThunderstormProbability => format!("{:.0}%", value),
-                WeatherElement::Humidity => format!("{:.0}%", value),
-                WeatherElement::Dewpoint => format!("{:.0}°", value),
-                WeatherElement::CAPE => format!("{:.0}", value),
-                WeatherElement::ApparentTemperature => format!("{:.0}°", value),
-                WeatherElement::PrecipitationProbability => format!("{:.0}%", value),
-                _ => format!("{:.1}", value),
-            };
+            element_labels = element_labels.push(Space::with_height(Length::Fixed(spacing_height)));
 
             element_labels = element_labels.push(
-                text(label_text)
+                text(format_scale_value(element, value))
                     .size(12)
-                    .style(Color::from_rgb(0.5, 0.5, 0.5)) // Grey to match the line
+                    .style(color) // Match the overlay line
                     .horizontal_alignment(iced::alignment::Horizontal::Left),
             );
         }
@@ -289,29 +700,75 @@ impl WeatherApp {
             .into()
     }
 
+    // Padded value range for an element's secondary axis
+    pub fn get_element_range(
+        &self,
+        timeline: &[WeatherDataPoint],
+        element: &WeatherElement,
+    ) -> (f64, f64) {
+        let mut min_element = f64::MAX;
+        let mut max_element = f64::MIN;
+        for point in timeline {
+            if self.excluded_from_scales(point, element) {
+                continue;
+            }
+            if let Some(v) = self.get_element_value_for_scale(point, element) {
+                min_element = min_element.min(v);
+                max_element = max_element.max(v);
+            }
+        }
+
+        if min_element == f64::MAX || max_element == f64::MIN {
+            return (min_element, max_element);
+        }
+
+        // Add padding to the element range
+        let element_range = max_element - min_element;
+        if element_range > 0.0 {
+            min_element -= element_range * 0.1;
+            max_element += element_range * 0.1;
+        } else {
+            // Handle case where all values are the same
+            min_element -= 1.0;
+            max_element += 1.0;
+        }
+
+        (min_element, max_element)
+    }
+
     pub fn get_unified_temp_range(&self, timeline: &[WeatherDataPoint]) -> (f64, f64) {
         let mut min_temp = f64::MAX;
         let mut max_temp = f64::MIN;
 
-        for point in timeline {
-            // Regular temperature
-            if let Some(temp) = point.temperature() {
-                min_temp = min_temp.min(temp);
-                max_temp = max_temp.max(temp);
-            }
+        // Dewpoint shares the axis only while it is overlaid
+        let mut elements = vec![
+            WeatherElement::Temperature,
+            WeatherElement::WBGT,
+            WeatherElement::ApparentTemperature,
+        ];
+        if self.is_element_selected(&WeatherElement::Dewpoint) {
+            elements.push(WeatherElement::Dewpoint);
+        }
 
-            // WBGT temperature
-            if let Some(wbgt) = self.get_element_value_for_scale(point, &WeatherElement::WBGT) {
-                min_temp = min_temp.min(wbgt);
-                max_temp = max_temp.max(wbgt);
-            }
+        // Keep the earlier runs on the scale too
+        for (_, value) in self.previous_run_series().iter().flatten() {
+            min_temp = min_temp.min(*value);
+            max_temp = max_temp.max(*value);
+        }
 
-            // Apparent temperature
-            if let Some(apparent) =
-                self.get_element_value_for_scale(point, &WeatherElement::ApparentTemperature)
-            {
-                min_temp = min_temp.min(apparent);
-                max_temp = max_temp.max(apparent);
+        for point in timeline {
+            for element in &elements {
+                if self.excluded_from_scales(point, element) {
+                    continue;
+                }
+                let value = match element {
+                    WeatherElement::Temperature => point.temperature(),
+                    _ => self.get_element_value_for_scale(point, element),
+                };
+                if let Some(value) = value {
+                    min_temp = min_temp.min(value);
+                    max_temp = max_temp.max(value);
+                }
             }
         }
 
@@ -322,112 +779,31 @@ impl WeatherApp {
         (min_temp, max_temp)
     }
 
+    // The quality check flag on a value, if it failed one
+    pub fn qc_flag(&self, point: &WeatherDataPoint, element: &WeatherElement) -> Option<&QcFlag> {
+        qc::flag_for(&self.qc_flags, point, element)
+    }
+
+    // Flagged values stay out of the axis ranges when `qc.exclude_from_scales` is set
+    fn excluded_from_scales(&self, point: &WeatherDataPoint, element: &WeatherElement) -> bool {
+        self.config.qc.exclude_from_scales && self.qc_flag(point, element).is_some()
+    }
+
     pub fn get_element_value_for_scale(
         &self,
         point: &WeatherDataPoint,
         element: &WeatherElement,
     ) -> Option<f64> {
-        match element {
-            WeatherElement::Temperature => point.temperature(),
-            WeatherElement::WBGT => match point {
-                WeatherDataPoint::Forecast(fc) => fc.wbg_temp_2m_f,
-                WeatherDataPoint::Observation(_) => None,
-            },
-            WeatherElement::WindSpeed => match point {
-                WeatherDataPoint::Observation(obs) => obs.wind_spd_10m_mph,
-                WeatherDataPoint::Forecast(fc) => fc.wind_spd_10m_mph,
-            },
-            WeatherElement::Pressure => match point {
-                WeatherDataPoint::Observation(obs) => obs.pressure_h_pa,
-                WeatherDataPoint::Forecast(_) => None,
-            },
-            WeatherElement::Humidity => match point {
-                WeatherDataPoint::Observation(obs) => {
-                    if let Some(humidity) = obs.specific_humidity_2m_dg_kg {
-                        Some(humidity / 10.0)
-                    } else if let (Some(dewpoint), Some(temp)) =
-                        (obs.dewpoint_2m_f, obs.temperature_2m_f)
-                    {
-                        Some(weather::dewpoint_to_relative_humidity(dewpoint, temp))
-                    } else {
-                        None
-                    }
-                }
-                WeatherDataPoint::Forecast(fc) => {
-                    if let (Some(dewpoint), Some(temp)) = (fc.dewpoint_2m_f, fc.temperature_2m_f) {
-                        Some(weather::dewpoint_to_relative_humidity(dewpoint, temp))
-                    } else {
-                        None
-                    }
-                }
-            },
-            WeatherElement::Dewpoint => match point {
-                WeatherDataPoint::Observation(obs) => {
-                    if let Some(dewpoint) = obs.dewpoint_2m_f {
-                        Some(dewpoint)
-                    } else if let (Some(humidity), Some(temp)) =
-                        (obs.specific_humidity_2m_dg_kg, obs.temperature_2m_f)
-                    {
-                        Some(weather::relative_humidity_to_dewpoint(
-                            humidity / 10.0,
-                            temp,
-                        ))
-                    } else {
-                        None
-                    }
-                }
-                WeatherDataPoint::Forecast(fc) => fc.dewpoint_2m_f,
-            },
-            WeatherElement::CloudCover => match point {
-                WeatherDataPoint::Observation(obs) => obs.cloud_cover_pct,
-                WeatherDataPoint::Forecast(fc) => fc.cloud_cover_pct,
-            },
-            WeatherElement::Visibility => match point {
-                WeatherDataPoint::Observation(obs) => obs.visibility_m.map(|v| v / 1609.34),
-                WeatherDataPoint::Forecast(fc) => fc.visibility_m.map(|v| v / 1609.34),
-            },
-            WeatherElement::SolarFlux => match point {
-                WeatherDataPoint::Observation(obs) => obs.solar_flux_w_m2,
-                WeatherDataPoint::Forecast(fc) => fc.solar_flux_w_m2,
-            },
-            WeatherElement::ThunderstormProbability => match point {
-                WeatherDataPoint::Observation(_) => None,
-                WeatherDataPoint::Forecast(fc) => fc.prob_thunderstorm_pct,
-            },
-            WeatherElement::CAPE => match point {
-                WeatherDataPoint::Observation(_) => None,
-                WeatherDataPoint::Forecast(fc) => fc.cape_surface_j_kg,
-            },
-            WeatherElement::ApparentTemperature => {
-                let temp = point.temperature()?;
-                let (humidity, wind) = match point {
-                    WeatherDataPoint::Observation(obs) => {
-                        let humidity = if let Some(h) = obs.specific_humidity_2m_dg_kg {
-                            h / 10.0
-                        } else if let Some(d) = obs.dewpoint_2m_f {
-                            weather::dewpoint_to_relative_humidity(d, temp)
-                        } else {
-                            50.0 // Default if no humidity data
-                        };
-                        (humidity, obs.wind_spd_10m_mph.unwrap_or(0.0))
-                    }
-                    WeatherDataPoint::Forecast(fc) => {
-                        let humidity = if let Some(d) = fc.dewpoint_2m_f {
-                            weather::dewpoint_to_relative_humidity(d, temp)
-                        } else {
-                            50.0 // Default if no humidity data
-                        };
-                        (humidity, fc.wind_spd_10m_mph.unwrap_or(0.0))
-                    }
-                };
-                Some(weather::calculate_apparent_temperature(
-                    temp, humidity, wind,
-                ))
-            }
-            WeatherElement::PrecipitationProbability => {
-                self.get_precipitation_probability(point.valid_date())
-            }
-        }
+        let weather = self.weather_data.as_ref()?;
+        timeline::element_value(weather, point, element)
+    }
+}
+
+// Store a fetch of `location`, logging failures, as a failed write shouldn't stop the display
+pub fn archive_fetch(archive: &mut Archive, location: &str, weather: &ApiWeatherData) {
+    let now = Utc::now().timestamp();
+    if let Err(e) = archive.store(location, weather::PROVIDER, weather, now) {
+        eprintln!("Failed to archive {}: {}", location, e);
     }
 }
 
@@ -440,12 +816,18 @@ impl Application for WeatherApp {
     type Flags = ();
 
     fn new(_flags: ()) -> (WeatherApp, Command<Message>) {
-        let mut app = WeatherApp::default();
-        app.current_city = "Denver".to_string();
-        let command = Command::perform(
-            weather::fetch_weather(39.7392, -104.9903),
-            Message::WeatherFetched,
-        );
+        let mut app = WeatherApp {
+            current_city: "Denver".to_string(),
+            coordinates: Some((39.7392, -104.9903)),
+            config: Config::load(),
+            notified_alerts: NotifiedAlerts::load(),
+            ..WeatherApp::default()
+        };
+        app.archive = app.config.archive.open();
+        let command = Command::batch([
+            app.fetch_location("Denver", 39.7392, -104.9903),
+            Command::perform(theme::system_prefers_dark(), Message::SystemThemeChanged),
+        ]);
         (app, command)
     }
 
@@ -457,28 +839,38 @@ impl Application for WeatherApp {
         match message {
             Message::FetchWeather(city, lat, lon) => {
                 self.current_city = city;
+                self.coordinates = Some((lat, lon));
                 self.loading = true;
                 self.error = None;
+                self.comparison = None;
+                self.history = None;
 
-                Command::perform(weather::fetch_weather(lat, lon), Message::WeatherFetched)
+                self.fetch_location(&self.current_city, lat, lon)
             }
-            Message::WeatherFetched(result) => {
+            Message::WeatherFetched(location, result) => {
+                // Left over from a location switched away from, which would otherwise be shown,
+                // alerted on and archived under the current one
+                if !self.is_current(&location) {
+                    return Command::none();
+                }
                 self.loading = false;
-                match result {
+                let alerts = match result {
                     Ok(data) => {
-                        self.weather_data = Some(data);
-                        self.error = None;
-                        self.last_updated = Some(Local::now());
-                        self.build_timeline();
                         self.should_scroll_to_now = true;
+                        self.show_fetch(data)
                     }
                     Err(error) => {
                         self.error = Some(error);
                         self.weather_data = None;
                         self.combined_timeline.clear();
                         self.last_updated = None;
+                        self.triggered_alerts.clear();
+                        self.qc_flags.clear();
+                        self.previous_runs.clear();
+                        self.run_changes.clear();
+                        Command::none()
                     }
-                }
+                };
 
                 // Return scroll command if we need to scroll to now
                 if self.should_scroll_to_now && !self.combined_timeline.is_empty() {
@@ -491,14 +883,14 @@ impl Application for WeatherApp {
                     ) {
                         dt.timestamp()
                     } else {
-                        return Command::none();
+                        return alerts;
                     };
                     let end_time = if let Ok(dt) = DateTime::parse_from_rfc3339(
                         self.combined_timeline.last().unwrap().valid_date(),
                     ) {
                         dt.timestamp()
                     } else {
-                        return Command::none();
+                        return alerts;
                     };
                     let time_range = end_time - start_time;
 
@@ -508,15 +900,18 @@ impl Application for WeatherApp {
                     let viewport_width = 800.0;
                     let scroll_offset = (now_position - viewport_width / 2.0).max(0.0);
 
-                    scrollable::scroll_to(
-                        self.meteogram_scroll_id.clone(),
-                        scrollable::AbsoluteOffset {
-                            x: scroll_offset,
-                            y: 0.0,
-                        },
-                    )
+                    Command::batch([
+                        alerts,
+                        scrollable::scroll_to(
+                            self.meteogram_scroll_id.clone(),
+                            scrollable::AbsoluteOffset {
+                                x: scroll_offset,
+                                y: 0.0,
+                            },
+                        ),
+                    ])
                 } else {
-                    Command::none()
+                    alerts
                 }
             }
             Message::LatInputChanged(value) => {
@@ -530,14 +925,12 @@ impl Application for WeatherApp {
             Message::FetchCustomLocation => {
                 match (self.lat_input.parse::<f64>(), self.lon_input.parse::<f64>()) {
                     (Ok(lat), Ok(lon)) => {
-                        if lat >= -90.0 && lat <= 90.0 && lon >= -180.0 && lon <= 180.0 {
+                        if (-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon) {
                             self.current_city = format!("{:.2}, {:.2}", lat, lon);
+                            self.coordinates = Some((lat, lon));
                             self.loading = true;
                             self.error = None;
-                            Command::perform(
-                                weather::fetch_weather(lat, lon),
-                                Message::WeatherFetched,
-                            )
+                            self.fetch_location(&self.current_city, lat, lon)
                         } else {
                             self.error = Some("Invalid coordinates: Latitude must be between -90 and 90, Longitude between -180 and 180".to_string());
                             Command::none()
@@ -551,6 +944,47 @@ impl Application for WeatherApp {
                     }
                 }
             }
+            // Skipped while a fetch is already under way
+            Message::Refresh => match self.coordinates {
+                Some((lat, lon)) if !self.loading => {
+                    self.loading = true;
+                    let location = (self.current_city.clone(), lat, lon);
+                    Command::perform(self.fetch(lat, lon), move |result| {
+                        Message::Refreshed(location, result)
+                    })
+                }
+                _ => Command::none(),
+            },
+            // Unlike a fetch the user asked for, a refresh stays on the selected hour, and a
+            // failed one leaves the last data up with the error marking it stale
+            Message::Refreshed(location, result) => {
+                if !self.is_current(&location) {
+                    return Command::none();
+                }
+                self.loading = false;
+                match result {
+                    Ok(data) => {
+                        let selected =
+                            self.combined_timeline
+                                .get(self.timeline_index)
+                                .and_then(|point| {
+                                    DateTime::parse_from_rfc3339(point.valid_date()).ok()
+                                });
+                        let command = self.show_fetch(data);
+                        if let Some(selected) = selected {
+                            self.timeline_index = timeline::closest_index(
+                                &self.combined_timeline,
+                                selected.with_timezone(&Utc),
+                            );
+                        }
+                        command
+                    }
+                    Err(error) => {
+                        self.error = Some(error);
+                        Command::none()
+                    }
+                }
+            }
             Message::PreviousHour => {
                 if self.timeline_index > 0 {
                     self.timeline_index -= 1;
@@ -623,17 +1057,985 @@ impl Application for WeatherApp {
                 Command::none()
             }
             Message::SelectWeatherElement(element) => {
-                self.selected_weather_element = element;
+                // Toggle the element in or out of the overlay set
+                if !element.is_overlay() {
+                    return Command::none();
+                }
+                if let Some(pos) = self
+                    .selected_weather_elements
+                    .iter()
+                    .position(|e| *e == element)
+                {
+                    self.selected_weather_elements.remove(pos);
+                } else {
+                    self.selected_weather_elements.push(element);
+                }
+                Command::none()
+            }
+            Message::TogglePanel(panel) => {
+                self.config.toggle_panel(panel);
+                self.save_config();
+                Command::none()
+            }
+            Message::MovePanelUp(panel) => {
+                self.config.move_panel_up(panel);
+                self.save_config();
+                Command::none()
+            }
+            Message::SelectPalette(palette) => {
+                self.config.color_ramp.palette = palette;
+                self.save_config();
+                Command::none()
+            }
+            Message::SelectThemeMode(mode) => {
+                self.config.theme = mode;
+                self.save_config();
+                if mode == ThemeMode::Auto {
+                    Command::perform(theme::system_prefers_dark(), Message::SystemThemeChanged)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::CheckSystemTheme => {
+                Command::perform(theme::system_prefers_dark(), Message::SystemThemeChanged)
+            }
+            Message::SystemThemeChanged(dark) => {
+                self.system_dark = dark;
+                Command::none()
+            }
+            Message::KeyPressed(key) => {
+                match keys::effective_bindings(&self.config.key_bindings).get(&key) {
+                    Some(action) => self.perform_action(*action),
+                    None => Command::none(),
+                }
+            }
+            Message::ToggleHelp => {
+                self.show_help = !self.show_help;
+                Command::none()
+            }
+            Message::SummaryAction(action) => {
+                // Allow selecting and copying, but not editing
+                if !action.is_edit() {
+                    self.summary.perform(action);
+                }
+                Command::none()
+            }
+            Message::AlertNotified(location, alert, result) => {
+                match result {
+                    Ok(()) => {
+                        self.notified_alerts.record(&location, &alert);
+                        if let Err(e) = self.notified_alerts.save() {
+                            eprintln!("Failed to save notified alerts: {}", e);
+                        }
+                    }
+                    Err(e) => eprintln!("Failed to send notification: {}", e),
+                }
+                Command::none()
+            }
+            Message::ToggleComparison => {
+                if self.comparison.take().is_some() {
+                    return Command::none();
+                }
+                self.history = None;
+                let element = self
+                    .selected_weather_elements
+                    .first()
+                    .cloned()
+                    .unwrap_or(WeatherElement::Temperature);
+                self.comparison = Some(Comparison::new(&self.config.locations, element));
+
+                // All sites are fetched at once
+                Command::batch(
+                    self.config
+                        .locations
+                        .iter()
+                        .enumerate()
+                        .map(|(i, location)| {
+                            Command::perform(
+                                self.fetch(location.lat, location.lon),
+                                move |result| Message::ComparisonFetched(i, result),
+                            )
+                        }),
+                )
+            }
+            Message::ComparisonFetched(index, result) => {
+                if let (Some(comparison), Some(archive), Ok(weather)) =
+                    (&self.comparison, &mut self.archive, &result)
+                {
+                    if let Some(entry) = comparison.sites.get(index) {
+                        archive_fetch(archive, &entry.location.name, weather);
+                    }
+                }
+                if let Some(comparison) = &mut self.comparison {
+                    comparison.set_result(index, result);
+                }
+                Command::none()
+            }
+            Message::SelectComparisonElement(element) => {
+                if let Some(comparison) = &mut self.comparison {
+                    comparison.element = element;
+                }
+                Command::none()
+            }
+            Message::CopySummary => iced::clipboard::write(self.summary.text()),
+            Message::ToggleTable => {
+                self.show_table = !self.show_table;
+                Command::none()
+            }
+            Message::ToggleAccuracy => {
+                self.show_accuracy = !self.show_accuracy;
+                Command::none()
+            }
+            Message::TogglePreviousRuns => {
+                self.show_previous_runs = !self.show_previous_runs;
+                Command::none()
+            }
+            Message::ToggleHistory => {
+                if self.history.take().is_none() {
+                    self.comparison = None;
+                    self.history =
+                        Some(History::new(&self.current_city, Local::now().date_naive()));
+                    self.load_history();
+                }
+                Command::none()
+            }
+            Message::HistoryStartChanged(value) => {
+                if let Some(history) = &mut self.history {
+                    history.start_input = value;
+                }
+                Command::none()
+            }
+            Message::HistoryEndChanged(value) => {
+                if let Some(history) = &mut self.history {
+                    history.end_input = value;
+                }
+                Command::none()
+            }
+            Message::HistoryLastDays(days) => {
+                if let Some(history) = &mut self.history {
+                    history.set_last_days(days, Local::now().date_naive());
+                }
+                self.load_history();
+                Command::none()
+            }
+            Message::HistoryShift(steps) => {
+                if let Some(history) = &mut self.history {
+                    history.shift(steps);
+                }
+                self.load_history();
+                Command::none()
+            }
+            Message::LoadHistory => {
+                self.load_history();
+                Command::none()
+            }
+            Message::HistoryPointClicked(index) => {
+                if let Some(site) = self.history.as_mut().and_then(|h| h.site.as_mut()) {
+                    if index < site.combined_timeline.len() {
+                        site.timeline_index = index;
+                    }
+                }
+                Command::none()
+            }
+            Message::ToggleTableColumn(column) => {
+                self.config.toggle_table_column(column);
+                self.save_config();
+                Command::none()
+            }
+            Message::SortTable(column) => {
+                // Ascending, then descending, then back to time order
+                self.table_sort = match self.table_sort {
+                    Some((c, true)) if c == column => Some((column, false)),
+                    Some((c, false)) if c == column => None,
+                    _ => Some((column, true)),
+                };
+                Command::none()
+            }
+            Message::CopyTableCsv => {
+                let rows = table::rows(self, self.table_sort);
+                iced::clipboard::write(table::to_csv(&rows, &self.config.table_columns))
+            }
+            Message::ExportMeteogram(format) => {
+                let svg = export::meteogram_svg(self, export::DEFAULT_EXPORT_WIDTH);
+                let path = export::default_export_path(self, format.extension());
+                self.export_status = Some("Exporting...".to_string());
+                Command::perform(export::write_export(svg, path, format), Message::Exported)
+            }
+            Message::ExportData(format) => {
+                let Some(weather) = &self.weather_data else {
+                    return Command::none();
+                };
+                let contents = data_export::export(&self.current_city, weather, format);
+                let path = export::default_export_path(self, format.extension());
+                self.export_status = Some("Exporting...".to_string());
+                Command::perform(export::write_data_export(contents, path), Message::Exported)
+            }
+            Message::ExportCalendar => {
+                let Some(weather) = &self.weather_data else {
+                    return Command::none();
+                };
+                let ics =
+                    calendar::export(&self.current_city, weather, &self.config.calendar.rules);
+                let path = export::default_export_path(self, "ics");
+                self.export_status = Some("Exporting...".to_string());
+                Command::perform(export::write_data_export(ics, path), Message::Exported)
+            }
+            Message::CalendarAutoExported(result) => {
+                if let Err(e) = result {
+                    eprintln!("Failed to update calendar: {}", e);
+                }
+                Command::none()
+            }
+            Message::Exported(result) => {
+                self.export_status = Some(match result {
+                    Ok(path) => format!("Exported to {}", path.display()),
+                    Err(e) => format!("Export failed: {}", e),
+                });
                 Command::none()
             }
         }
     }
 
     fn theme(&self) -> Theme {
-        Theme::Light
+        self.active_theme()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        // Bindings are resolved in `update` so they can come from the config
+        let keys = keyboard::on_key_press(|key, modifiers| {
+            keys::key_name(key, modifiers).map(Message::KeyPressed)
+        });
+
+        let refresh = iced::time::every(self.config.refresh_interval()).map(|_| Message::Refresh);
+
+        // The portal can't be watched without a long-lived D-Bus stream, so poll it instead
+        if self.config.theme == ThemeMode::Auto {
+            Subscription::batch([
+                keys,
+                refresh,
+                iced::time::every(Duration::from_secs(10)).map(|_| Message::CheckSystemTheme),
+            ])
+        } else {
+            Subscription::batch([keys, refresh])
+        }
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         view::view(self)
     }
 }
+
+// Fetch → timeline → selection flows against the mock API
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{AlertRule, Condition};
+    use iced_runtime::command::Action;
+    use rusty_wx::mock::{MockResponse, MockServer};
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    async fn app_with(response: MockResponse) -> (WeatherApp, MockServer) {
+        let server = MockServer::start(response).await.expect("mock server");
+        let app = WeatherApp {
+            api_url: server.base_url(),
+            ..WeatherApp::default()
+        };
+        (app, server)
+    }
+
+    // Run the futures a command would have the runtime run, feeding their messages back into
+    // `update` until nothing is left; widget, clipboard and window actions are dropped
+    async fn settle(app: &mut WeatherApp, command: Command<Message>) {
+        let mut pending = command.actions();
+        while let Some(action) = pending.pop() {
+            if let Action::Future(future) = action {
+                let message = future.await;
+                pending.extend(app.update(message).actions());
+            }
+        }
+    }
+
+    async fn send(app: &mut WeatherApp, message: Message) {
+        let command = app.update(message);
+        settle(app, command).await;
+    }
+
+    #[tokio::test]
+    async fn fetch_builds_timeline() {
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+
+        let command = app.update(Message::FetchWeather("Denver".to_string(), 39.74, -104.99));
+        assert!(app.loading);
+        assert_eq!(app.current_city, "Denver");
+        settle(&mut app, command).await;
+
+        assert!(!app.loading);
+        assert!(app.error.is_none());
+        assert!(app.weather_data.is_some());
+        assert!(app.last_updated.is_some());
+        assert_eq!(app.combined_timeline.len(), 96);
+        assert_eq!(
+            app.timeline_index,
+            timeline::closest_index(&app.combined_timeline, Utc::now())
+        );
+        assert!(!app.summary.text().trim().is_empty());
+        assert_eq!(server.requests(), ["/weather?lat=39.74&lon=-104.99"]);
+    }
+
+    #[tokio::test]
+    async fn selection_stays_in_bounds() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        let last = app.combined_timeline.len() - 1;
+
+        send(&mut app, Message::MeteogramClicked(0)).await;
+        assert_eq!(app.timeline_index, 0);
+        send(&mut app, Message::PreviousHour).await;
+        assert_eq!(app.timeline_index, 0);
+        send(&mut app, Message::NextHour).await;
+        assert_eq!(app.timeline_index, 1);
+
+        send(&mut app, Message::MeteogramClicked(last)).await;
+        send(&mut app, Message::NextHour).await;
+        assert_eq!(app.timeline_index, last);
+        // Clicks past the end are ignored
+        send(&mut app, Message::MeteogramClicked(last + 5)).await;
+        assert_eq!(app.timeline_index, last);
+
+        send(&mut app, Message::GoToNow).await;
+        assert_eq!(
+            app.timeline_index,
+            timeline::closest_index(&app.combined_timeline, Utc::now())
+        );
+    }
+
+    #[tokio::test]
+    async fn overlay_selection_toggles() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::PrecipitationProbability]
+        );
+
+        send(
+            &mut app,
+            Message::SelectWeatherElement(WeatherElement::WBGT),
+        )
+        .await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [
+                WeatherElement::PrecipitationProbability,
+                WeatherElement::WBGT
+            ]
+        );
+
+        send(
+            &mut app,
+            Message::SelectWeatherElement(WeatherElement::PrecipitationProbability),
+        )
+        .await;
+        assert_eq!(app.selected_weather_elements, [WeatherElement::WBGT]);
+
+        // Temperature is the meteogram's own line
+        send(
+            &mut app,
+            Message::SelectWeatherElement(WeatherElement::Temperature),
+        )
+        .await;
+        assert_eq!(app.selected_weather_elements, [WeatherElement::WBGT]);
+    }
+
+    // Stands in for the desktop's notification daemon
+    struct Notifications {
+        sent: Arc<Mutex<Vec<(String, String)>>>,
+    }
+
+    #[zbus::interface(name = "org.freedesktop.Notifications")]
+    impl Notifications {
+        #[allow(clippy::too_many_arguments)]
+        fn notify(
+            &self,
+            _app_name: &str,
+            _replaces_id: u32,
+            _app_icon: &str,
+            summary: &str,
+            body: &str,
+            _actions: Vec<&str>,
+            _hints: HashMap<&str, zbus::zvariant::Value<'_>>,
+            _expire_timeout: i32,
+        ) -> u32 {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push((summary.to_string(), body.to_string()));
+            sent.len() as u32
+        }
+    }
+
+    #[tokio::test]
+    async fn notification_reaches_the_service() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let (client, server) = tokio::net::UnixStream::pair().unwrap();
+        let service = zbus::connection::Builder::unix_stream(server)
+            .server(zbus::Guid::generate())
+            .unwrap()
+            .p2p()
+            .serve_at(
+                "/org/freedesktop/Notifications",
+                Notifications { sent: sent.clone() },
+            )
+            .unwrap()
+            .build();
+        let connection = zbus::connection::Builder::unix_stream(client).p2p().build();
+        let (_service, connection) = tokio::try_join!(service, connection).unwrap();
+
+        alerts::notify_on(&connection, "Denver: Gusts above 40 mph", "at Sun 3 PM")
+            .await
+            .unwrap();
+        assert_eq!(
+            *sent.lock().unwrap(),
+            [(
+                "Denver: Gusts above 40 mph".to_string(),
+                "at Sun 3 PM".to_string()
+            )]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_notifications_are_retried() {
+        let mut app = WeatherApp::default();
+        let alert = TriggeredAlert {
+            rule: AlertRule {
+                element: WeatherElement::WindGust,
+                condition: Condition::Above,
+                threshold: 40.0,
+                within_hours: None,
+            },
+            start: 1_000_000,
+            end: 1_003_600,
+            peak: 45.0,
+        };
+        let alerts = [alert.clone()];
+
+        send(
+            &mut app,
+            Message::AlertNotified(
+                "Denver".to_string(),
+                alert.clone(),
+                Err("no session bus".to_string()),
+            ),
+        )
+        .await;
+        assert_eq!(
+            app.notified_alerts
+                .unseen("Denver", &alerts, 1_000_000)
+                .len(),
+            1
+        );
+
+        send(
+            &mut app,
+            Message::AlertNotified("Denver".to_string(), alert, Ok(())),
+        )
+        .await;
+        assert!(app
+            .notified_alerts
+            .unseen("Denver", &alerts, 1_000_000)
+            .is_empty());
+        assert_eq!(
+            app.notified_alerts
+                .unseen("Boulder", &alerts, 1_000_000)
+                .len(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn cycling_moves_the_last_overlay() {
+        let mut app = WeatherApp {
+            selected_weather_elements: vec![WeatherElement::Dewpoint, WeatherElement::WindSpeed],
+            ..WeatherApp::default()
+        };
+
+        send(&mut app, Message::KeyPressed("Tab".to_string())).await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::Dewpoint, WeatherElement::WindGust]
+        );
+        send(&mut app, Message::KeyPressed("Shift+Tab".to_string())).await;
+        send(&mut app, Message::KeyPressed("Shift+Tab".to_string())).await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::Dewpoint, WeatherElement::WBGT]
+        );
+
+        // Past the ends without landing on temperature, and skipping what is already shown
+        app.selected_weather_elements = vec![WeatherElement::ApparentTemperature];
+        send(&mut app, Message::KeyPressed("Shift+Tab".to_string())).await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::PrecipitationProbability]
+        );
+        app.selected_weather_elements = vec![
+            WeatherElement::ApparentTemperature,
+            WeatherElement::PrecipitationProbability,
+        ];
+        send(&mut app, Message::KeyPressed("Tab".to_string())).await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::ApparentTemperature, WeatherElement::WBGT]
+        );
+
+        app.selected_weather_elements.clear();
+        send(&mut app, Message::KeyPressed("Tab".to_string())).await;
+        assert_eq!(
+            app.selected_weather_elements,
+            [WeatherElement::ApparentTemperature]
+        );
+    }
+
+    #[tokio::test]
+    async fn fetch_errors_clear_the_timeline() {
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert!(!app.combined_timeline.is_empty());
+
+        server.set_response(MockResponse::status(500));
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert!(!app.loading);
+        assert!(app.error.as_deref().unwrap_or("").contains("500"));
+        assert!(app.weather_data.is_none());
+        assert!(app.combined_timeline.is_empty());
+        assert!(app.last_updated.is_none());
+
+        // And the next good response recovers
+        server.set_response(MockResponse::raw(FIXTURE));
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert!(app.error.is_none());
+        assert_eq!(app.combined_timeline.len(), 96);
+    }
+
+    #[tokio::test]
+    async fn malformed_responses_are_errors() {
+        for response in [
+            MockResponse::raw(FIXTURE).truncated(),
+            MockResponse::raw(FIXTURE).without_fields(&["observationsInstant"]),
+            MockResponse::status(404),
+        ] {
+            let (mut app, _server) = app_with(response).await;
+            send(
+                &mut app,
+                Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+            )
+            .await;
+            assert!(app.error.is_some());
+            assert!(app.combined_timeline.is_empty());
+        }
+    }
+
+    #[tokio::test]
+    async fn slow_fetch_keeps_loading() {
+        let (mut app, _server) =
+            app_with(MockResponse::raw(FIXTURE).with_delay(Duration::from_millis(300))).await;
+        let command = app.update(Message::FetchWeather("Denver".to_string(), 39.74, -104.99));
+
+        // Navigating while the fetch is in flight has nothing to move through
+        send(&mut app, Message::NextHour).await;
+        assert!(app.loading);
+        assert_eq!(app.timeline_index, 0);
+
+        settle(&mut app, command).await;
+        assert!(!app.loading);
+        assert_eq!(app.combined_timeline.len(), 96);
+    }
+
+    #[tokio::test]
+    async fn replies_for_a_location_left_are_dropped() {
+        let mut denver: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        denver["observationsInstant"][0]["temperature2mF"] = 99.0.into();
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+        server.push_response(
+            MockResponse::raw(denver.to_string()).with_delay(Duration::from_millis(300)),
+        );
+        app.archive = Some(Archive::open_in_memory().unwrap());
+
+        // Denver's reply is still on its way when Boulder is picked
+        let command = app.update(Message::FetchWeather("Denver".to_string(), 39.74, -104.99));
+        let denver_reply = tokio::spawn(
+            command
+                .actions()
+                .into_iter()
+                .find_map(|action| match action {
+                    Action::Future(future) => Some(future),
+                    _ => None,
+                })
+                .unwrap(),
+        );
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Boulder".to_string(), 40.01, -105.27),
+        )
+        .await;
+        send(&mut app, denver_reply.await.unwrap()).await;
+
+        assert_eq!(app.current_city, "Boulder");
+        assert!(!app.loading);
+        let weather = app.weather_data.as_ref().unwrap();
+        assert_ne!(weather.observations_instant[0].temperature_2m_f, Some(99.0));
+        let archive = app.archive.as_ref().unwrap();
+        assert!(archive
+            .cycles("Denver", weather::PROVIDER)
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            archive.cycles("Boulder", weather::PROVIDER).unwrap().len(),
+            1
+        );
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn custom_location() {
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+
+        send(&mut app, Message::LatInputChanged("91".to_string())).await;
+        send(&mut app, Message::LonInputChanged("-104.99".to_string())).await;
+        send(&mut app, Message::FetchCustomLocation).await;
+        assert!(app.error.is_some());
+        send(&mut app, Message::LatInputChanged("north".to_string())).await;
+        send(&mut app, Message::FetchCustomLocation).await;
+        assert!(app.error.is_some());
+        assert!(server.requests().is_empty());
+
+        send(&mut app, Message::LatInputChanged("39.74".to_string())).await;
+        send(&mut app, Message::FetchCustomLocation).await;
+        assert!(app.error.is_none());
+        assert_eq!(app.current_city, "39.74, -104.99");
+        assert_eq!(app.combined_timeline.len(), 96);
+        assert_eq!(server.requests(), ["/weather?lat=39.74&lon=-104.99"]);
+    }
+
+    #[tokio::test]
+    async fn refresh_refetches_the_current_location() {
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+        // Nothing fetched yet, so nothing to refresh
+        send(&mut app, Message::Refresh).await;
+        assert!(server.requests().is_empty());
+
+        send(&mut app, Message::LatInputChanged("39.74".to_string())).await;
+        send(&mut app, Message::LonInputChanged("-104.99".to_string())).await;
+        send(&mut app, Message::FetchCustomLocation).await;
+        send(&mut app, Message::Refresh).await;
+        assert_eq!(app.current_city, "39.74, -104.99");
+        assert!(!app.loading);
+        assert_eq!(
+            server.requests(),
+            [
+                "/weather?lat=39.74&lon=-104.99",
+                "/weather?lat=39.74&lon=-104.99"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn refresh_keeps_the_view() {
+        let (mut app, server) = app_with(MockResponse::raw(FIXTURE)).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        send(&mut app, Message::MeteogramClicked(10)).await;
+
+        // The picked hour stays picked, with no scroll back to now
+        let command = app.update(Message::Refresh);
+        let Some(Action::Future(reply)) = command.actions().pop() else {
+            panic!("refresh fetched nothing");
+        };
+        let command = app.update(reply.await);
+        assert!(!command
+            .actions()
+            .iter()
+            .any(|action| matches!(action, Action::Widget(_))));
+        assert_eq!(app.timeline_index, 10);
+        assert!(app.error.is_none());
+
+        // A failed refresh leaves the last data up, marked with the error
+        let updated = app.last_updated;
+        server.set_response(MockResponse::status(503));
+        send(&mut app, Message::Refresh).await;
+        assert!(!app.loading);
+        assert!(app.error.is_some());
+        assert!(app.weather_data.is_some());
+        assert_eq!(app.combined_timeline.len(), 96);
+        assert_eq!(app.timeline_index, 10);
+        assert_eq!(app.last_updated, updated);
+
+        server.set_response(MockResponse::raw(FIXTURE));
+        send(&mut app, Message::Refresh).await;
+        assert!(app.error.is_none());
+    }
+
+    #[tokio::test]
+    async fn refresh_rewrites_the_calendar() {
+        let path =
+            std::env::temp_dir().join(format!("rusty-wx-calendar-{}.ics", std::process::id()));
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        app.config.calendar.auto_export = true;
+        app.config.calendar.path = Some(path.clone());
+
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert!(std::fs::read_to_string(&path)
+            .unwrap()
+            .starts_with("BEGIN:VCALENDAR"));
+        std::fs::remove_file(&path).unwrap();
+
+        send(&mut app, Message::Refresh).await;
+        assert!(path.exists());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn status_bar_errors_are_escaped() {
+        let status = crate::statusbar::error_status(
+            "error sending request for url (http://x/weather?lat=1&lon=<2>)",
+        );
+        assert_eq!(
+            status["tooltip"],
+            "error sending request for url (http://x/weather?lat=1&amp;lon=&lt;2&gt;)"
+        );
+    }
+
+    #[tokio::test]
+    async fn flagged_values_can_leave_the_scale() {
+        let mut fixture: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        fixture["observationsInstant"][3]["temperature2mF"] = 150.0.into();
+        let (mut app, _server) = app_with(MockResponse::raw(fixture.to_string())).await;
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+
+        assert_eq!(app.qc_flags.len(), 1);
+        let flagged = &app.combined_timeline[20];
+        assert!(app.qc_flag(flagged, &WeatherElement::Temperature).is_some());
+        assert!(app.get_unified_temp_range(&app.combined_timeline).1 > 150.0);
+
+        app.config.qc.exclude_from_scales = true;
+        assert!(app.get_unified_temp_range(&app.combined_timeline).1 < 100.0);
+
+        app.config.qc.enabled = false;
+        app.build_timeline();
+        assert!(app.qc_flags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn comparison_fetches_are_archived() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        app.archive = Some(Archive::open_in_memory().unwrap());
+        send(&mut app, Message::ToggleComparison).await;
+
+        let archived: Vec<String> = app
+            .archive
+            .as_ref()
+            .unwrap()
+            .locations()
+            .unwrap()
+            .into_iter()
+            .map(|location| location.name)
+            .collect();
+        assert_eq!(archived, ["Denver", "Los Angeles", "Miami", "New York"]);
+    }
+
+    #[tokio::test]
+    async fn past_runs_come_from_the_archive() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        app.archive = Some(Archive::open_in_memory().unwrap());
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+        assert_eq!(app.scored_runs, 1);
+        assert!(app.previous_runs.is_empty());
+
+        // The next cycle, 5°F warmer
+        let mut next: serde_json::Value = serde_json::from_str(FIXTURE).unwrap();
+        for fc in next["forecastsInstant"].as_array_mut().unwrap() {
+            fc["cycleDate"] = "2026-10-18T17:00:00Z".into();
+            fc["temperature2mF"] = (fc["temperature2mF"].as_f64().unwrap() + 5.0).into();
+        }
+        let server = MockServer::start(MockResponse::raw(next.to_string()))
+            .await
+            .unwrap();
+        app.api_url = server.base_url();
+        send(&mut app, Message::Refresh).await;
+
+        assert_eq!(app.scored_runs, 2);
+        let cycles: Vec<&str> = app
+            .previous_runs
+            .iter()
+            .map(|run| run.cycle_date.as_str())
+            .collect();
+        assert_eq!(cycles, ["2026-10-18T11:00:00Z"]);
+        assert!(!app.run_changes.is_empty());
+
+        app.config.previous_runs = 0;
+        send(&mut app, Message::Refresh).await;
+        assert!(app.previous_runs.is_empty());
+        assert!(app.run_changes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn history_from_the_archive() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        app.archive = Some(Archive::open_in_memory().unwrap());
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+
+        send(&mut app, Message::ToggleHistory).await;
+        send(
+            &mut app,
+            Message::HistoryStartChanged("2026-10-16".to_string()),
+        )
+        .await;
+        send(
+            &mut app,
+            Message::HistoryEndChanged("2026-10-19".to_string()),
+        )
+        .await;
+        send(&mut app, Message::LoadHistory).await;
+        let history = app.history.as_ref().unwrap();
+        assert!(history.error.is_none());
+        let site = history.site.as_ref().unwrap();
+        // The archived observations only, whatever day the forecasts were for
+        assert_eq!(site.combined_timeline.len(), 24);
+        assert!(site
+            .weather_data
+            .as_ref()
+            .unwrap()
+            .forecasts_instant
+            .is_empty());
+        assert_eq!(history.statistics[0].element, WeatherElement::Temperature);
+        assert_eq!(history.statistics[0].count, 24);
+        assert!(!history.days.is_empty());
+
+        // Clicks move the history's selection, not the main timeline's
+        let index = app.timeline_index;
+        send(&mut app, Message::HistoryPointClicked(3)).await;
+        assert_eq!(app.timeline_index, index);
+        assert_eq!(
+            app.history
+                .as_ref()
+                .unwrap()
+                .site
+                .as_ref()
+                .unwrap()
+                .timeline_index,
+            3
+        );
+
+        // The week before has nothing archived, and a range backwards isn't one
+        send(&mut app, Message::HistoryShift(-1)).await;
+        let history = app.history.as_ref().unwrap();
+        assert_eq!(
+            (history.start_input.as_str(), history.end_input.as_str()),
+            ("2026-10-12", "2026-10-15")
+        );
+        assert!(history.site.is_none());
+        assert!(history.error.is_some());
+        send(
+            &mut app,
+            Message::HistoryEndChanged("2026-10-01".to_string()),
+        )
+        .await;
+        send(&mut app, Message::LoadHistory).await;
+        assert_eq!(
+            app.history.as_ref().unwrap().error.as_deref(),
+            Some("The range starts after it ends")
+        );
+
+        send(&mut app, Message::ToggleHistory).await;
+        assert!(app.history.is_none());
+    }
+
+    #[tokio::test]
+    async fn long_history_ranges_are_downsampled() {
+        let (mut app, _server) = app_with(MockResponse::raw(FIXTURE)).await;
+        app.archive = Some(Archive::open_in_memory().unwrap());
+        send(
+            &mut app,
+            Message::FetchWeather("Denver".to_string(), 39.74, -104.99),
+        )
+        .await;
+
+        send(&mut app, Message::ToggleHistory).await;
+        send(
+            &mut app,
+            Message::HistoryStartChanged("2026-10-16".to_string()),
+        )
+        .await;
+        send(
+            &mut app,
+            Message::HistoryEndChanged("2026-10-19".to_string()),
+        )
+        .await;
+        send(&mut app, Message::LoadHistory).await;
+        assert!(!app.history.as_ref().unwrap().downsampled);
+
+        // A month: each day's low and high on the chart, every hour in the statistics
+        send(
+            &mut app,
+            Message::HistoryStartChanged("2026-09-20".to_string()),
+        )
+        .await;
+        send(&mut app, Message::LoadHistory).await;
+        let history = app.history.as_ref().unwrap();
+        assert!(history.downsampled);
+        let site = history.site.as_ref().unwrap();
+        assert!(site.combined_timeline.len() <= 2 * history.days.len());
+        assert!(site.combined_timeline.len() < 24);
+        assert_eq!(history.statistics[0].count, 24);
+        let temperatures: Vec<f64> = site
+            .combined_timeline
+            .iter()
+            .filter_map(|point| point.temperature())
+            .collect();
+        assert!(history
+            .days
+            .iter()
+            .all(|day| temperatures.contains(&day.low) && temperatures.contains(&day.high)));
+    }
+}
@@ -0,0 +1,532 @@
+//! An SQLite archive of fetched observations, forecasts and normals per location and provider,
+//! the local history behind browsing, verification and offline use.
+//!
+//! Observations and normals are kept once per location and valid time, forecasts and their
+//! precipitation chances once per location, valid time and forecast cycle. Storing a refetch
+//! replaces the earlier copy. Points are kept as the feed's JSON, so every field survives.
+//!
+//! ```no_run
+//! use rusty_wx::archive::{Archive, Retention};
+//! use rusty_wx::weather;
+//!
+//! # async fn example() -> Result<(), String> {
+//! let mut archive = Archive::open(std::path::Path::new("weather.sqlite3"))?;
+//! let data = weather::fetch_weather(39.74, -104.99).await?;
+//! let now = chrono::Utc::now().timestamp();
+//! archive.store("Denver", weather::PROVIDER, &data, now)?;
+//! let last_week = archive.observations("Denver", weather::PROVIDER, now - 7 * 86400..now)?;
+//! archive.prune(&Retention::default(), now)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::verification::ForecastRun;
+use crate::weather::{
+    ApiWeatherData, ForecastInstant, ForecastPrecip, NormalTemperature, ObservationInstant,
+};
+use chrono::DateTime;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+use std::path::Path;
+use std::time::Duration;
+
+// How long a write waits for another process holding the database, e.g. the GUI and
+// `serve-metrics` archiving at the same time
+const BUSY_TIMEOUT: Duration = Duration::from_secs(5);
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS locations (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    provider TEXT NOT NULL,
+    latitude REAL NOT NULL,
+    longitude REAL NOT NULL,
+    UNIQUE (name, provider)
+);
+CREATE TABLE IF NOT EXISTS observations (
+    location_id INTEGER NOT NULL REFERENCES locations (id),
+    valid_time INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (location_id, valid_time)
+);
+CREATE TABLE IF NOT EXISTS forecasts (
+    location_id INTEGER NOT NULL REFERENCES locations (id),
+    valid_time INTEGER NOT NULL,
+    cycle_time INTEGER NOT NULL,
+    fetched_at INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (location_id, valid_time, cycle_time)
+);
+CREATE INDEX IF NOT EXISTS forecasts_by_cycle ON forecasts (location_id, cycle_time);
+CREATE TABLE IF NOT EXISTS precipitation (
+    location_id INTEGER NOT NULL REFERENCES locations (id),
+    hours INTEGER NOT NULL,
+    valid_time INTEGER NOT NULL,
+    cycle_time INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (location_id, hours, valid_time, cycle_time)
+);
+CREATE TABLE IF NOT EXISTS normals (
+    location_id INTEGER NOT NULL REFERENCES locations (id),
+    valid_time INTEGER NOT NULL,
+    data TEXT NOT NULL,
+    PRIMARY KEY (location_id, valid_time)
+);
+";
+
+/// A location with something archived.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArchivedLocation {
+    pub name: String,
+    pub provider: String,
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+/// How long archived points are kept by [`Archive::prune`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Retention {
+    /// Observations and normals, by valid time
+    pub observation_days: u32,
+    /// Forecasts and precipitation chances, by forecast cycle
+    pub forecast_days: u32,
+}
+
+impl Default for Retention {
+    fn default() -> Self {
+        Self {
+            observation_days: 365,
+            forecast_days: 30,
+        }
+    }
+}
+
+/// Rows [`Archive::store`] added or changed; refetched points that are identical don't count.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stored {
+    pub observations: usize,
+    pub forecasts: usize,
+}
+
+/// An open archive database.
+pub struct Archive {
+    conn: Connection,
+}
+
+fn timestamp(date: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+fn to_json<T: Serialize>(value: &T) -> Result<String, String> {
+    serde_json::to_string(value).map_err(|e| e.to_string())
+}
+
+fn from_json<T: DeserializeOwned>(rows: Vec<String>) -> Result<Vec<T>, String> {
+    rows.iter()
+        .map(|data| serde_json::from_str(data).map_err(|e| e.to_string()))
+        .collect()
+}
+
+impl Archive {
+    /// Open the archive at `path`, creating it if needed. The directory must exist.
+    pub fn open(path: &Path) -> Result<Archive, String> {
+        Archive::with_connection(Connection::open(path).map_err(|e| e.to_string())?)
+    }
+
+    /// A throwaway archive, for tests.
+    pub fn open_in_memory() -> Result<Archive, String> {
+        Archive::with_connection(Connection::open_in_memory().map_err(|e| e.to_string())?)
+    }
+
+    fn with_connection(conn: Connection) -> Result<Archive, String> {
+        conn.busy_timeout(BUSY_TIMEOUT).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        Ok(Archive { conn })
+    }
+
+    /// Archive a fetched response for `location` from `provider`. Precipitation chances belong
+    /// to the cycle of the response's forecasts.
+    pub fn store(
+        &mut self,
+        location: &str,
+        provider: &str,
+        weather: &ApiWeatherData,
+        fetched_at: i64,
+    ) -> Result<Stored, String> {
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        tx.execute(
+            "INSERT INTO locations (name, provider, latitude, longitude) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (name, provider) DO UPDATE
+             SET latitude = excluded.latitude, longitude = excluded.longitude",
+            params![location, provider, weather.latitude, weather.longitude],
+        )
+        .map_err(|e| e.to_string())?;
+        let location_id: i64 = tx
+            .query_row(
+                "SELECT id FROM locations WHERE name = ?1 AND provider = ?2",
+                params![location, provider],
+                |row| row.get(0),
+            )
+            .map_err(|e| e.to_string())?;
+
+        let mut stored = Stored::default();
+        for obs in &weather.observations_instant {
+            let Some(valid_time) = timestamp(&obs.valid_date) else {
+                continue;
+            };
+            stored.observations += tx
+                .execute(
+                    "INSERT INTO observations (location_id, valid_time, fetched_at, data)
+                     VALUES (?1, ?2, ?3, ?4)
+                     ON CONFLICT DO UPDATE
+                     SET fetched_at = excluded.fetched_at, data = excluded.data
+                     WHERE data != excluded.data",
+                    params![location_id, valid_time, fetched_at, to_json(obs)?],
+                )
+                .map_err(|e| e.to_string())?;
+        }
+
+        for fc in &weather.forecasts_instant {
+            let (Some(valid_time), Some(cycle_time)) =
+                (timestamp(&fc.valid_date), timestamp(&fc.cycle_date))
+            else {
+                continue;
+            };
+            stored.forecasts += tx
+                .execute(
+                    "INSERT INTO forecasts (location_id, valid_time, cycle_time, fetched_at, data)
+                     VALUES (?1, ?2, ?3, ?4, ?5)
+                     ON CONFLICT DO UPDATE
+                     SET fetched_at = excluded.fetched_at, data = excluded.data
+                     WHERE data != excluded.data",
+                    params![
+                        location_id,
+                        valid_time,
+                        cycle_time,
+                        fetched_at,
+                        to_json(fc)?
+                    ],
+                )
+                .map_err(|e| e.to_string())?;
+        }
+
+        let cycle_time = weather
+            .forecasts_instant
+            .first()
+            .and_then(|fc| timestamp(&fc.cycle_date));
+        if let Some(cycle_time) = cycle_time {
+            for (hours, precips) in [
+                (1, &weather.forecasts_precip_1hr),
+                (6, &weather.forecasts_precip_6hr),
+            ] {
+                for precip in precips {
+                    let Some(valid_time) = timestamp(&precip.valid_date) else {
+                        continue;
+                    };
+                    tx.execute(
+                        "INSERT OR REPLACE INTO precipitation
+                         (location_id, hours, valid_time, cycle_time, data)
+                         VALUES (?1, ?2, ?3, ?4, ?5)",
+                        params![location_id, hours, valid_time, cycle_time, to_json(precip)?],
+                    )
+                    .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+
+        for normal in &weather.normal_temperatures {
+            let Some(valid_time) = timestamp(&normal.valid_date) else {
+                continue;
+            };
+            tx.execute(
+                "INSERT OR REPLACE INTO normals (location_id, valid_time, data)
+                 VALUES (?1, ?2, ?3)",
+                params![location_id, valid_time, to_json(normal)?],
+            )
+            .map_err(|e| e.to_string())?;
+        }
+
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(stored)
+    }
+
+    /// Every archived location, by name.
+    pub fn locations(&self) -> Result<Vec<ArchivedLocation>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT name, provider, latitude, longitude FROM locations ORDER BY name, provider",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ArchivedLocation {
+                    name: row.get(0)?,
+                    provider: row.get(1)?,
+                    latitude: row.get(2)?,
+                    longitude: row.get(3)?,
+                })
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+
+    fn location_id(&self, location: &str, provider: &str) -> Result<Option<i64>, String> {
+        self.conn
+            .query_row(
+                "SELECT id FROM locations WHERE name = ?1 AND provider = ?2",
+                params![location, provider],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| e.to_string())
+    }
+
+    // The `data` column of a query taking the location id and the range as its parameters
+    fn query_data(
+        &self,
+        sql: &str,
+        location: &str,
+        provider: &str,
+        range: &Range<i64>,
+    ) -> Result<Vec<String>, String> {
+        let Some(location_id) = self.location_id(location, provider)? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(sql).map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![location_id, range.start, range.end], |row| {
+                row.get(0)
+            })
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+
+    /// Observations valid in `range` (Unix seconds, end excluded), oldest first.
+    pub fn observations(
+        &self,
+        location: &str,
+        provider: &str,
+        range: Range<i64>,
+    ) -> Result<Vec<ObservationInstant>, String> {
+        from_json(self.query_data(
+            "SELECT data FROM observations
+             WHERE location_id = ?1 AND valid_time >= ?2 AND valid_time < ?3
+             ORDER BY valid_time",
+            location,
+            provider,
+            &range,
+        )?)
+    }
+
+    /// Forecasts valid in `range` from every archived cycle, by valid time and then cycle.
+    pub fn forecasts(
+        &self,
+        location: &str,
+        provider: &str,
+        range: Range<i64>,
+    ) -> Result<Vec<ForecastInstant>, String> {
+        from_json(self.query_data(
+            "SELECT data FROM forecasts
+             WHERE location_id = ?1 AND valid_time >= ?2 AND valid_time < ?3
+             ORDER BY valid_time, cycle_time",
+            location,
+            provider,
+            &range,
+        )?)
+    }
+
+    /// Normal temperatures for days in `range`, oldest first.
+    pub fn normals(
+        &self,
+        location: &str,
+        provider: &str,
+        range: Range<i64>,
+    ) -> Result<Vec<NormalTemperature>, String> {
+        from_json(self.query_data(
+            "SELECT data FROM normals
+             WHERE location_id = ?1 AND valid_time >= ?2 AND valid_time < ?3
+             ORDER BY valid_time",
+            location,
+            provider,
+            &range,
+        )?)
+    }
+
+    /// Forecast cycles archived for the location (Unix seconds), oldest first.
+    pub fn cycles(&self, location: &str, provider: &str) -> Result<Vec<i64>, String> {
+        let Some(location_id) = self.location_id(location, provider)? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT DISTINCT cycle_time FROM forecasts WHERE location_id = ?1
+                 ORDER BY cycle_time",
+            )
+            .map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map(params![location_id], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+    }
+
+    /// Forecast runs cycled in `range` (Unix seconds, end excluded), oldest first, each with its
+    /// forecasts and precipitation chances, as [`ForecastRun::from_weather`] keeps them.
+    pub fn runs(
+        &self,
+        location: &str,
+        provider: &str,
+        range: Range<i64>,
+    ) -> Result<Vec<ForecastRun>, String> {
+        let Some(found) = self
+            .locations()?
+            .into_iter()
+            .find(|l| l.name == location && l.provider == provider)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut runs = Vec::new();
+        for cycle in self.cycles(location, provider)? {
+            if !range.contains(&cycle) {
+                continue;
+            }
+            let cycle_range = cycle..cycle + 1;
+            let forecasts: Vec<ForecastInstant> = from_json(self.query_data(
+                "SELECT data FROM forecasts
+                 WHERE location_id = ?1 AND cycle_time >= ?2 AND cycle_time < ?3
+                 ORDER BY valid_time",
+                location,
+                provider,
+                &cycle_range,
+            )?)?;
+            let precipitation = |hours: i64| -> Result<Vec<ForecastPrecip>, String> {
+                from_json(self.query_data(
+                    &format!(
+                        "SELECT data FROM precipitation
+                         WHERE location_id = ?1 AND cycle_time >= ?2 AND cycle_time < ?3
+                         AND hours = {hours}
+                         ORDER BY valid_time"
+                    ),
+                    location,
+                    provider,
+                    &cycle_range,
+                )?)
+            };
+            let Some(cycle_date) = forecasts.first().map(|fc| fc.cycle_date.clone()) else {
+                continue;
+            };
+            runs.push(ForecastRun {
+                cycle_date,
+                weather: ApiWeatherData {
+                    latitude: found.latitude,
+                    longitude: found.longitude,
+                    grid_id: None,
+                    normal_temperatures: Vec::new(),
+                    observations_instant: Vec::new(),
+                    forecasts_instant: forecasts,
+                    forecasts_precip_1hr: precipitation(1)?,
+                    forecasts_precip_6hr: precipitation(6)?,
+                },
+            });
+        }
+        Ok(runs)
+    }
+
+    /// `range` as a single response, the way the feed would have served it: observations newest
+    /// first, and for each valid time the forecast and precipitation chances of the latest cycle
+    /// that had one. `None` for a location that isn't archived.
+    pub fn weather(
+        &self,
+        location: &str,
+        provider: &str,
+        range: Range<i64>,
+    ) -> Result<Option<ApiWeatherData>, String> {
+        let Some(found) = self
+            .locations()?
+            .into_iter()
+            .find(|l| l.name == location && l.provider == provider)
+        else {
+            return Ok(None);
+        };
+
+        let mut observations = self.observations(location, provider, range.clone())?;
+        observations.reverse();
+        let forecasts: Vec<ForecastInstant> = from_json(self.query_data(
+            "SELECT data FROM forecasts f
+             WHERE location_id = ?1 AND valid_time >= ?2 AND valid_time < ?3
+             AND cycle_time = (SELECT MAX(cycle_time) FROM forecasts
+                               WHERE location_id = f.location_id AND valid_time = f.valid_time)
+             ORDER BY valid_time",
+            location,
+            provider,
+            &range,
+        )?)?;
+        let precipitation = |hours: i64| -> Result<Vec<ForecastPrecip>, String> {
+            from_json(self.query_data(
+                &format!(
+                    "SELECT data FROM precipitation p
+                     WHERE location_id = ?1 AND valid_time >= ?2 AND valid_time < ?3
+                     AND hours = {hours}
+                     AND cycle_time = (SELECT MAX(cycle_time) FROM precipitation
+                                       WHERE location_id = p.location_id AND hours = p.hours
+                                       AND valid_time = p.valid_time)
+                     ORDER BY valid_time"
+                ),
+                location,
+                provider,
+                &range,
+            )?)
+        };
+
+        Ok(Some(ApiWeatherData {
+            latitude: found.latitude,
+            longitude: found.longitude,
+            grid_id: None,
+            normal_temperatures: self.normals(location, provider, range.clone())?,
+            observations_instant: observations,
+            forecasts_instant: forecasts,
+            forecasts_precip_1hr: precipitation(1)?,
+            forecasts_precip_6hr: precipitation(6)?,
+        }))
+    }
+
+    /// Delete what `retention` no longer keeps, returning the number of rows removed.
+    pub fn prune(&mut self, retention: &Retention, now: i64) -> Result<usize, String> {
+        let observation_cutoff = now - i64::from(retention.observation_days) * 86400;
+        let forecast_cutoff = now - i64::from(retention.forecast_days) * 86400;
+        let tx = self.conn.transaction().map_err(|e| e.to_string())?;
+        let mut removed = 0;
+        for (sql, cutoff) in [
+            (
+                "DELETE FROM observations WHERE valid_time < ?1",
+                observation_cutoff,
+            ),
+            (
+                "DELETE FROM normals WHERE valid_time < ?1",
+                observation_cutoff,
+            ),
+            (
+                "DELETE FROM forecasts WHERE cycle_time < ?1",
+                forecast_cutoff,
+            ),
+            (
+                "DELETE FROM precipitation WHERE cycle_time < ?1",
+                forecast_cutoff,
+            ),
+        ] {
+            removed += tx
+                .execute(sql, params![cutoff])
+                .map_err(|e| e.to_string())?;
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        Ok(removed)
+    }
+}
@@ -0,0 +1,202 @@
+use crate::config::{ArchiveConfig, SavedLocation};
+use chrono::{DateTime, Utc};
+use rusty_wx::archive::{Archive, Retention};
+use rusty_wx::weather::{self, ApiWeatherData};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+// Lowercase ASCII with underscores, for topics, entity and API ids
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    slug.trim_matches('_').to_string()
+}
+
+// A slug for each location, with a numeric suffix on repeats and location_<n> for a name with
+// no ASCII letters or digits (e.g. "東京"), so no two sites share a topic or URL
+fn unique_ids(locations: &[SavedLocation]) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+    for (i, location) in locations.iter().enumerate() {
+        let mut base = slug(&location.name);
+        if base.is_empty() {
+            base = format!("location_{}", i + 1);
+        }
+        let mut id = base.clone();
+        let mut n = 2;
+        while ids.contains(&id) {
+            id = format!("{}_{}", base, n);
+            n += 1;
+        }
+        ids.push(id);
+    }
+    ids
+}
+
+// The latest fetch of one saved location, with fetch statistics
+#[derive(Debug, Clone)]
+pub struct CachedSite {
+    // Unique among the cached sites, for topics, entity and API ids
+    pub id: String,
+    pub location: SavedLocation,
+    pub data: Option<ApiWeatherData>,
+    pub fetched_at: Option<DateTime<Utc>>,
+    pub last_error: Option<String>,
+    pub successes: u64,
+    pub failures: u64,
+    // Seconds, over all fetches and for the latest one
+    pub latency_sum: f64,
+    pub last_latency: Option<f64>,
+}
+
+// Saved locations kept up to date in the background, shared by the long-running modes
+#[derive(Clone)]
+pub struct SiteCache {
+    sites: Arc<Mutex<Vec<CachedSite>>>,
+    // The API fetched from, RUSTY_WX_API_URL or the real one unless a test points it elsewhere
+    api_url: String,
+    // Every successful fetch is also archived here, pruned after each refresh
+    archive: Option<Arc<Mutex<(Archive, Retention)>>>,
+}
+
+impl SiteCache {
+    pub fn new(locations: &[SavedLocation]) -> Self {
+        let sites = locations
+            .iter()
+            .zip(unique_ids(locations))
+            .map(|(location, id)| CachedSite {
+                id,
+                location: location.clone(),
+                data: None,
+                fetched_at: None,
+                last_error: None,
+                successes: 0,
+                failures: 0,
+                latency_sum: 0.0,
+                last_latency: None,
+            })
+            .collect();
+        Self {
+            sites: Arc::new(Mutex::new(sites)),
+            api_url: weather::base_url(),
+            archive: None,
+        }
+    }
+
+    #[cfg(test)]
+    pub fn with_api_url(mut self, url: &str) -> Self {
+        self.api_url = url.to_string();
+        self
+    }
+
+    // Archive fetches as configured
+    pub fn with_archive(mut self, config: &ArchiveConfig) -> Self {
+        self.archive = config
+            .open()
+            .map(|archive| Arc::new(Mutex::new((archive, config.retention.clone()))));
+        self
+    }
+
+    // Run `f` on the archive on the blocking pool, since a write can wait on another process's
+    // lock; does nothing without an archive
+    async fn with_archive_blocking<F>(&self, f: F) -> Result<(), String>
+    where
+        F: FnOnce(&mut Archive, &Retention) -> Result<(), String> + Send + 'static,
+    {
+        let Some(archive) = self.archive.clone() else {
+            return Ok(());
+        };
+        tokio::task::spawn_blocking(move || {
+            let mut archive = archive.lock().map_err(|e| e.to_string())?;
+            let (archive, retention) = &mut *archive;
+            f(archive, retention)
+        })
+        .await
+        .unwrap_or_else(|e| Err(e.to_string()))
+    }
+
+    async fn archive_fetch(&self, location: &SavedLocation, data: &ApiWeatherData) {
+        let (name, data) = (location.name.clone(), data.clone());
+        let stored = self
+            .with_archive_blocking(move |archive, _| {
+                archive
+                    .store(&name, weather::PROVIDER, &data, Utc::now().timestamp())
+                    .map(|_| ())
+            })
+            .await;
+        if let Err(e) = stored {
+            eprintln!("Archiving {} failed: {}", location.name, e);
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<CachedSite> {
+        self.sites
+            .lock()
+            .map(|sites| sites.clone())
+            .unwrap_or_default()
+    }
+
+    // Fetch every location once, one after another to go easy on the API
+    pub async fn refresh(&self) {
+        let locations: Vec<SavedLocation> = self
+            .snapshot()
+            .into_iter()
+            .map(|site| site.location)
+            .collect();
+        for (i, location) in locations.iter().enumerate() {
+            let started = Instant::now();
+            let result =
+                weather::fetch_weather_from(&self.api_url, location.lat, location.lon).await;
+            let latency = started.elapsed().as_secs_f64();
+            if let Ok(data) = &result {
+                self.archive_fetch(location, data).await;
+            }
+
+            let Ok(mut sites) = self.sites.lock() else {
+                return;
+            };
+            let Some(site) = sites.get_mut(i) else {
+                continue;
+            };
+            site.latency_sum += latency;
+            site.last_latency = Some(latency);
+            match result {
+                Ok(data) => {
+                    site.data = Some(data);
+                    site.fetched_at = Some(Utc::now());
+                    site.last_error = None;
+                    site.successes += 1;
+                }
+                Err(e) => {
+                    eprintln!("Fetching {} failed: {}", location.name, e);
+                    site.last_error = Some(e);
+                    site.failures += 1;
+                }
+            }
+        }
+
+        let pruned = self
+            .with_archive_blocking(|archive, retention| {
+                archive.prune(retention, Utc::now().timestamp()).map(|_| ())
+            })
+            .await;
+        if let Err(e) = pruned {
+            eprintln!("Failed to prune the archive: {}", e);
+        }
+    }
+
+    // Refresh now and then every `interval`, forever
+    pub async fn refresh_every(self, interval: std::time::Duration) {
+        loop {
+            self.refresh().await;
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
@@ -0,0 +1,297 @@
+//! iCalendar (.ics) export of events derived from the forecast: daily highs and lows,
+//! likely precipitation, freezes and heat stress.
+
+use crate::element::WeatherElement;
+use crate::timeline::{self, element_value};
+use crate::weather::{ApiWeatherData, WeatherDataPoint};
+use chrono::{DateTime, Duration, Local, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Thresholds for the generated events.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarRules {
+    /// Hours with at least this chance of precipitation become "Rain likely" blocks
+    pub rain_pct: f64,
+    /// Hours at or below this temperature (°F) become freeze warnings
+    pub freeze_f: f64,
+    /// Hours at or above this WBGT (°F) become heat-stress windows
+    pub heat_wbgt_f: f64,
+}
+
+impl Default for CalendarRules {
+    fn default() -> Self {
+        Self {
+            rain_pct: 60.0,
+            freeze_f: 32.0,
+            heat_wbgt_f: 82.0,
+        }
+    }
+}
+
+/// When an event happens: a whole local day, or a span of time.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EventTime {
+    AllDay(NaiveDate),
+    Span(DateTime<Utc>, DateTime<Utc>),
+}
+
+/// One calendar entry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalendarEvent {
+    /// Stable across regenerations of the same forecast, so calendars update in place
+    pub uid: String,
+    pub time: EventTime,
+    pub summary: String,
+    pub description: String,
+}
+
+/// Events for the upcoming part of the forecast, days first, then spans in time order. Days
+/// and the times in summaries are those of `tz`, usually [`chrono::Local`].
+pub fn events<Tz: TimeZone>(
+    location: &str,
+    weather: &ApiWeatherData,
+    rules: &CalendarRules,
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<CalendarEvent> {
+    let timeline = timeline::build_timeline(weather);
+    let site = format!("{:.2}_{:.2}", weather.latitude, weather.longitude);
+    let mut events = daily_events(location, &site, &timeline, now, tz);
+
+    let forecasts: Vec<(DateTime<Utc>, &WeatherDataPoint)> = timeline
+        .iter()
+        .filter(|point| matches!(point, WeatherDataPoint::Forecast(_)))
+        .filter_map(|point| {
+            let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+            Some((time.with_timezone(&Utc), point))
+        })
+        .filter(|(time, _)| *time >= now - Duration::hours(1))
+        .collect();
+    let value =
+        |point: &WeatherDataPoint, element: WeatherElement| element_value(weather, point, &element);
+
+    for (start, end, peak) in runs(&forecasts, |p| {
+        value(p, WeatherElement::PrecipitationProbability).filter(|v| *v >= rules.rain_pct)
+    }) {
+        events.push(span_event(
+            tz,
+            "rain",
+            &site,
+            start,
+            end,
+            "Rain likely".to_string(),
+            format!("{}: up to {:.0}% chance of precipitation.", location, peak),
+        ));
+    }
+
+    // Runs keep the highest value, so negate to track the lowest temperature
+    for (start, end, coldest) in runs(&forecasts, |p| {
+        value(p, WeatherElement::Temperature)
+            .filter(|v| *v <= rules.freeze_f)
+            .map(|v| -v)
+    }) {
+        events.push(span_event(
+            tz,
+            "freeze",
+            &site,
+            start,
+            end,
+            format!("Freeze warning, low {:.0}°F", -coldest),
+            format!(
+                "{}: temperatures at or below {:.0}°F.",
+                location, rules.freeze_f
+            ),
+        ));
+    }
+
+    for (start, end, peak) in runs(&forecasts, |p| {
+        value(p, WeatherElement::WBGT).filter(|v| *v >= rules.heat_wbgt_f)
+    }) {
+        events.push(span_event(
+            tz,
+            "heat",
+            &site,
+            start,
+            end,
+            format!("Heat stress, WBGT up to {:.0}°F", peak),
+            format!(
+                "{}: wet bulb globe temperature at or above {:.0}°F. Limit strenuous activity \
+                 and take breaks in the shade.",
+                location, rules.heat_wbgt_f
+            ),
+        ));
+    }
+
+    events
+}
+
+// High and low for each day of `tz` from today on
+fn daily_events<Tz: TimeZone>(
+    location: &str,
+    site: &str,
+    timeline: &[WeatherDataPoint],
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<CalendarEvent> {
+    let today = now.with_timezone(tz).date_naive();
+    let mut days: Vec<(NaiveDate, f64, f64)> = Vec::new();
+    for point in timeline {
+        let (Ok(dt), Some(temp)) = (
+            DateTime::parse_from_rfc3339(point.valid_date()),
+            point.temperature(),
+        ) else {
+            continue;
+        };
+        let date = dt.with_timezone(tz).date_naive();
+        if date < today {
+            continue;
+        }
+        match days.iter_mut().find(|(d, _, _)| *d == date) {
+            Some((_, high, low)) => {
+                *high = high.max(temp);
+                *low = low.min(temp);
+            }
+            None => days.push((date, temp, temp)),
+        }
+    }
+
+    days.into_iter()
+        .map(|(date, high, low)| CalendarEvent {
+            uid: format!("day-{}-{}@rusty-wx", date.format("%Y%m%d"), site),
+            time: EventTime::AllDay(date),
+            summary: format!("High {:.0}° / Low {:.0}°", high, low),
+            description: format!(
+                "{}: forecast high {:.0}°F, low {:.0}°F.",
+                location, high, low
+            ),
+        })
+        .collect()
+}
+
+fn span_event<Tz: TimeZone>(
+    tz: &Tz,
+    kind: &str,
+    site: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    title: String,
+    description: String,
+) -> CalendarEvent {
+    let local = |time: DateTime<Utc>| {
+        let time = time.with_timezone(tz).naive_local();
+        time.format("%H:%M").to_string()
+    };
+    CalendarEvent {
+        uid: format!(
+            "{}-{}-{}@rusty-wx",
+            kind,
+            start.format("%Y%m%dT%H%MZ"),
+            site
+        ),
+        time: EventTime::Span(start, end),
+        summary: format!("{} {}–{}", title, local(start), local(end)),
+        description,
+    }
+}
+
+// Contiguous points where `matching` returns a value, as (start, end, highest value);
+// each point lasts until the next one, and the last for an hour
+fn runs(
+    points: &[(DateTime<Utc>, &WeatherDataPoint)],
+    matching: impl Fn(&WeatherDataPoint) -> Option<f64>,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>, f64)> {
+    let mut runs = Vec::new();
+    let mut current: Option<(DateTime<Utc>, DateTime<Utc>, f64)> = None;
+    for (i, (time, point)) in points.iter().enumerate() {
+        let until = points
+            .get(i + 1)
+            .map_or(*time + Duration::hours(1), |(next, _)| *next);
+        match matching(point) {
+            Some(value) => match current.as_mut() {
+                Some((_, end, peak)) => {
+                    *end = until;
+                    *peak = peak.max(value);
+                }
+                None => current = Some((*time, until, value)),
+            },
+            None => runs.extend(current.take()),
+        }
+    }
+    runs.extend(current);
+    runs
+}
+
+/// The events as an iCalendar document, with CRLF line endings and folded lines.
+pub fn to_ics(calendar_name: &str, events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//rusty-wx//Weather forecast//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        format!("X-WR-CALNAME:{}", escape(calendar_name)),
+    ];
+
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", stamp));
+        match &event.time {
+            EventTime::AllDay(date) => {
+                lines.push(format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")));
+                let next = *date + Duration::days(1);
+                lines.push(format!("DTEND;VALUE=DATE:{}", next.format("%Y%m%d")));
+                // Daily entries shouldn't block anyone's schedule
+                lines.push("TRANSP:TRANSPARENT".to_string());
+            }
+            EventTime::Span(start, end) => {
+                lines.push(format!("DTSTART:{}", start.format("%Y%m%dT%H%M%SZ")));
+                lines.push(format!("DTEND:{}", end.format("%Y%m%dT%H%M%SZ")));
+                lines.push("TRANSP:TRANSPARENT".to_string());
+            }
+        }
+        lines.push(format!("SUMMARY:{}", escape(&event.summary)));
+        lines.push(format!("DESCRIPTION:{}", escape(&event.description)));
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut ics = String::new();
+    for line in lines {
+        ics.push_str(&fold(&line));
+        ics.push_str("\r\n");
+    }
+    ics
+}
+
+/// Build the events for a location in the local time zone and render them, the usual way to
+/// get a file.
+pub fn export(location: &str, weather: &ApiWeatherData, rules: &CalendarRules) -> String {
+    let now = Utc::now();
+    let events = events(location, weather, rules, now, &Local);
+    to_ics(&format!("Weather: {}", location), &events, now)
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Lines longer than 75 octets continue on the next line after a space (RFC 5545 3.1)
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
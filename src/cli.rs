@@ -0,0 +1,578 @@
+use crate::api;
+use crate::app::{self, WeatherApp};
+use crate::cache::SiteCache;
+use crate::config::Config;
+use crate::export::{self, ExportFormat};
+use crate::metrics;
+use crate::mqtt;
+use crate::statusbar;
+use crate::table::{self, TableColumn, TableRow};
+use chrono::{DateTime, Duration, Local, Utc};
+use clap::{Args, Parser, Subcommand};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::mock::{MockResponse, MockServer};
+use rusty_wx::weather;
+use rusty_wx::{calendar, daily};
+use serde_json::{json, Map, Value};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Parser)]
+#[command(
+    name = "rusty-wx",
+    about = "Weather app; starts the GUI when no subcommand is given"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<CliCommand>,
+}
+
+#[derive(Args, Clone)]
+pub struct LocationArgs {
+    #[arg(long, allow_hyphen_values = true)]
+    lat: f64,
+    #[arg(long, allow_hyphen_values = true)]
+    lon: f64,
+    /// Location name shown in the output
+    #[arg(long)]
+    name: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum CliCommand {
+    /// Current conditions and the forecast summary
+    Now {
+        #[command(flatten)]
+        location: LocationArgs,
+    },
+    /// Hour-by-hour table, using the columns chosen for the GUI table
+    Hourly {
+        #[command(flatten)]
+        location: LocationArgs,
+        /// Number of hours from now
+        #[arg(long, default_value_t = 24)]
+        hours: i64,
+    },
+    /// Daily highs, lows, wind and chances of precipitation
+    Daily {
+        #[command(flatten)]
+        location: LocationArgs,
+    },
+    /// Current conditions, hourly and daily forecasts and the summary as JSON
+    Json {
+        #[command(flatten)]
+        location: LocationArgs,
+    },
+    /// Write forecast events (daily highs and lows, rain, freezes, heat stress) as iCalendar,
+    /// using the thresholds from the config
+    Calendar {
+        #[command(flatten)]
+        location: LocationArgs,
+        /// Output .ics file; stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Status bar updates as JSON lines for a waybar or COSMIC panel custom module
+    Waybar {
+        #[command(flatten)]
+        location: LocationArgs,
+        /// Hours of forecast in the tooltip and precipitation percentage
+        #[arg(long, default_value_t = 6)]
+        hours: i64,
+        /// Minutes between updates; defaults to `refresh_minutes` from the config
+        #[arg(long)]
+        interval: Option<u64>,
+        /// Print one update and exit, for modules polled with `interval`
+        #[arg(long)]
+        once: bool,
+    },
+    /// Serve current and near-term values for the saved locations as Prometheus metrics
+    ServeMetrics {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:9464")]
+        listen: SocketAddr,
+        /// Minutes between refreshes; defaults to `refresh_minutes` from the config
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+    /// Serve the saved locations' timelines, daily forecasts and alerts as a local JSON API
+    ServeApi {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8470")]
+        listen: SocketAddr,
+        /// Minutes between refreshes; defaults to `refresh_minutes` from the config
+        #[arg(long)]
+        interval: Option<u64>,
+    },
+    /// Publish conditions and forecasts for the saved locations to an MQTT broker, with
+    /// Home Assistant discovery, using the broker settings from the config
+    Mqtt {
+        /// Publish once and exit
+        #[arg(long)]
+        once: bool,
+    },
+    /// Serve a recorded API response locally, to run the app without the real API; point the
+    /// app at it with RUSTY_WX_API_URL
+    MockServer {
+        /// Recorded API response (JSON) to serve
+        fixture: PathBuf,
+        #[arg(long, default_value = "127.0.0.1:8001")]
+        listen: SocketAddr,
+        /// Milliseconds to wait before each response
+        #[arg(long, default_value_t = 0)]
+        delay_ms: u64,
+        /// Answer with this HTTP status instead of the fixture
+        #[arg(long)]
+        status: Option<u16>,
+        /// Send only the first half of the fixture
+        #[arg(long)]
+        truncate: bool,
+        /// Remove this field from every object in the fixture, may be repeated
+        #[arg(long = "drop-field")]
+        drop_fields: Vec<String>,
+    },
+    /// Render the meteogram to an SVG or PNG file (format taken from the extension)
+    Export {
+        #[command(flatten)]
+        location: LocationArgs,
+        /// Overlay element, may be repeated (e.g. precip, dewpoint, wind)
+        #[arg(long = "overlay", value_parser = parse_overlay)]
+        overlays: Vec<WeatherElement>,
+        /// Width of the plot area in pixels
+        #[arg(long, default_value_t = export::DEFAULT_EXPORT_WIDTH)]
+        width: f32,
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+}
+
+fn parse_overlay(value: &str) -> Result<WeatherElement, String> {
+    let element: WeatherElement = value.parse()?;
+    if !element.is_overlay() {
+        return Err("temperature is always drawn, it isn't an overlay".to_string());
+    }
+    Ok(element)
+}
+
+pub fn run(command: CliCommand) -> Result<(), String> {
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    runtime.block_on(async {
+        match command {
+            CliCommand::Now { location } => {
+                let app = load_app(location).await?;
+                print_now(&app);
+                Ok(())
+            }
+            CliCommand::Hourly { location, hours } => {
+                let app = load_app(location).await?;
+                print_hourly(&app, hours, Utc::now());
+                Ok(())
+            }
+            CliCommand::Daily { location } => {
+                let app = load_app(location).await?;
+                print_daily(&app, Utc::now());
+                Ok(())
+            }
+            CliCommand::Json { location } => {
+                let app = load_app(location).await?;
+                let output = serde_json::to_string_pretty(&json_report(&app, Utc::now()))
+                    .map_err(|e| e.to_string())?;
+                println!("{}", output);
+                Ok(())
+            }
+            CliCommand::Calendar { location, output } => {
+                let app = load_app(location).await?;
+                let Some(weather) = &app.weather_data else {
+                    return Err("no weather data".to_string());
+                };
+                let ics = calendar::export(&app.current_city, weather, &app.config.calendar.rules);
+                match output {
+                    Some(path) => {
+                        let path = export::write_data_export(ics, path).await?;
+                        eprintln!("Wrote {}", path.display());
+                    }
+                    None => print!("{}", ics),
+                }
+                Ok(())
+            }
+            CliCommand::Waybar {
+                location,
+                hours,
+                interval,
+                once,
+            } => {
+                let interval = refresh_interval(interval, &Config::load());
+                loop {
+                    // A failed fetch shows in the bar and is retried on the next update
+                    let update = match load_app(location.clone()).await {
+                        Ok(app) => statusbar::status(&app, hours, Utc::now(), &Local),
+                        Err(e) => statusbar::error_status(&e),
+                    };
+                    println!("{}", update);
+                    std::io::stdout().flush().map_err(|e| e.to_string())?;
+                    if once {
+                        return Ok(());
+                    }
+                    tokio::time::sleep(interval).await;
+                }
+            }
+            CliCommand::ServeMetrics { listen, interval } => {
+                let config = Config::load();
+                if config.locations.is_empty() {
+                    return Err("no saved locations in the config".to_string());
+                }
+                let cache = SiteCache::new(&config.locations).with_archive(&config.archive);
+                metrics::serve(listen, cache, refresh_interval(interval, &config)).await
+            }
+            CliCommand::ServeApi { listen, interval } => {
+                let config = Config::load();
+                if config.locations.is_empty() {
+                    return Err("no saved locations in the config".to_string());
+                }
+                let cache = SiteCache::new(&config.locations).with_archive(&config.archive);
+                let interval = refresh_interval(interval, &config);
+                api::serve(listen, cache, config.alerts, interval).await
+            }
+            CliCommand::Mqtt { once } => {
+                let config = Config::load();
+                if config.locations.is_empty() {
+                    return Err("no saved locations in the config".to_string());
+                }
+                let cache = SiteCache::new(&config.locations).with_archive(&config.archive);
+                let interval = config.refresh_interval();
+                mqtt::run(config.mqtt, cache, interval, once).await
+            }
+            CliCommand::MockServer {
+                fixture,
+                listen,
+                delay_ms,
+                status,
+                truncate,
+                drop_fields,
+            } => {
+                let body = std::fs::read_to_string(&fixture)
+                    .map_err(|e| format!("{}: {}", fixture.display(), e))?;
+                let fields: Vec<&str> = drop_fields.iter().map(String::as_str).collect();
+                let mut response = match status {
+                    Some(status) => MockResponse::status(status),
+                    None => MockResponse::raw(body).without_fields(&fields),
+                };
+                if truncate {
+                    response = response.truncated();
+                }
+                let response = response.with_delay(std::time::Duration::from_millis(delay_ms));
+                let server = MockServer::bind(listen, response).await?;
+                eprintln!(
+                    "Serving {} at {}; run the app with RUSTY_WX_API_URL={}",
+                    fixture.display(),
+                    server.base_url(),
+                    server.base_url()
+                );
+                tokio::signal::ctrl_c().await.map_err(|e| e.to_string())
+            }
+            CliCommand::Export {
+                location,
+                overlays,
+                width,
+                output,
+            } => {
+                let mut app = load_app(location).await?;
+                if !overlays.is_empty() {
+                    app.selected_weather_elements = overlays;
+                }
+                let svg = export::meteogram_svg(&app, width);
+                let format = ExportFormat::from_path(&output);
+                let path = export::write_export(svg, output, format).await?;
+                eprintln!("Wrote {}", path.display());
+                Ok(())
+            }
+        }
+    })
+}
+
+// `--interval` minutes, or the shared refresh interval from the config
+fn refresh_interval(minutes: Option<u64>, config: &Config) -> std::time::Duration {
+    match minutes {
+        Some(minutes) => std::time::Duration::from_secs(minutes.max(1) * 60),
+        None => config.refresh_interval(),
+    }
+}
+
+// Fetch a location and build the same app state the GUI would show
+async fn load_app(location: LocationArgs) -> Result<WeatherApp, String> {
+    let LocationArgs { lat, lon, name } = location;
+    let data = weather::fetch_weather(lat, lon).await?;
+    let name = name.unwrap_or_else(|| format!("{:.2}, {:.2}", lat, lon));
+    let config = Config::load();
+    if let Some(mut archive) = config.archive.open() {
+        app::archive_fetch(&mut archive, &name, &data);
+    }
+    let mut app = WeatherApp::for_location(&name, data);
+    app.config = config;
+    Ok(app)
+}
+
+// Rows from the hour closest to `now` onwards
+fn upcoming_rows(app: &WeatherApp, now: DateTime<Utc>) -> Vec<TableRow> {
+    let start = now - Duration::minutes(30);
+    table::rows(app, None)
+        .into_iter()
+        .filter(|row| row.time >= start)
+        .collect()
+}
+
+fn summary_lines(app: &WeatherApp) -> Vec<String> {
+    app.summary
+        .text()
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn kind(row: &TableRow) -> &'static str {
+    if row.observation {
+        "observation"
+    } else {
+        "forecast"
+    }
+}
+
+fn cell(value: Option<f64>, format: impl Fn(f64) -> String) -> String {
+    value.map(format).unwrap_or_else(|| "-".to_string())
+}
+
+fn print_now(app: &WeatherApp) {
+    let rows = table::rows(app, None);
+    let Some(row) = rows.get(app.timeline_index) else {
+        println!("{}: no data", app.current_city);
+        return;
+    };
+    println!(
+        "{} - {} ({})",
+        app.current_city,
+        row.time.format("%a %b %-d %-I:%M %p"),
+        kind(row)
+    );
+    for column in TableColumn::ALL {
+        if let Some(value) = row.value(column) {
+            println!("  {:<12} {}", column.header(), column.format(value));
+        }
+    }
+
+    let summary = summary_lines(app);
+    if !summary.is_empty() {
+        println!();
+        for line in summary {
+            println!("{}", line);
+        }
+    }
+}
+
+fn print_hourly(app: &WeatherApp, hours: i64, now: DateTime<Utc>) {
+    let end = now + Duration::hours(hours);
+    let columns = &app.config.table_columns;
+    let widths: Vec<usize> = columns
+        .iter()
+        .map(|column| column.header().chars().count().max(5))
+        .collect();
+
+    println!("{}", app.current_city);
+    let mut header = format!("{:<16} {:<4}", "Time", "");
+    for (column, width) in columns.iter().zip(&widths) {
+        header.push_str(&format!(" {:>width$}", column.header(), width = width));
+    }
+    println!("{}", header);
+
+    for row in upcoming_rows(app, now).iter().filter(|row| row.time <= end) {
+        let kind = if row.observation { "obs" } else { "" };
+        let mut line = format!("{:<16} {:<4}", row.time.format("%a %-m/%-d %-I %p"), kind);
+        for (column, width) in columns.iter().zip(&widths) {
+            let value = cell(row.value(*column), |v| column.format(v));
+            line.push_str(&format!(" {:>width$}", value, width = width));
+        }
+        println!("{}", line);
+    }
+}
+
+fn print_daily(app: &WeatherApp, now: DateTime<Utc>) {
+    let today = now.with_timezone(&Local).date_naive();
+    println!("{}", app.current_city);
+    println!(
+        "{:<10} {:>6} {:>6} {:>8} {:>8} {:>8} {:>9}",
+        "Date", "High", "Low", "Wind mph", "Gust mph", "Precip %", "T-Storm %"
+    );
+    let days = match &app.weather_data {
+        Some(weather) => daily::daily_rows(weather, &Local),
+        None => Vec::new(),
+    };
+    for day in days.iter().filter(|day| day.date >= today) {
+        let temp = |v: f64| format!("{:.0}°", v);
+        let whole = |v: f64| format!("{:.0}", v);
+        println!(
+            "{:<10} {:>6} {:>6} {:>8} {:>8} {:>8} {:>9}",
+            day.date.format("%a %-m/%-d").to_string(),
+            cell(day.high, temp),
+            cell(day.low, temp),
+            cell(day.wind, whole),
+            cell(day.gust, whole),
+            cell(day.precipitation, whole),
+            cell(day.thunderstorm, whole),
+        );
+    }
+}
+
+// Missing values are null; the keys carry the units
+fn row_json(row: &TableRow) -> Value {
+    let mut object = Map::new();
+    object.insert("valid_date".to_string(), json!(row.valid_date));
+    object.insert("kind".to_string(), json!(kind(row)));
+    for column in TableColumn::ALL {
+        object.insert(column.key().to_string(), json!(row.value(column)));
+    }
+    Value::Object(object)
+}
+
+fn json_report(app: &WeatherApp, now: DateTime<Utc>) -> Value {
+    let rows = table::rows(app, None);
+    let today = now.with_timezone(&Local).date_naive();
+    let (lat, lon) = app.weather_data.as_ref().map_or((None, None), |data| {
+        (Some(data.latitude), Some(data.longitude))
+    });
+
+    json!({
+        "location": {
+            "name": app.current_city,
+            "lat": lat,
+            "lon": lon,
+        },
+        "now": rows.get(app.timeline_index).map(row_json),
+        "summary": summary_lines(app),
+        "hourly": upcoming_rows(app, now).iter().map(row_json).collect::<Vec<_>>(),
+        "daily": app
+            .weather_data
+            .as_ref()
+            .map(|weather| daily::daily_rows(weather, &Local))
+            .unwrap_or_default()
+            .iter()
+            .filter(|day| day.date >= today)
+            .collect::<Vec<_>>(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    // Between the fixture's last observation and its first forecast
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-10-18T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn parse(args: &[&str]) -> Result<CliCommand, String> {
+        let args = ["rusty-wx"].iter().chain(args);
+        Cli::try_parse_from(args)
+            .map(|cli| cli.command.unwrap())
+            .map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn arguments() {
+        let Ok(CliCommand::Hourly { location, hours }) = parse(&[
+            "hourly", "--lat", "39.74", "--lon", "-104.99", "--hours", "12",
+        ]) else {
+            panic!("not an hourly command");
+        };
+        assert_eq!((location.lat, location.lon, hours), (39.74, -104.99, 12));
+        assert_eq!(location.name, None);
+
+        let Ok(CliCommand::Export { overlays, .. }) = parse(&[
+            "export",
+            "--lat",
+            "0",
+            "--lon",
+            "0",
+            "--overlay",
+            "gust",
+            "--overlay",
+            "precip",
+            "-o",
+            "out.svg",
+        ]) else {
+            panic!("not an export command");
+        };
+        assert_eq!(
+            overlays,
+            [
+                WeatherElement::WindGust,
+                WeatherElement::PrecipitationProbability
+            ]
+        );
+
+        let error = |args: &[&str]| parse(args).err().unwrap();
+        let export = [
+            "export",
+            "--lat",
+            "0",
+            "--lon",
+            "0",
+            "-o",
+            "out.svg",
+            "--overlay",
+        ];
+        let overlay = |element| [&export[..], &[element]].concat();
+        assert!(error(&overlay("temperature")).contains("isn't an overlay"));
+        assert!(error(&overlay("rain")).contains("unknown element 'rain'"));
+        assert!(error(&["daily", "--lat", "39.74"]).contains("--lon"));
+    }
+
+    #[test]
+    fn interval_is_at_least_a_minute() {
+        let config = Config::default();
+        assert_eq!(
+            refresh_interval(Some(0), &config),
+            std::time::Duration::from_secs(60)
+        );
+        assert_eq!(
+            refresh_interval(Some(15), &config),
+            std::time::Duration::from_secs(900)
+        );
+        assert_eq!(refresh_interval(None, &config), config.refresh_interval());
+    }
+
+    #[test]
+    fn json_report_of_the_fixture() {
+        let mut app = WeatherApp::for_location("Denver", serde_json::from_str(FIXTURE).unwrap());
+        // The last observation, whatever the clock says
+        app.timeline_index = 23;
+        let report = json_report(&app, now());
+
+        assert_eq!(
+            report["location"],
+            json!({ "name": "Denver", "lat": 39.74, "lon": -104.99 })
+        );
+        assert_eq!(report["now"]["valid_date"], "2026-10-18T12:00:00Z");
+        assert_eq!(report["now"]["kind"], "observation");
+        assert!(report["now"]["wbgt_f"].is_null());
+
+        // From the hour closest to now to the end of the forecast
+        let hourly = report["hourly"].as_array().unwrap();
+        assert_eq!(hourly.len(), 73);
+        assert_eq!(hourly[0]["valid_date"], "2026-10-18T12:00:00Z");
+        assert_eq!(hourly[1]["kind"], "forecast");
+        assert_eq!(
+            hourly[1].as_object().unwrap().len(),
+            2 + TableColumn::ALL.len()
+        );
+
+        let daily = report["daily"].as_array().unwrap();
+        let today = now().with_timezone(&Local).date_naive().to_string();
+        assert_eq!(daily[0]["date"], today);
+        assert!(daily[0]["high_f"].is_number());
+    }
+}
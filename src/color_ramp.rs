@@ -0,0 +1,237 @@
+use iced::Color;
+use serde::{Deserialize, Serialize};
+
+// Built-in temperature color maps; Custom uses the stops from the config file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Palette {
+    #[default]
+    Classic,
+    Viridis,
+    Magma,
+    // Colorblind-safe (blue-yellow, readable with red-green deficiencies)
+    Cividis,
+    Custom,
+}
+
+impl Palette {
+    pub const ALL: [Palette; 5] = [
+        Palette::Classic,
+        Palette::Viridis,
+        Palette::Magma,
+        Palette::Cividis,
+        Palette::Custom,
+    ];
+}
+
+impl std::fmt::Display for Palette {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Palette::Classic => "Classic",
+            Palette::Viridis => "Viridis",
+            Palette::Magma => "Magma",
+            Palette::Cividis => "Cividis (colorblind-safe)",
+            Palette::Custom => "Custom",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorStop {
+    // Temperature in °F
+    pub value: f64,
+    // "#rrggbb"
+    pub color: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorRampConfig {
+    #[serde(default)]
+    pub palette: Palette,
+    // Temperatures mapped to the ends of the built-in uniform palettes
+    #[serde(default = "default_ramp_min")]
+    pub min: f64,
+    #[serde(default = "default_ramp_max")]
+    pub max: f64,
+    // Used when palette is Custom
+    #[serde(default)]
+    pub stops: Vec<ColorStop>,
+}
+
+fn default_ramp_min() -> f64 {
+    0.0
+}
+
+fn default_ramp_max() -> f64 {
+    100.0
+}
+
+impl Default for ColorRampConfig {
+    fn default() -> Self {
+        Self {
+            palette: Palette::default(),
+            min: default_ramp_min(),
+            max: default_ramp_max(),
+            stops: Vec::new(),
+        }
+    }
+}
+
+// A continuous temperature to color mapping with stops in °F
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColorRamp {
+    stops: Vec<(f64, Color)>,
+}
+
+impl Default for ColorRamp {
+    fn default() -> Self {
+        Self::from_config(&ColorRampConfig::default())
+    }
+}
+
+impl ColorRamp {
+    pub fn from_config(config: &ColorRampConfig) -> Self {
+        let (min_f, max_f) = (config.min, config.max);
+
+        let mut stops: Vec<(f64, Color)> = match config.palette {
+            // The original banded ladder, anchored mid-band and blended between bands
+            Palette::Classic => vec![
+                (20.0, Color::from_rgb(0.0, 0.0, 1.0)),
+                (41.0, Color::from_rgb(0.0, 0.5, 1.0)),
+                (57.5, Color::from_rgb(0.0, 0.8, 0.8)),
+                (70.0, Color::from_rgb(0.0, 1.0, 0.0)),
+                (80.0, Color::from_rgb(1.0, 1.0, 0.0)),
+                (90.0, Color::from_rgb(1.0, 0.5, 0.0)),
+                (100.0, Color::from_rgb(1.0, 0.0, 0.0)),
+            ],
+            Palette::Viridis => normalized(VIRIDIS, min_f, max_f),
+            Palette::Magma => normalized(MAGMA, min_f, max_f),
+            Palette::Cividis => normalized(CIVIDIS, min_f, max_f),
+            Palette::Custom => config
+                .stops
+                .iter()
+                .filter_map(|stop| {
+                    let color = parse_hex_color(&stop.color)?;
+                    Some((stop.value, color))
+                })
+                .collect(),
+        };
+
+        // A custom ramp with no usable stops falls back to the classic ladder
+        if stops.is_empty() {
+            return Self::from_config(&ColorRampConfig {
+                palette: Palette::Classic,
+                ..config.clone()
+            });
+        }
+
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { stops }
+    }
+
+    // Color for a temperature in °F, clamped to the end stops
+    pub fn color_at(&self, temp_f: f64) -> Color {
+        let first = self.stops[0];
+        let last = self.stops[self.stops.len() - 1];
+        if temp_f <= first.0 {
+            return first.1;
+        }
+        if temp_f >= last.0 {
+            return last.1;
+        }
+
+        for pair in self.stops.windows(2) {
+            let (t1, c1) = pair[0];
+            let (t2, c2) = pair[1];
+            if temp_f <= t2 {
+                let ratio = if t2 > t1 {
+                    ((temp_f - t1) / (t2 - t1)) as f32
+                } else {
+                    1.0
+                };
+                return Color::from_rgb(
+                    c1.r + (c2.r - c1.r) * ratio,
+                    c1.g + (c2.g - c1.g) * ratio,
+                    c1.b + (c2.b - c1.b) * ratio,
+                );
+            }
+        }
+        last.1
+    }
+}
+
+fn normalized(palette: &[(f64, &str)], min_f: f64, max_f: f64) -> Vec<(f64, Color)> {
+    palette
+        .iter()
+        .filter_map(|(t, hex)| Some((min_f + t * (max_f - min_f), parse_hex_color(hex)?)))
+        .collect()
+}
+
+pub fn parse_hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.trim().trim_start_matches('#');
+    // Checking the digits first also keeps the slicing below on character boundaries
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+// Samples of the matplotlib perceptually uniform color maps
+const VIRIDIS: &[(f64, &str)] = &[
+    (0.0, "#440154"),
+    (0.125, "#472d7b"),
+    (0.25, "#3b528b"),
+    (0.375, "#2c728e"),
+    (0.5, "#21918c"),
+    (0.625, "#28ae80"),
+    (0.75, "#5ec962"),
+    (0.875, "#addc30"),
+    (1.0, "#fde725"),
+];
+
+const MAGMA: &[(f64, &str)] = &[
+    (0.0, "#000004"),
+    (0.125, "#1c1044"),
+    (0.25, "#4f127b"),
+    (0.375, "#812581"),
+    (0.5, "#b5367a"),
+    (0.625, "#e55064"),
+    (0.75, "#fb8761"),
+    (0.875, "#fec287"),
+    (1.0, "#fcfdbf"),
+];
+
+const CIVIDIS: &[(f64, &str)] = &[
+    (0.0, "#00224e"),
+    (0.125, "#123570"),
+    (0.25, "#3b496c"),
+    (0.375, "#575d6d"),
+    (0.5, "#707173"),
+    (0.625, "#8a8779"),
+    (0.75, "#a69d75"),
+    (0.875, "#c4b56c"),
+    (1.0, "#fee838"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors() {
+        assert_eq!(
+            parse_hex_color(" #FF8000 "),
+            Some(Color::from_rgb8(255, 128, 0))
+        );
+        assert_eq!(
+            parse_hex_color("21918c"),
+            Some(Color::from_rgb8(33, 145, 140))
+        );
+        for invalid in [
+            "", "#fff", "#12345g", "#+1+2+3", "#ééé", "#aébcd", "#1234567",
+        ] {
+            assert_eq!(parse_hex_color(invalid), None, "{:?}", invalid);
+        }
+    }
+}
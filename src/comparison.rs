@@ -0,0 +1,305 @@
+use crate::app::{Message, WeatherApp};
+use crate::config::SavedLocation;
+use crate::meteogram::{draw_day_backgrounds, time_bounds, time_to_x};
+use crate::render::DrawTarget;
+use crate::theme::ChartColors;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Timelike, Utc};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    widget::canvas::{self, Frame},
+    Color, Point, Rectangle, Size, Theme,
+};
+use rusty_wx::element::{format_scale_value, WeatherElement};
+use rusty_wx::weather::{ApiWeatherData, WeatherDataPoint};
+
+// Room on the left of the chart for the value labels
+const SCALE_WIDTH: f32 = 45.0;
+const TOP_MARGIN: f32 = 40.0;
+const BOTTOM_MARGIN: f32 = 10.0;
+
+// Distinct line colors for up to ten sites, repeating after that
+const SITE_COLORS: [(f32, f32, f32); 10] = [
+    (0.12, 0.47, 0.71),
+    (1.0, 0.5, 0.05),
+    (0.17, 0.63, 0.17),
+    (0.84, 0.15, 0.16),
+    (0.58, 0.4, 0.74),
+    (0.55, 0.34, 0.29),
+    (0.89, 0.47, 0.76),
+    (0.5, 0.5, 0.5),
+    (0.74, 0.74, 0.13),
+    (0.09, 0.75, 0.81),
+];
+
+pub fn site_color(index: usize) -> Color {
+    let (r, g, b) = SITE_COLORS[index % SITE_COLORS.len()];
+    Color::from_rgb(r, g, b)
+}
+
+// One saved location in the comparison; `site` is None until its fetch finishes
+pub struct ComparisonSite {
+    pub location: SavedLocation,
+    pub site: Option<WeatherApp>,
+    pub error: Option<String>,
+}
+
+// Several locations fetched side by side, plotted on one time axis
+pub struct Comparison {
+    pub sites: Vec<ComparisonSite>,
+    pub element: WeatherElement,
+}
+
+impl Comparison {
+    pub fn new(locations: &[SavedLocation], element: WeatherElement) -> Self {
+        Self {
+            sites: locations
+                .iter()
+                .map(|location| ComparisonSite {
+                    location: location.clone(),
+                    site: None,
+                    error: None,
+                })
+                .collect(),
+            element,
+        }
+    }
+
+    pub fn set_result(&mut self, index: usize, result: Result<ApiWeatherData, String>) {
+        let Some(entry) = self.sites.get_mut(index) else {
+            return;
+        };
+        match result {
+            Ok(data) => {
+                entry.site = Some(WeatherApp::for_location(&entry.location.name, data));
+                entry.error = None;
+            }
+            Err(e) => entry.error = Some(e),
+        }
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.sites
+            .iter()
+            .any(|entry| entry.site.is_none() && entry.error.is_none())
+    }
+
+    pub fn chart(&self) -> ComparisonChart {
+        let series = self
+            .sites
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| {
+                let site = entry.site.as_ref()?;
+                let values = site
+                    .combined_timeline
+                    .iter()
+                    .filter_map(|point| {
+                        let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+                        let value = site.get_element_value_for_scale(point, &self.element)?;
+                        Some((time.timestamp(), value))
+                    })
+                    .collect();
+                Some((site_color(i), values))
+            })
+            .collect();
+
+        // Every site's points on one sorted axis, for the shared day shading
+        let mut axis: Vec<WeatherDataPoint> = self
+            .sites
+            .iter()
+            .filter_map(|entry| entry.site.as_ref())
+            .flat_map(|site| site.combined_timeline.iter().cloned())
+            .collect();
+        let timestamp = |point: &WeatherDataPoint| {
+            DateTime::parse_from_rfc3339(point.valid_date())
+                .map(|dt| dt.timestamp())
+                .unwrap_or(0)
+        };
+        axis.sort_by_key(timestamp);
+        axis.dedup_by_key(|point| timestamp(point));
+
+        ComparisonChart {
+            axis,
+            series,
+            element: self.element.clone(),
+            now: Utc::now().timestamp(),
+        }
+    }
+
+    // Upcoming dates in `tz`, for the columns of the highs and lows table
+    pub fn table_dates<Tz: TimeZone>(&self, tz: &Tz) -> Vec<NaiveDate> {
+        let today = Utc::now().with_timezone(tz).date_naive();
+        let mut dates: Vec<NaiveDate> = self
+            .sites
+            .iter()
+            .filter_map(|entry| entry.site.as_ref())
+            .flat_map(|site| {
+                daily_extremes(site, tz)
+                    .into_iter()
+                    .map(|(date, _, _)| date)
+            })
+            .filter(|date| *date >= today)
+            .collect();
+        dates.sort();
+        dates.dedup();
+        dates.truncate(7);
+        dates
+    }
+}
+
+// (date, high, low) of the temperature for each day of `tz` in a site's timeline
+pub fn daily_extremes<Tz: TimeZone>(site: &WeatherApp, tz: &Tz) -> Vec<(NaiveDate, f64, f64)> {
+    let mut days: Vec<(NaiveDate, f64, f64)> = Vec::new();
+    for point in &site.combined_timeline {
+        let (Ok(dt), Some(temp)) = (
+            DateTime::parse_from_rfc3339(point.valid_date()),
+            point.temperature(),
+        ) else {
+            continue;
+        };
+        let date = dt.with_timezone(tz).date_naive();
+        match days.iter_mut().find(|(d, _, _)| *d == date) {
+            Some((_, high, low)) => {
+                *high = high.max(temp);
+                *low = low.min(temp);
+            }
+            None => days.push((date, temp, temp)),
+        }
+    }
+    days
+}
+
+pub struct ComparisonChart {
+    axis: Vec<WeatherDataPoint>,
+    // (line color, (timestamp, value)) per site
+    series: Vec<(Color, Vec<(i64, f64)>)>,
+    element: WeatherElement,
+    now: i64,
+}
+
+impl ComparisonChart {
+    // Draw the "now" line at this timestamp instead of the current time
+    #[cfg(test)]
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
+    fn value_range(&self) -> Option<(f64, f64)> {
+        let values = self
+            .series
+            .iter()
+            .flat_map(|(_, values)| values.iter().map(|(_, v)| *v));
+        let (min, max) = values.fold((f64::MAX, f64::MIN), |(min, max), v| {
+            (min.min(v), max.max(v))
+        });
+        if min > max {
+            return None;
+        }
+        let padding = ((max - min) * 0.1).max(1.0);
+        Some((min - padding, max + padding))
+    }
+
+    pub fn render<T: DrawTarget>(&self, target: &mut T, colors: &ChartColors, bounds: Size) {
+        draw_day_backgrounds(target, colors, &self.axis, None, bounds);
+
+        let (Some((start, end)), Some((min, max))) = (time_bounds(&self.axis), self.value_range())
+        else {
+            return;
+        };
+        let plot_width = bounds.width - SCALE_WIDTH;
+        let plot_height = bounds.height - TOP_MARGIN - BOTTOM_MARGIN;
+        let x_at = |time: i64| SCALE_WIDTH + time_to_x(time, start, end, plot_width);
+        let y_at =
+            |value: f64| TOP_MARGIN + (1.0 - ((value - min) / (max - min)) as f32) * plot_height;
+
+        // Day and hour labels along the top
+        for point in &self.axis {
+            let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+                continue;
+            };
+            let local = dt.with_timezone(&Local);
+            if local.minute() != 0 || local.hour() % 6 != 0 {
+                continue;
+            }
+            let x = x_at(dt.timestamp());
+            if local.hour() == 0 {
+                target.text(
+                    &local.format("%a %-m/%-d").to_string(),
+                    Point::new(x, 2.0),
+                    13.0,
+                    colors.label,
+                    Horizontal::Center,
+                    Vertical::Top,
+                );
+            }
+            target.text(
+                &local.format("%-I%p").to_string(),
+                Point::new(x, 20.0),
+                11.0,
+                colors.text,
+                Horizontal::Center,
+                Vertical::Top,
+            );
+        }
+
+        // Value scale on the left
+        let label_count = 5;
+        for i in 0..=label_count {
+            let value = max - (i as f64 / label_count as f64) * (max - min);
+            let label = if self.element.shares_temp_axis() {
+                format!("{:.0}°", value)
+            } else {
+                format_scale_value(&self.element, value)
+            };
+            target.text(
+                &label,
+                Point::new(SCALE_WIDTH - 5.0, y_at(value)),
+                12.0,
+                colors.text,
+                Horizontal::Right,
+                Vertical::Center,
+            );
+        }
+
+        for (color, values) in &self.series {
+            let color = colors.series(*color);
+            for pair in values.windows(2) {
+                target.line(
+                    Point::new(x_at(pair[0].0), y_at(pair[0].1)),
+                    Point::new(x_at(pair[1].0), y_at(pair[1].1)),
+                    color,
+                    2.0,
+                );
+            }
+        }
+
+        // "Now" line, matching the meteogram, when now is on the chart
+        if (start..=end).contains(&self.now) {
+            let now_x = x_at(self.now);
+            target.line(
+                Point::new(now_x, TOP_MARGIN),
+                Point::new(now_x, bounds.height - BOTTOM_MARGIN),
+                colors.accent,
+                2.0,
+            );
+        }
+    }
+}
+
+impl canvas::Program<Message> for ComparisonChart {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        self.render(&mut frame, &ChartColors::from_theme(theme), bounds.size());
+        vec![frame.into_geometry()]
+    }
+}
@@ -1,13 +1,16 @@
-use crate::app::{Message, WeatherApp, WeatherElement};
-use crate::weather::{self, WeatherDataPoint};
+use crate::app::{Message, WeatherApp};
+use crate::theme::QC_COLOR;
 use chrono::{DateTime, Local, Utc};
 use iced::{
     alignment, theme,
-    widget::{button, column, container, row, text, Space},
+    widget::{button, column, container, row, text, tooltip, Button, Space, Text},
     Alignment, Color, Element, Length,
 };
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::QcFlag;
+use rusty_wx::weather::{self, WeatherDataPoint};
 
-pub fn create_status_display(app: &WeatherApp) -> Element<Message> {
+pub fn create_status_display(app: &WeatherApp) -> Element<'_, Message> {
     let status_text = if app.loading {
         text("Loading weather data...").size(18)
     } else if let Some(error) = &app.error {
@@ -33,9 +36,18 @@ pub fn create_temperature_card<'a>(
 ) -> Element<'a, Message> {
     let city_text = text(&app.current_city).size(24);
     let temp_text = match data_point.temperature() {
-        Some(temp) => text(format!("{:.0}°", temp)).size(42),
+        Some(temp) => text(format!("{:.0}°", temp))
+            .size(42)
+            .style(app.config.temperature_ramp().color_at(temp)),
         None => text("--°").size(42),
     };
+    // Why the temperature is suspect, if it failed a quality check
+    let qc_note = app
+        .qc_flag(data_point, &WeatherElement::Temperature)
+        .map(|flag| text(format!("⚠ {}", flag.reason)).size(12).style(QC_COLOR));
+    let temp_display = column![temp_text]
+        .push_maybe(qc_note)
+        .align_items(Alignment::Center);
 
     // Data type badge
     let data_badge = match data_point {
@@ -105,18 +117,31 @@ pub fn create_temperature_card<'a>(
         .width(Length::Fixed(90.0))
     };
 
-    let now_button = button(text("Now").size(14).horizontal_alignment(alignment::Horizontal::Center))
-        .on_press(Message::GoToNow)
-        .style(theme::Button::Secondary)
-        .width(Length::Fixed(70.0));
+    let now_button = button(
+        text("Now")
+            .size(14)
+            .horizontal_alignment(alignment::Horizontal::Center),
+    )
+    .on_press(Message::GoToNow)
+    .style(theme::Button::Secondary)
+    .width(Length::Fixed(70.0));
 
     let time_nav = row![prev_button, now_button, next_button].spacing(8);
 
-    // API response time
+    // API response time, marked stale when the last refresh failed
     let api_time = if let Some(updated) = &app.last_updated {
-        text(format!("Updated: {}", updated.format("%I:%M:%S %p")))
+        match &app.error {
+            Some(error) => text(format!(
+                "Updated: {} · refresh failed: {}",
+                updated.format("%I:%M:%S %p"),
+                error
+            ))
             .size(12)
-            .style(Color::from_rgb(0.5, 0.5, 0.5))
+            .style(Color::from_rgb(0.8, 0.2, 0.2)),
+            None => text(format!("Updated: {}", updated.format("%I:%M:%S %p")))
+                .size(12)
+                .style(Color::from_rgb(0.5, 0.5, 0.5)),
+        }
     } else {
         text("").size(12)
     };
@@ -149,7 +174,7 @@ pub fn create_temperature_card<'a>(
             api_time,
             cycle_time,
             Space::with_height(Length::Fixed(10.0)),
-            temp_text,
+            temp_display,
             Space::with_height(Length::Fixed(10.0)),
             time_info,
             Space::with_height(Length::Fill),
@@ -214,17 +239,24 @@ pub fn create_weather_grid<'a>(
     };
 
     // Create all weather cards using simplified functions
-    weather_cards.push(create_wind_card(wind_spd, wind_dir, wind_gust, app));
-    weather_cards.push(create_solar_flux_card(solar_flux, app));
-    weather_cards.push(create_cloud_cover_card(cloud_cover, cloud_ceiling, app));
-    weather_cards.push(create_visibility_card(visibility, app));
+    weather_cards.push(create_wind_card(
+        wind_spd, wind_dir, wind_gust, data_point, app,
+    ));
+    weather_cards.push(create_solar_flux_card(solar_flux, data_point, app));
+    weather_cards.push(create_cloud_cover_card(
+        cloud_cover,
+        cloud_ceiling,
+        data_point,
+        app,
+    ));
+    weather_cards.push(create_visibility_card(visibility, data_point, app));
     weather_cards.push(create_apparent_temp_card(data_point, app));
     weather_cards.push(create_dewpoint_card(data_point, app));
     weather_cards.push(create_wbgt_card(wbg_temp, app));
     weather_cards.push(create_humidity_card(data_point, app));
     weather_cards.push(create_thunderstorm_card(thunder_pct, app));
     weather_cards.push(create_cape_card(cape, app));
-    weather_cards.push(create_pressure_card(pressure, app));
+    weather_cards.push(create_pressure_card(pressure, data_point, app));
     weather_cards.push(create_precipitation_card(app, data_point));
 
     // Arrange cards in rows of 4
@@ -258,19 +290,45 @@ pub fn create_weather_grid<'a>(
     weather_grid.into()
 }
 
-fn create_wind_card(
+// A card title, marked in the QC color when the value failed a quality check
+fn card_title<'a>(label: &str, style: theme::Text, flag: Option<&QcFlag>) -> Text<'a> {
+    match flag {
+        Some(_) => text(format!("{} ⚠", label)).size(14).style(QC_COLOR),
+        None => text(label).size(14).style(style),
+    }
+}
+
+// Hovering a flagged card shows why the value was flagged
+fn with_qc_tooltip<'a>(card: Button<'a, Message>, flag: Option<&QcFlag>) -> Element<'a, Message> {
+    match flag {
+        Some(flag) => tooltip(
+            card,
+            text(format!("Suspect value: {}", flag.reason)).size(12),
+            tooltip::Position::Top,
+        )
+        .style(theme::Container::Box)
+        .into(),
+        None => card.into(),
+    }
+}
+
+fn create_wind_card<'a>(
     wind_spd: Option<f64>,
     wind_dir: Option<f64>,
     wind_gust: Option<f64>,
-    app: &WeatherApp,
-) -> Element<Message> {
+    data_point: &WeatherDataPoint,
+    app: &'a WeatherApp,
+) -> Element<'a, Message> {
+    let flag = app
+        .qc_flag(data_point, &WeatherElement::WindSpeed)
+        .or_else(|| app.qc_flag(data_point, &WeatherElement::WindGust));
     let has_data = wind_spd.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Wind").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Wind", dimmed_color, flag)].spacing(4);
 
     if let Some(spd) = wind_spd {
         content = content.push(text(format!("{:.0} mph", spd)).size(20));
@@ -290,33 +348,40 @@ fn create_wind_card(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::WindSpeed {
+    let card_style = if app.is_element_selected(&WeatherElement::WindSpeed) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(10)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(10)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::WindSpeed))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::WindSpeed))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
-fn create_solar_flux_card(solar_flux: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_solar_flux_card<'a>(
+    solar_flux: Option<f64>,
+    data_point: &WeatherDataPoint,
+    app: &'a WeatherApp,
+) -> Element<'a, Message> {
+    let flag = app.qc_flag(data_point, &WeatherElement::SolarFlux);
     let has_data = solar_flux.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Solar Flux").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Solar Flux", dimmed_color, flag)].spacing(4);
 
     if let Some(solar) = solar_flux {
         content = content.push(text(format!("{:.0}", solar)).size(24));
@@ -325,38 +390,42 @@ fn create_solar_flux_card(solar_flux: Option<f64>, app: &WeatherApp) -> Element<
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::SolarFlux {
+    let card_style = if app.is_element_selected(&WeatherElement::SolarFlux) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(10)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(10)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::SolarFlux))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::SolarFlux))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
 // Simplified versions of remaining card functions
-fn create_cloud_cover_card(
+fn create_cloud_cover_card<'a>(
     cloud_cover: Option<f64>,
     cloud_ceiling: Option<f64>,
-    app: &WeatherApp,
-) -> Element<Message> {
+    data_point: &WeatherDataPoint,
+    app: &'a WeatherApp,
+) -> Element<'a, Message> {
+    let flag = app.qc_flag(data_point, &WeatherElement::CloudCover);
     let has_data = cloud_cover.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Cloud Cover").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Cloud Cover", dimmed_color, flag)].spacing(4);
 
     if let Some(cloud) = cloud_cover {
         content = content.push(text(format!("{:.0}%", cloud)).size(24));
@@ -367,33 +436,40 @@ fn create_cloud_cover_card(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::CloudCover {
+    let card_style = if app.is_element_selected(&WeatherElement::CloudCover) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::CloudCover))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::CloudCover))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
-fn create_visibility_card(visibility: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_visibility_card<'a>(
+    visibility: Option<f64>,
+    data_point: &WeatherDataPoint,
+    app: &'a WeatherApp,
+) -> Element<'a, Message> {
+    let flag = app.qc_flag(data_point, &WeatherElement::Visibility);
     let has_data = visibility.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Visibility").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Visibility", dimmed_color, flag)].spacing(4);
 
     if let Some(vis) = visibility {
         content = content.push(text(format!("{:.1}", vis / 1609.34)).size(24));
@@ -402,23 +478,25 @@ fn create_visibility_card(visibility: Option<f64>, app: &WeatherApp) -> Element<
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::Visibility {
+    let card_style = if app.is_element_selected(&WeatherElement::Visibility) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::Visibility))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::Visibility))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
 fn create_apparent_temp_card<'a>(
@@ -460,13 +538,14 @@ fn create_apparent_temp_card<'a>(
         }
     };
 
+    let flag = app.qc_flag(data_point, &WeatherElement::ApparentTemperature);
     let has_data = apparent_temp.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Apparent Temp").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Apparent Temp", dimmed_color, flag)].spacing(4);
 
     if let Some(feels_like) = apparent_temp {
         content = content.push(text(format!("{:.0}°F", feels_like)).size(24));
@@ -489,25 +568,27 @@ fn create_apparent_temp_card<'a>(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::ApparentTemperature {
+    let card_style = if app.is_element_selected(&WeatherElement::ApparentTemperature) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(
+            WeatherElement::ApparentTemperature,
+        ))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(
-        WeatherElement::ApparentTemperature,
-    ))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
 fn create_dewpoint_card<'a>(
@@ -532,13 +613,14 @@ fn create_dewpoint_card<'a>(
         WeatherDataPoint::Forecast(fc) => fc.dewpoint_2m_f,
     };
 
+    let flag = app.qc_flag(data_point, &WeatherElement::Dewpoint);
     let has_data = dewpoint_value.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Dewpoint").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Dewpoint", dimmed_color, flag)].spacing(4);
 
     if let Some(dewpoint) = dewpoint_value {
         content = content.push(text(format!("{:.0}°F", dewpoint)).size(24));
@@ -546,31 +628,33 @@ fn create_dewpoint_card<'a>(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::Dewpoint {
+    let card_style = if app.is_element_selected(&WeatherElement::Dewpoint) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::Dewpoint))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::Dewpoint))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
-fn create_wbgt_card(wbg_temp: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_wbgt_card(wbg_temp: Option<f64>, app: &WeatherApp) -> Element<'_, Message> {
     let has_data = wbg_temp.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
     let mut content = column![text("WBGT").size(14).style(dimmed_color)].spacing(4);
 
@@ -580,7 +664,7 @@ fn create_wbgt_card(wbg_temp: Option<f64>, app: &WeatherApp) -> Element<Message>
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::WBGT {
+    let card_style = if app.is_element_selected(&WeatherElement::WBGT) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
@@ -622,13 +706,14 @@ fn create_humidity_card<'a>(
         }
     };
 
+    let flag = app.qc_flag(data_point, &WeatherElement::Humidity);
     let has_data = humidity_value.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Humidity").size(14).style(dimmed_color)].spacing(4);
+    let mut content = column![card_title("Humidity", dimmed_color, flag)].spacing(4);
 
     if let Some(humidity) = humidity_value {
         content = content.push(text(format!("{:.0}%", humidity)).size(24));
@@ -636,31 +721,33 @@ fn create_humidity_card<'a>(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::Humidity {
+    let card_style = if app.is_element_selected(&WeatherElement::Humidity) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::Humidity))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::Humidity))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
-fn create_thunderstorm_card(thunder_pct: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_thunderstorm_card(thunder_pct: Option<f64>, app: &WeatherApp) -> Element<'_, Message> {
     let has_data = thunder_pct.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
     let mut content = column![text("T-Storm Prob").size(14).style(dimmed_color)].spacing(4);
 
@@ -670,7 +757,7 @@ fn create_thunderstorm_card(thunder_pct: Option<f64>, app: &WeatherApp) -> Eleme
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::ThunderstormProbability {
+    let card_style = if app.is_element_selected(&WeatherElement::ThunderstormProbability) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
@@ -691,12 +778,12 @@ fn create_thunderstorm_card(thunder_pct: Option<f64>, app: &WeatherApp) -> Eleme
     .into()
 }
 
-fn create_cape_card(cape: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_cape_card(cape: Option<f64>, app: &WeatherApp) -> Element<'_, Message> {
     let has_data = cape.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
     let mut content = column![text("CAPE").size(14).style(dimmed_color)].spacing(4);
 
@@ -707,7 +794,7 @@ fn create_cape_card(cape: Option<f64>, app: &WeatherApp) -> Element<Message> {
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::CAPE {
+    let card_style = if app.is_element_selected(&WeatherElement::CAPE) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
@@ -726,14 +813,19 @@ fn create_cape_card(cape: Option<f64>, app: &WeatherApp) -> Element<Message> {
     .into()
 }
 
-fn create_pressure_card(pressure: Option<f64>, app: &WeatherApp) -> Element<Message> {
+fn create_pressure_card<'a>(
+    pressure: Option<f64>,
+    data_point: &WeatherDataPoint,
+    app: &'a WeatherApp,
+) -> Element<'a, Message> {
+    let flag = app.qc_flag(data_point, &WeatherElement::Pressure);
     let has_data = pressure.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
-    let mut content = column![text("Pressure").size(14).style(dimmed_color)].spacing(2);
+    let mut content = column![card_title("Pressure", dimmed_color, flag)].spacing(2);
 
     if let Some(press) = pressure {
         let press_inhg = press * 0.02953;
@@ -743,23 +835,25 @@ fn create_pressure_card(pressure: Option<f64>, app: &WeatherApp) -> Element<Mess
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::Pressure {
+    let card_style = if app.is_element_selected(&WeatherElement::Pressure) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
     };
 
-    button(
-        container(content)
-            .padding(12)
-            .width(Length::Fill)
-            .height(Length::Fixed(100.0)),
+    with_qc_tooltip(
+        button(
+            container(content)
+                .padding(12)
+                .width(Length::Fill)
+                .height(Length::Fixed(100.0)),
+        )
+        .on_press(Message::SelectWeatherElement(WeatherElement::Pressure))
+        .style(card_style)
+        .width(Length::Fill)
+        .height(Length::Fixed(100.0)),
+        flag,
     )
-    .on_press(Message::SelectWeatherElement(WeatherElement::Pressure))
-    .style(card_style)
-    .width(Length::Fill)
-    .height(Length::Fixed(100.0))
-    .into()
 }
 
 fn create_precipitation_card<'a>(
@@ -769,9 +863,9 @@ fn create_precipitation_card<'a>(
     let precip_prob = app.get_precipitation_probability(data_point.valid_date());
     let has_data = precip_prob.is_some();
     let dimmed_color = if has_data {
-        Color::from_rgb(0.0, 0.0, 0.0)
+        theme::Text::Default
     } else {
-        Color::from_rgb(0.7, 0.7, 0.7)
+        theme::Text::Color(Color::from_rgb(0.7, 0.7, 0.7))
     };
     let mut content = column![text("Precip Prob").size(14).style(dimmed_color)].spacing(4);
 
@@ -781,7 +875,7 @@ fn create_precipitation_card<'a>(
         content = content.push(text("").size(24));
     }
 
-    let card_style = if app.selected_weather_element == WeatherElement::PrecipitationProbability {
+    let card_style = if app.is_element_selected(&WeatherElement::PrecipitationProbability) {
         theme::Button::Primary
     } else {
         theme::Button::Secondary
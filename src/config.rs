@@ -0,0 +1,459 @@
+use crate::alerts::AlertRule;
+use crate::color_ramp::{ColorRamp, ColorRampConfig};
+use crate::keys::KeyBindings;
+use crate::table::TableColumn;
+use crate::theme::ThemeMode;
+use chrono::Utc;
+use rusty_wx::archive::{Archive, Retention};
+use rusty_wx::calendar::CalendarRules;
+use rusty_wx::qc::QcRules;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+// Panels that can be stacked under the shared meteogram time axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MeteogramPanel {
+    Temperature,
+    Precipitation,
+    Wind,
+    CloudCover,
+    PressureCape,
+}
+
+impl MeteogramPanel {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MeteogramPanel::Temperature => "Temperature",
+            MeteogramPanel::Precipitation => "Precipitation",
+            MeteogramPanel::Wind => "Wind",
+            MeteogramPanel::CloudCover => "Cloud Cover",
+            MeteogramPanel::PressureCape => "Pressure / CAPE",
+        }
+    }
+}
+
+// A location with a button in the header, selectable with the digit keys
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedLocation {
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+}
+
+// Calendar export thresholds, and whether to rewrite the file after every fetch
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CalendarConfig {
+    #[serde(flatten)]
+    pub rules: CalendarRules,
+    #[serde(default)]
+    pub auto_export: bool,
+    // Fixed file for auto export, so calendar apps can subscribe to it;
+    // defaults to rusty-wx.ics in the config directory
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+// Broker and topics for `rusty-wx mqtt`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttConfig {
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub client_id: String,
+    // Home Assistant listens for discovery configs under this prefix
+    pub discovery_prefix: String,
+    // States go to <base_topic>/<location>/state, availability to <base_topic>/status
+    pub base_topic: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            host: "localhost".to_string(),
+            port: 1883,
+            username: None,
+            password: None,
+            client_id: "rusty-wx".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+            base_topic: "rusty-wx".to_string(),
+        }
+    }
+}
+
+// Observation quality checks and what to do with the values they flag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QcConfig {
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub rules: QcRules,
+    // Leave flagged values out of the temperature axis range, so one bad reading
+    // doesn't squash the rest of the meteogram
+    pub exclude_from_scales: bool,
+}
+
+impl Default for QcConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            rules: QcRules::default(),
+            exclude_from_scales: false,
+        }
+    }
+}
+
+// The SQLite archive of every fetch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    pub enabled: bool,
+    // Defaults to archive.sqlite3 in the config directory
+    pub path: Option<PathBuf>,
+    #[serde(flatten)]
+    pub retention: Retention,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            path: None,
+            retention: Retention::default(),
+        }
+    }
+}
+
+impl ArchiveConfig {
+    pub fn path(&self) -> PathBuf {
+        self.path
+            .clone()
+            .unwrap_or_else(|| config_dir().join("archive.sqlite3"))
+    }
+
+    // Open the archive and drop what the retention policy no longer keeps, or None when
+    // disabled or it can't be opened
+    pub fn open(&self) -> Option<Archive> {
+        if !self.enabled {
+            return None;
+        }
+        let path = self.path();
+        if let Some(dir) = path.parent() {
+            let _ = std::fs::create_dir_all(dir);
+        }
+        let mut archive = match Archive::open(&path) {
+            Ok(archive) => archive,
+            Err(e) => {
+                eprintln!("Failed to open the archive {}: {}", path.display(), e);
+                return None;
+            }
+        };
+        if let Err(e) = archive.prune(&self.retention, Utc::now().timestamp()) {
+            eprintln!("Failed to prune the archive: {}", e);
+        }
+        Some(archive)
+    }
+}
+
+impl CalendarConfig {
+    pub fn auto_export_path(&self) -> PathBuf {
+        self.path
+            .clone()
+            .unwrap_or_else(|| config_dir().join("rusty-wx.ics"))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PanelConfig {
+    pub panel: MeteogramPanel,
+    pub visible: bool,
+}
+
+// User settings persisted between runs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    // Panels in display order, top to bottom
+    #[serde(default = "default_panels")]
+    pub meteogram_panels: Vec<PanelConfig>,
+    // Where the Export buttons write files; defaults to ~/Pictures, or ~/Documents for data
+    #[serde(default)]
+    pub export_dir: Option<PathBuf>,
+    #[serde(default)]
+    pub color_ramp: ColorRampConfig,
+    #[serde(default)]
+    pub theme: ThemeMode,
+    #[serde(default = "default_locations")]
+    pub locations: Vec<SavedLocation>,
+    // Overrides on top of the default key bindings
+    #[serde(default)]
+    pub key_bindings: KeyBindings,
+    // Threshold rules checked on every fetch
+    #[serde(default)]
+    pub alerts: Vec<AlertRule>,
+    // Columns shown in the hourly table
+    #[serde(default = "default_table_columns")]
+    pub table_columns: Vec<TableColumn>,
+    #[serde(default)]
+    pub calendar: CalendarConfig,
+    #[serde(default)]
+    pub mqtt: MqttConfig,
+    #[serde(default)]
+    pub qc: QcConfig,
+    // Earlier forecast runs drawn behind the temperature line when Past Runs is on
+    #[serde(default = "default_previous_runs")]
+    pub previous_runs: usize,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    // Minutes between fetches in the GUI and every long-running mode
+    #[serde(default = "default_refresh_minutes")]
+    pub refresh_minutes: u64,
+    // Set when the file couldn't be read, so the first save moves it aside instead of
+    // overwriting the user's settings with the defaults
+    #[serde(skip)]
+    pub invalid_file: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            meteogram_panels: default_panels(),
+            export_dir: None,
+            color_ramp: ColorRampConfig::default(),
+            theme: ThemeMode::default(),
+            locations: default_locations(),
+            key_bindings: KeyBindings::new(),
+            alerts: Vec::new(),
+            table_columns: default_table_columns(),
+            calendar: CalendarConfig::default(),
+            mqtt: MqttConfig::default(),
+            qc: QcConfig::default(),
+            previous_runs: default_previous_runs(),
+            archive: ArchiveConfig::default(),
+            refresh_minutes: default_refresh_minutes(),
+            invalid_file: false,
+        }
+    }
+}
+
+fn default_table_columns() -> Vec<TableColumn> {
+    TableColumn::ALL.to_vec()
+}
+
+fn default_previous_runs() -> usize {
+    3
+}
+
+fn default_refresh_minutes() -> u64 {
+    10
+}
+
+fn default_locations() -> Vec<SavedLocation> {
+    [
+        ("Denver", 39.7392, -104.9903),
+        ("Los Angeles", 34.0522, -118.2437),
+        ("Miami", 25.7617, -80.1918),
+        ("New York", 40.7128, -74.0060),
+    ]
+    .into_iter()
+    .map(|(name, lat, lon)| SavedLocation {
+        name: name.to_string(),
+        lat,
+        lon,
+    })
+    .collect()
+}
+
+fn default_panels() -> Vec<PanelConfig> {
+    vec![
+        PanelConfig {
+            panel: MeteogramPanel::Temperature,
+            visible: true,
+        },
+        PanelConfig {
+            panel: MeteogramPanel::Precipitation,
+            visible: true,
+        },
+        PanelConfig {
+            panel: MeteogramPanel::Wind,
+            visible: true,
+        },
+        PanelConfig {
+            panel: MeteogramPanel::CloudCover,
+            visible: false,
+        },
+        PanelConfig {
+            panel: MeteogramPanel::PressureCape,
+            visible: false,
+        },
+    ]
+}
+
+impl Config {
+    // Load the config file, falling back to defaults if it is missing or invalid
+    pub fn load() -> Self {
+        Self::load_from(&config_path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let mut config = match std::fs::read_to_string(path) {
+            Ok(contents) => match serde_json::from_str::<Config>(&contents) {
+                Ok(config) => config,
+                Err(e) => {
+                    eprintln!(
+                        "Ignoring invalid config {}: {} (it is kept as {} on the next save)",
+                        path.display(),
+                        e,
+                        backup_path(path).display()
+                    );
+                    Config {
+                        invalid_file: true,
+                        ..Config::default()
+                    }
+                }
+            },
+            Err(_) => Config::default(),
+        };
+        config.normalize();
+        config
+    }
+
+    pub fn save(&mut self) -> Result<(), String> {
+        self.save_to(&config_path())
+    }
+
+    pub fn save_to(&mut self, path: &Path) -> Result<(), String> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        if self.invalid_file {
+            let backup = backup_path(path);
+            std::fs::rename(path, &backup)
+                .map_err(|e| format!("Failed to move aside {}: {}", path.display(), e))?;
+            self.invalid_file = false;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        std::fs::write(path, contents).map_err(|e| e.to_string())
+    }
+
+    // Make sure every panel appears exactly once, appending any the file didn't list
+    fn normalize(&mut self) {
+        let mut seen = Vec::new();
+        self.meteogram_panels.retain(|p| {
+            if seen.contains(&p.panel) {
+                false
+            } else {
+                seen.push(p.panel);
+                true
+            }
+        });
+        for default in default_panels() {
+            if !seen.contains(&default.panel) {
+                self.meteogram_panels.push(default);
+            }
+        }
+    }
+
+    pub fn toggle_panel(&mut self, panel: MeteogramPanel) {
+        if let Some(p) = self.meteogram_panels.iter_mut().find(|p| p.panel == panel) {
+            p.visible = !p.visible;
+        }
+    }
+
+    // Columns keep their fixed order however they're toggled
+    pub fn toggle_table_column(&mut self, column: TableColumn) {
+        if self.table_columns.contains(&column) {
+            self.table_columns.retain(|c| *c != column);
+        } else {
+            self.table_columns.push(column);
+            self.table_columns.sort_by_key(|c| {
+                TableColumn::ALL
+                    .iter()
+                    .position(|a| a == c)
+                    .unwrap_or(usize::MAX)
+            });
+        }
+    }
+
+    pub fn move_panel_up(&mut self, panel: MeteogramPanel) {
+        if let Some(pos) = self.meteogram_panels.iter().position(|p| p.panel == panel) {
+            if pos > 0 {
+                self.meteogram_panels.swap(pos, pos - 1);
+            }
+        }
+    }
+
+    // Never zero, so a bad setting can't turn the refresh into a busy loop
+    pub fn refresh_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.refresh_minutes.max(1) * 60)
+    }
+
+    pub fn temperature_ramp(&self) -> ColorRamp {
+        ColorRamp::from_config(&self.color_ramp)
+    }
+
+    pub fn visible_panels(&self) -> impl Iterator<Item = MeteogramPanel> + '_ {
+        self.meteogram_panels
+            .iter()
+            .filter(|p| p.visible)
+            .map(|p| p.panel)
+    }
+}
+
+// $RUSTY_WX_CONFIG, else $XDG_CONFIG_HOME/rusty-wx/config.json, else ~/.config/rusty-wx/config.json
+pub fn config_path() -> PathBuf {
+    if let Ok(path) = std::env::var("RUSTY_WX_CONFIG") {
+        return PathBuf::from(path);
+    }
+    config_dir().join("config.json")
+}
+
+// Where an unreadable config is moved before it is first overwritten, e.g. config.json.bak
+fn backup_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    PathBuf::from(backup)
+}
+
+pub fn config_dir() -> PathBuf {
+    let base = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+            PathBuf::from(home).join(".config")
+        }
+    };
+    base.join("rusty-wx")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_file_is_kept_on_save() {
+        let dir = std::env::temp_dir().join(format!("rusty-wx-config-{}", std::process::id()));
+        let path = dir.join("config.json");
+        let backup = dir.join("config.json.bak");
+        std::fs::create_dir_all(&dir).unwrap();
+        let contents = r#"{"locations": [{"name": "Home", "lat": 1.0}], "#;
+        std::fs::write(&path, contents).unwrap();
+
+        let mut config = Config::load_from(&path);
+        assert!(config.invalid_file);
+        assert_eq!(config.locations, default_locations());
+
+        config.theme = ThemeMode::Dark;
+        config.save_to(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), contents);
+        assert_eq!(Config::load_from(&path).theme, ThemeMode::Dark);
+
+        // Only the unreadable file is kept, not the first save over it
+        config.theme = ThemeMode::Light;
+        config.save_to(&path).unwrap();
+        assert_eq!(std::fs::read_to_string(&backup).unwrap(), contents);
+        assert!(!Config::load_from(&path).invalid_file);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
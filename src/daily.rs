@@ -0,0 +1,79 @@
+//! Each day's temperature range and the worst of its wind, precipitation and thunderstorm
+//! chances, over the observations and forecasts of the timeline, as in `rusty-wx daily`.
+
+use crate::element::WeatherElement;
+use crate::timeline::{self, element_value};
+use crate::weather::ApiWeatherData;
+use chrono::{DateTime, NaiveDate, TimeZone};
+use serde::Serialize;
+
+/// One day of [`daily_rows`]. Serializes with the units in the keys, e.g. `high_f`, and
+/// missing values as null.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DailyRow {
+    pub date: NaiveDate,
+    #[serde(rename = "high_f")]
+    pub high: Option<f64>,
+    #[serde(rename = "low_f")]
+    pub low: Option<f64>,
+    /// Highest wind speed and gust (mph)
+    #[serde(rename = "wind_speed_mph")]
+    pub wind: Option<f64>,
+    #[serde(rename = "wind_gust_mph")]
+    pub gust: Option<f64>,
+    /// Highest chances (%)
+    #[serde(rename = "precipitation_pct")]
+    pub precipitation: Option<f64>,
+    #[serde(rename = "thunderstorm_pct")]
+    pub thunderstorm: Option<f64>,
+}
+
+/// Every day of `weather`'s timeline, oldest first. Days are those of `tz`, usually
+/// [`chrono::Local`].
+pub fn daily_rows<Tz: TimeZone>(weather: &ApiWeatherData, tz: &Tz) -> Vec<DailyRow> {
+    let max = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(x), Some(y)) => Some(x.max(y)),
+        (x, y) => x.or(y),
+    };
+    let min = |a: Option<f64>, b: Option<f64>| match (a, b) {
+        (Some(x), Some(y)) => Some(x.min(y)),
+        (x, y) => x.or(y),
+    };
+
+    let mut days: Vec<DailyRow> = Vec::new();
+    for point in timeline::build_timeline(weather) {
+        let Ok(time) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+            continue;
+        };
+        let date = time.with_timezone(tz).date_naive();
+        if days.last().map(|day| day.date) != Some(date) {
+            days.push(DailyRow {
+                date,
+                high: None,
+                low: None,
+                wind: None,
+                gust: None,
+                precipitation: None,
+                thunderstorm: None,
+            });
+        }
+        let Some(day) = days.last_mut() else {
+            continue;
+        };
+        let value = |element| element_value(weather, &point, &element);
+        let temperature = value(WeatherElement::Temperature);
+        day.high = max(day.high, temperature);
+        day.low = min(day.low, temperature);
+        day.wind = max(day.wind, value(WeatherElement::WindSpeed));
+        day.gust = max(day.gust, value(WeatherElement::WindGust));
+        day.precipitation = max(
+            day.precipitation,
+            value(WeatherElement::PrecipitationProbability),
+        );
+        day.thunderstorm = max(
+            day.thunderstorm,
+            value(WeatherElement::ThunderstormProbability),
+        );
+    }
+    days
+}
@@ -0,0 +1,258 @@
+//! Export of the merged timeline, with every raw and derived value, to CSV and
+//! newline-delimited JSON.
+
+use crate::element::WeatherElement;
+use crate::timeline::{self, element_value};
+use crate::weather::{self, ApiWeatherData, WeatherDataPoint};
+use chrono::Utc;
+use serde_json::{json, Map, Value};
+
+/// File formats for [`export`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataFormat {
+    Csv,
+    Ndjson,
+}
+
+impl DataFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Ndjson => "ndjson",
+        }
+    }
+}
+
+/// The exported columns after `valid_date`, `kind` and `cycle_date`, as (key, unit).
+/// Raw fields are as reported; derived ones are computed the same way as in the app.
+pub const FIELDS: [(&str, &str); 21] = [
+    ("temperature_f", "°F"),
+    ("dewpoint_f", "°F"),
+    ("relative_humidity_pct", "%"),
+    ("specific_humidity_dg_kg", "dg/kg"),
+    ("apparent_temperature_f", "°F"),
+    ("wbgt_f", "°F"),
+    ("wind_speed_mph", "mph"),
+    ("wind_gust_mph", "mph"),
+    ("wind_direction_deg", "degrees from north"),
+    ("cloud_cover_pct", "%"),
+    ("cloud_ceiling_m", "m"),
+    ("cloud_ceiling_ft", "ft"),
+    ("visibility_m", "m"),
+    ("visibility_mi", "mi"),
+    ("pressure_hpa", "hPa"),
+    ("solar_flux_w_m2", "W/m²"),
+    ("thunderstorm_pct", "%"),
+    ("cape_j_kg", "J/kg"),
+    ("precipitation_pct", "%"),
+    ("heat_index_f", "°F"),
+    ("wind_chill_f", "°F"),
+];
+
+/// Where the data came from, written at the top of every export.
+#[derive(Debug, Clone)]
+pub struct ExportHeader {
+    pub location: String,
+    pub latitude: f64,
+    pub longitude: f64,
+    pub provider: String,
+    /// Cycle of the first forecast, RFC 3339
+    pub forecast_cycle: Option<String>,
+    pub exported_at: String,
+}
+
+impl ExportHeader {
+    pub fn new(location: &str, weather: &ApiWeatherData) -> Self {
+        Self {
+            location: location.to_string(),
+            latitude: weather.latitude,
+            longitude: weather.longitude,
+            provider: weather::PROVIDER.to_string(),
+            forecast_cycle: weather
+                .forecasts_instant
+                .first()
+                .map(|fc| fc.cycle_date.clone()),
+            exported_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// One point of the timeline, with a value (or `None`) for each of [`FIELDS`]. Values are
+/// always finite.
+#[derive(Debug, Clone)]
+pub struct Record {
+    pub valid_date: String,
+    /// `"observation"` or `"forecast"`
+    pub kind: &'static str,
+    pub cycle_date: Option<String>,
+    pub values: Vec<Option<f64>>,
+}
+
+/// Records for every point of `timeline`, in its order.
+pub fn records(weather: &ApiWeatherData, timeline: &[WeatherDataPoint]) -> Vec<Record> {
+    timeline
+        .iter()
+        .map(|point| {
+            let (kind, cycle_date) = match point {
+                WeatherDataPoint::Observation(_) => ("observation", None),
+                WeatherDataPoint::Forecast(fc) => ("forecast", Some(fc.cycle_date.clone())),
+            };
+            Record {
+                valid_date: point.valid_date().to_string(),
+                kind,
+                cycle_date,
+                values: field_values(weather, point),
+            }
+        })
+        .collect()
+}
+
+fn field_values(weather: &ApiWeatherData, point: &WeatherDataPoint) -> Vec<Option<f64>> {
+    let derived = |element: WeatherElement| element_value(weather, point, &element);
+    let (obs, fc) = match point {
+        WeatherDataPoint::Observation(obs) => (Some(obs), None),
+        WeatherDataPoint::Forecast(fc) => (None, Some(fc)),
+    };
+    let raw = |from_obs: fn(&weather::ObservationInstant) -> Option<f64>,
+               from_fc: fn(&weather::ForecastInstant) -> Option<f64>| {
+        obs.and_then(from_obs).or_else(|| fc.and_then(from_fc))
+    };
+
+    let temperature = point.temperature();
+    let humidity = derived(WeatherElement::Humidity);
+    let wind = raw(|o| o.wind_spd_10m_mph, |f| f.wind_spd_10m_mph);
+    let ceiling = raw(|o| o.cloud_ceiling_m, |f| f.cloud_ceiling_m);
+    let visibility = raw(|o| o.visibility_m, |f| f.visibility_m);
+
+    let values = [
+        temperature,
+        derived(WeatherElement::Dewpoint),
+        humidity,
+        obs.and_then(|o| o.specific_humidity_2m_dg_kg),
+        derived(WeatherElement::ApparentTemperature),
+        fc.and_then(|f| f.wbg_temp_2m_f),
+        wind,
+        raw(|o| o.wind_gust_10m_mph, |f| f.wind_gust_10m_mph),
+        raw(|o| o.wind_dir_10m_deg_fm_n, |f| f.wind_dir_10m_deg_fm_n),
+        raw(|o| o.cloud_cover_pct, |f| f.cloud_cover_pct),
+        ceiling,
+        ceiling.map(|m| m * 3.28084),
+        visibility,
+        visibility.map(|m| m / 1609.34),
+        obs.and_then(|o| o.pressure_h_pa),
+        raw(|o| o.solar_flux_w_m2, |f| f.solar_flux_w_m2),
+        fc.and_then(|f| f.prob_thunderstorm_pct),
+        fc.and_then(|f| f.cape_surface_j_kg),
+        timeline::precipitation_probability_from(
+            &weather.forecasts_precip_1hr,
+            &weather.forecasts_precip_6hr,
+            point.valid_date(),
+        ),
+        temperature
+            .zip(humidity)
+            .map(|(t, rh)| weather::calculate_heat_index(t, rh)),
+        temperature
+            .zip(wind)
+            .map(|(t, w)| weather::calculate_wind_chill(t, w)),
+    ];
+    // A formula can give NaN at the edge of its range; that's a missing value in both formats
+    values
+        .into_iter()
+        .map(|value| value.filter(|v| v.is_finite()))
+        .collect()
+}
+
+// Derived values carry meaningless precision; two decimals is plenty
+fn round(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+/// CSV with the header as leading `#` comment lines, then one row per record.
+/// Missing values are empty cells.
+pub fn to_csv(header: &ExportHeader, records: &[Record]) -> String {
+    let units: Vec<String> = FIELDS
+        .iter()
+        .map(|(key, unit)| format!("{}={}", key, unit))
+        .collect();
+    let mut csv = format!(
+        "# location: {}\n# latitude: {}\n# longitude: {}\n# provider: {}\n# forecast_cycle: {}\n# exported_at: {}\n# units: valid_date=UTC, {}\n",
+        header.location,
+        header.latitude,
+        header.longitude,
+        header.provider,
+        header.forecast_cycle.as_deref().unwrap_or(""),
+        header.exported_at,
+        units.join(", ")
+    );
+
+    csv.push_str("valid_date,kind,cycle_date");
+    for (key, _) in FIELDS {
+        csv.push(',');
+        csv.push_str(key);
+    }
+    csv.push('\n');
+
+    for record in records {
+        csv.push_str(&format!(
+            "{},{},{}",
+            record.valid_date,
+            record.kind,
+            record.cycle_date.as_deref().unwrap_or("")
+        ));
+        for value in &record.values {
+            csv.push(',');
+            if let Some(value) = value {
+                csv.push_str(&round(*value).to_string());
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+/// One JSON object per line: the header first (`"kind": "header"`, with a `units` map),
+/// then the records. Missing values are `null`.
+pub fn to_ndjson(header: &ExportHeader, records: &[Record]) -> String {
+    let units: Map<String, Value> = FIELDS
+        .iter()
+        .map(|(key, unit)| (key.to_string(), json!(unit)))
+        .collect();
+    let mut lines = vec![json!({
+        "kind": "header",
+        "location": header.location,
+        "latitude": header.latitude,
+        "longitude": header.longitude,
+        "provider": header.provider,
+        "forecast_cycle": header.forecast_cycle,
+        "exported_at": header.exported_at,
+        "units": units,
+    })];
+
+    for record in records {
+        let mut object = Map::new();
+        object.insert("valid_date".to_string(), json!(record.valid_date));
+        object.insert("kind".to_string(), json!(record.kind));
+        object.insert("cycle_date".to_string(), json!(record.cycle_date));
+        for ((key, _), value) in FIELDS.iter().zip(&record.values) {
+            object.insert(key.to_string(), json!(value.map(round)));
+        }
+        lines.push(Value::Object(object));
+    }
+
+    let mut ndjson = String::new();
+    for line in lines {
+        ndjson.push_str(&line.to_string());
+        ndjson.push('\n');
+    }
+    ndjson
+}
+
+/// Export a fetched location's whole timeline in `format`.
+pub fn export(location: &str, weather: &ApiWeatherData, format: DataFormat) -> String {
+    let header = ExportHeader::new(location, weather);
+    let records = records(weather, &timeline::build_timeline(weather));
+    match format {
+        DataFormat::Csv => to_csv(&header, &records),
+        DataFormat::Ndjson => to_ndjson(&header, &records),
+    }
+}
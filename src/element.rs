@@ -0,0 +1,161 @@
+//! The weather elements shown in charts, tables and alert rules.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A quantity that can be read from any point of the timeline, observed or derived.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum WeatherElement {
+    Temperature,
+    ApparentTemperature,
+    WBGT,
+    WindSpeed,
+    WindGust,
+    Pressure,
+    Humidity,
+    Dewpoint,
+    CloudCover,
+    Visibility,
+    SolarFlux,
+    ThunderstormProbability,
+    CAPE,
+    PrecipitationProbability,
+}
+
+impl WeatherElement {
+    pub const ALL: [WeatherElement; 14] = [
+        WeatherElement::Temperature,
+        WeatherElement::ApparentTemperature,
+        WeatherElement::WBGT,
+        WeatherElement::WindSpeed,
+        WeatherElement::WindGust,
+        WeatherElement::Pressure,
+        WeatherElement::Humidity,
+        WeatherElement::Dewpoint,
+        WeatherElement::CloudCover,
+        WeatherElement::Visibility,
+        WeatherElement::SolarFlux,
+        WeatherElement::ThunderstormProbability,
+        WeatherElement::CAPE,
+        WeatherElement::PrecipitationProbability,
+    ];
+
+    /// Short identifier used on the command line and in config files, e.g. `"gust"`.
+    pub fn key(&self) -> &'static str {
+        match self {
+            WeatherElement::Temperature => "temperature",
+            WeatherElement::ApparentTemperature => "apparent",
+            WeatherElement::WBGT => "wbgt",
+            WeatherElement::WindSpeed => "wind",
+            WeatherElement::WindGust => "gust",
+            WeatherElement::Pressure => "pressure",
+            WeatherElement::Humidity => "humidity",
+            WeatherElement::Dewpoint => "dewpoint",
+            WeatherElement::CloudCover => "cloud",
+            WeatherElement::Visibility => "visibility",
+            WeatherElement::SolarFlux => "solar",
+            WeatherElement::ThunderstormProbability => "thunder",
+            WeatherElement::CAPE => "cape",
+            WeatherElement::PrecipitationProbability => "precip",
+        }
+    }
+
+    /// Looks up an element by its [`key`](Self::key), ignoring case.
+    pub fn from_key(key: &str) -> Option<WeatherElement> {
+        WeatherElement::ALL
+            .iter()
+            .find(|e| e.key().eq_ignore_ascii_case(key))
+            .cloned()
+    }
+
+    /// Human-readable name for legends and menus.
+    pub fn label(&self) -> &'static str {
+        match self {
+            WeatherElement::Temperature => "Temperature",
+            WeatherElement::ApparentTemperature => "Apparent Temp",
+            WeatherElement::WBGT => "WBGT",
+            WeatherElement::WindSpeed => "Wind",
+            WeatherElement::WindGust => "Gusts",
+            WeatherElement::Pressure => "Pressure",
+            WeatherElement::Humidity => "Humidity",
+            WeatherElement::Dewpoint => "Dewpoint",
+            WeatherElement::CloudCover => "Cloud Cover",
+            WeatherElement::Visibility => "Visibility",
+            WeatherElement::SolarFlux => "Solar Flux",
+            WeatherElement::ThunderstormProbability => "T-Storm Prob",
+            WeatherElement::CAPE => "CAPE",
+            WeatherElement::PrecipitationProbability => "Precip Prob",
+        }
+    }
+
+    /// Whether the element can be drawn as a meteogram overlay: all but temperature, which is
+    /// the meteogram's own line.
+    pub fn is_overlay(&self) -> bool {
+        *self != WeatherElement::Temperature
+    }
+
+    /// Whether the element is in °F and so shares the temperature axis instead of its own.
+    pub fn shares_temp_axis(&self) -> bool {
+        matches!(
+            self,
+            WeatherElement::Temperature
+                | WeatherElement::Dewpoint
+                | WeatherElement::ApparentTemperature
+                | WeatherElement::WBGT
+        )
+    }
+}
+
+impl std::fmt::Display for WeatherElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// Parses a [`key`](WeatherElement::key) given on the command line or in a query string, with
+/// an error listing the valid keys.
+impl std::str::FromStr for WeatherElement {
+    type Err = String;
+
+    fn from_str(key: &str) -> Result<Self, String> {
+        WeatherElement::from_key(key).ok_or_else(|| {
+            let keys: Vec<&str> = WeatherElement::ALL.iter().map(|e| e.key()).collect();
+            format!(
+                "unknown element '{}', expected one of: {}",
+                key,
+                keys.join(", ")
+            )
+        })
+    }
+}
+
+// Elements are written by key in config files, e.g. "gust"
+impl Serialize for WeatherElement {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.key())
+    }
+}
+
+impl<'de> Deserialize<'de> for WeatherElement {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let key = String::deserialize(deserializer)?;
+        WeatherElement::from_key(&key)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown weather element '{}'", key)))
+    }
+}
+
+/// Formats a value of `element` for an axis label or table cell, without units.
+pub fn format_scale_value(element: &WeatherElement, value: f64) -> String {
+    match element {
+        WeatherElement::WindSpeed | WeatherElement::WindGust => format!("{:.0}", value),
+        WeatherElement::Pressure => format!("{:.0}", value),
+        WeatherElement::CloudCover => format!("{:.0}%", value),
+        WeatherElement::Visibility => format!("{:.1}", value),
+        WeatherElement::SolarFlux => format!("{:.0}", value),
+        WeatherElement::ThunderstormProbability => format!("{:.0}%", value),
+        WeatherElement::Humidity => format!("{:.0}%", value),
+        WeatherElement::CAPE => format!("{:.0}", value),
+        WeatherElement::PrecipitationProbability => format!("{:.0}%", value),
+        _ => format!("{:.1}", value),
+    }
+}
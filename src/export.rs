@@ -0,0 +1,292 @@
+use crate::app::WeatherApp;
+use crate::config::MeteogramPanel;
+use crate::panels::{PanelCanvas, PANEL_HEIGHT};
+use crate::render::{self, DrawTarget, SvgDocument};
+use crate::theme::{ChartColors, ElementColor};
+use chrono::{DateTime, Local};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    Point, Size,
+};
+use rusty_wx::element::format_scale_value;
+use std::path::{Path, PathBuf};
+
+const HEADER_HEIGHT: f32 = 50.0;
+const LEGEND_HEIGHT: f32 = 24.0;
+const LEFT_SCALE_WIDTH: f32 = 45.0;
+const ELEMENT_SCALE_WIDTH: f32 = 50.0;
+const METEOGRAM_HEIGHT: f32 = 230.0;
+const PADDING: f32 = 10.0;
+
+pub const DEFAULT_EXPORT_WIDTH: f32 = 2000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    Svg,
+    Png,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Svg => "svg",
+            ExportFormat::Png => "png",
+        }
+    }
+
+    // Pick the format from a file extension, defaulting to SVG
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => ExportFormat::Png,
+            _ => ExportFormat::Svg,
+        }
+    }
+}
+
+// Render the visible meteogram panels, with scales, legend, location and forecast cycle, as SVG
+pub fn meteogram_svg(app: &WeatherApp, plot_width: f32) -> String {
+    let timeline = &app.combined_timeline;
+    let right_scale_elements: Vec<_> = app
+        .selected_weather_elements
+        .iter()
+        .filter(|e| !e.shares_temp_axis())
+        .collect();
+    let right_width = ELEMENT_SCALE_WIDTH * right_scale_elements.len().max(1) as f32;
+
+    let panels: Vec<MeteogramPanel> = app.config.visible_panels().collect();
+    let panels_height: f32 = panels
+        .iter()
+        .map(|p| match p {
+            MeteogramPanel::Temperature => METEOGRAM_HEIGHT,
+            _ => PANEL_HEIGHT,
+        })
+        .sum();
+
+    let width = LEFT_SCALE_WIDTH + plot_width + right_width + PADDING;
+    let height = HEADER_HEIGHT + LEGEND_HEIGHT + panels_height + PADDING;
+    let mut doc = SvgDocument::new(width, height);
+    let theme = app.active_theme();
+    let colors = ChartColors::from_theme(&theme);
+
+    doc.fill_rect(
+        Point::ORIGIN,
+        Size::new(width, height),
+        theme.palette().background,
+    );
+
+    // Header: location and forecast cycle
+    doc.text(
+        &format!("rusty-wx — {}", app.current_city),
+        Point::new(PADDING, 8.0),
+        18.0,
+        colors.text,
+        Horizontal::Left,
+        Vertical::Top,
+    );
+    let mut subtitle = Vec::new();
+    if let Some(cycle) = forecast_cycle(app) {
+        subtitle.push(format!(
+            "Forecast Cycle: {}",
+            cycle.format("%I:%M %p %m/%d")
+        ));
+    }
+    if let Some(updated) = &app.last_updated {
+        subtitle.push(format!("Updated: {}", updated.format("%I:%M:%S %p")));
+    }
+    doc.text(
+        &subtitle.join("   "),
+        Point::new(PADDING, 30.0),
+        12.0,
+        colors.muted,
+        Horizontal::Left,
+        Vertical::Top,
+    );
+
+    // Legend, matching the on-screen legend entries
+    let mut legend_x = LEFT_SCALE_WIDTH;
+    let legend_y = HEADER_HEIGHT + 4.0;
+    let mut entries = vec![("━ Temperature".to_string(), colors.text)];
+    for element in &app.selected_weather_elements {
+        let axis_note = if element.shares_temp_axis() {
+            " (°F axis)"
+        } else {
+            ""
+        };
+        entries.push((
+            format!("━ {}{}", element.label(), axis_note),
+            colors.series(element.color()),
+        ));
+    }
+    for (label, color) in entries {
+        doc.text(
+            &label,
+            Point::new(legend_x, legend_y),
+            12.0,
+            color,
+            Horizontal::Left,
+            Vertical::Top,
+        );
+        // Rough text advance; SVG has no layout to measure against
+        legend_x += label.chars().count() as f32 * 7.0 + 16.0;
+    }
+
+    // Panels, stacked on the shared time axis
+    let mut y = HEADER_HEIGHT + LEGEND_HEIGHT;
+    let plot_x = LEFT_SCALE_WIDTH;
+    let right_x = plot_x + plot_width;
+
+    for panel in panels {
+        match panel {
+            MeteogramPanel::Temperature => {
+                let meteogram = app.build_meteogram();
+                doc.translated(plot_x, y, Size::new(plot_width, METEOGRAM_HEIGHT), |doc| {
+                    meteogram.render(doc, &colors, Size::new(plot_width, METEOGRAM_HEIGHT));
+                });
+
+                // Temperature scale, laid out like `create_temp_scale`
+                let (min_temp, max_temp) = app.get_unified_temp_range(timeline);
+                let label_count = 5;
+                for i in 0..=label_count {
+                    let temp = max_temp - (i as f64 / label_count as f64) * (max_temp - min_temp);
+                    let label_y = y + 40.0 + i as f32 * (180.0 / label_count as f32);
+                    doc.text(
+                        &format!("{:.0}°", temp),
+                        Point::new(LEFT_SCALE_WIDTH - 5.0, label_y),
+                        12.0,
+                        colors.text,
+                        Horizontal::Right,
+                        Vertical::Center,
+                    );
+                }
+
+                // One secondary scale per overlay with its own units
+                for (j, element) in right_scale_elements.iter().enumerate() {
+                    let (min_value, max_value) = app.get_element_range(timeline, element);
+                    if min_value == f64::MAX || max_value == f64::MIN {
+                        continue;
+                    }
+                    let scale_x = right_x + 5.0 + j as f32 * ELEMENT_SCALE_WIDTH;
+                    for i in 0..=label_count {
+                        let value =
+                            max_value - (i as f64 / label_count as f64) * (max_value - min_value);
+                        let label_y = y + 40.0 + i as f32 * (180.0 / label_count as f32);
+                        doc.text(
+                            &format_scale_value(element, value),
+                            Point::new(scale_x, label_y),
+                            12.0,
+                            colors.series(element.color()),
+                            Horizontal::Left,
+                            Vertical::Center,
+                        );
+                    }
+                }
+
+                y += METEOGRAM_HEIGHT;
+            }
+            _ => {
+                let canvas = app.build_panel(panel);
+                doc.translated(plot_x, y, Size::new(plot_width, PANEL_HEIGHT), |doc| {
+                    canvas.render(doc, &colors, Size::new(plot_width, PANEL_HEIGHT));
+                });
+
+                let (min_value, max_value) = PanelCanvas::value_range(panel, timeline);
+                let unit = match panel {
+                    MeteogramPanel::Precipitation | MeteogramPanel::CloudCover => "%",
+                    _ => "",
+                };
+                let label_count = 2;
+                for i in 0..=label_count {
+                    let value =
+                        max_value - (i as f64 / label_count as f64) * (max_value - min_value);
+                    let label_y =
+                        y + 10.0 + i as f32 * ((PANEL_HEIGHT - 20.0) / label_count as f32);
+                    doc.text(
+                        &format!("{:.0}{}", value, unit),
+                        Point::new(LEFT_SCALE_WIDTH - 5.0, label_y),
+                        12.0,
+                        colors.text,
+                        Horizontal::Right,
+                        Vertical::Center,
+                    );
+                }
+
+                y += PANEL_HEIGHT;
+            }
+        }
+    }
+
+    doc.finish()
+}
+
+// Write the meteogram in the given format; PNG rasterization happens off the caller's thread
+pub async fn write_export(
+    svg: String,
+    path: PathBuf,
+    format: ExportFormat,
+) -> Result<PathBuf, String> {
+    tokio::task::spawn_blocking(move || {
+        let bytes = match format {
+            ExportFormat::Svg => svg.into_bytes(),
+            ExportFormat::Png => render::rasterize_svg(&svg)?,
+        };
+        if let Some(dir) = path.parent() {
+            if !dir.as_os_str().is_empty() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+        }
+        std::fs::write(&path, bytes).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Write the timeline as CSV or NDJSON, off the UI thread like the image exports
+pub async fn write_data_export(contents: String, path: PathBuf) -> Result<PathBuf, String> {
+    tokio::task::spawn_blocking(move || {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        std::fs::write(&path, contents).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(path)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+// Default location for GUI exports, e.g. ~/Pictures/rusty-wx-Denver-20240101-1200.png;
+// data exports go to ~/Documents instead
+pub fn default_export_path(app: &WeatherApp, extension: &str) -> PathBuf {
+    let dir = match &app.config.export_dir {
+        Some(dir) => dir.clone(),
+        None => {
+            let home = PathBuf::from(std::env::var("HOME").unwrap_or_else(|_| ".".to_string()));
+            let default_dir = match extension {
+                "svg" | "png" => home.join("Pictures"),
+                _ => home.join("Documents"),
+            };
+            if default_dir.is_dir() {
+                default_dir
+            } else {
+                home
+            }
+        }
+    };
+    let city: String = app
+        .current_city
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect();
+    dir.join(format!(
+        "rusty-wx-{}-{}.{}",
+        city,
+        Local::now().format("%Y%m%d-%H%M"),
+        extension
+    ))
+}
+
+fn forecast_cycle(app: &WeatherApp) -> Option<DateTime<Local>> {
+    let first_forecast = app.weather_data.as_ref()?.forecasts_instant.first()?;
+    let dt = DateTime::parse_from_rfc3339(&first_forecast.cycle_date).ok()?;
+    Some(dt.with_timezone(&Local))
+}
@@ -0,0 +1,59 @@
+use rusty_wx::archive::Archive;
+use rusty_wx::qc::{self, QcRules};
+use rusty_wx::verification::{self, ForecastRun, Scores};
+use rusty_wx::weather::{self, ApiWeatherData};
+
+// Forecast runs read back from the archive, which keeps every fetched run and observation, so
+// they can be drawn behind the current one and scored once their valid times are observed
+
+// A week of runs verifies every lead time many times over without reading back the whole archive
+const SCORED_DAYS: i64 = 7;
+
+// Up to `count` runs cycled before `cycle` (Unix seconds), newest first
+pub fn previous_runs(
+    archive: &Archive,
+    location: &str,
+    cycle: i64,
+    count: usize,
+) -> Result<Vec<ForecastRun>, String> {
+    let cycles = archive.cycles(location, weather::PROVIDER)?;
+    let earlier: Vec<i64> = cycles.into_iter().filter(|c| *c < cycle).collect();
+    let Some(first) = earlier[earlier.len().saturating_sub(count)..].first() else {
+        return Ok(Vec::new());
+    };
+    let mut runs = archive.runs(location, weather::PROVIDER, *first..cycle)?;
+    runs.reverse();
+    Ok(runs)
+}
+
+// The runs cycled in the SCORED_DAYS up to `cycle` and their scores against the observations
+// archived since, leaving out observations that fail the quality checks when `rules` are given
+pub fn scores(
+    archive: &Archive,
+    location: &str,
+    cycle: i64,
+    rules: Option<&QcRules>,
+) -> Result<(usize, Vec<Scores>), String> {
+    let start = cycle - SCORED_DAYS * 86400;
+    let runs = archive.runs(location, weather::PROVIDER, start..cycle + 1)?;
+    let Some(latest) = runs.last() else {
+        return Ok((0, Vec::new()));
+    };
+    // Newest first, as in the feed, which the quality checks expect
+    let mut observations = archive.observations(location, weather::PROVIDER, start..i64::MAX)?;
+    observations.reverse();
+    let observed = ApiWeatherData {
+        latitude: latest.weather.latitude,
+        longitude: latest.weather.longitude,
+        grid_id: latest.weather.grid_id,
+        normal_temperatures: Vec::new(),
+        observations_instant: observations,
+        forecasts_instant: Vec::new(),
+        forecasts_precip_1hr: Vec::new(),
+        forecasts_precip_6hr: Vec::new(),
+    };
+    let flags = rules
+        .map(|rules| qc::check_observations(&observed, rules))
+        .unwrap_or_default();
+    Ok((runs.len(), verification::verify(&runs, &observed, &flags)))
+}
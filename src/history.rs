@@ -0,0 +1,147 @@
+use crate::app::WeatherApp;
+use crate::config::Config;
+use chrono::{Duration, Local, NaiveDate};
+use rusty_wx::archive::Archive;
+use rusty_wx::statistics::{self, DailyTemperatures, Statistics};
+use rusty_wx::weather::{self, ApiWeatherData};
+
+// Days shown when the history is first opened, ending today
+const DEFAULT_DAYS: i64 = 7;
+
+// Longer ranges are drawn as each day's low and high rather than every hour, which would make
+// the meteogram tens of thousands of pixels wide
+const HOURLY_DAYS: i64 = 14;
+
+// Archived observations of one location over a past date range, with their statistics
+pub struct History {
+    pub location: String,
+    // The date range as typed, YYYY-MM-DD, both days included
+    pub start_input: String,
+    pub end_input: String,
+    // The observations of the range as a site of their own, None until loaded or if nothing was
+    // archived (boxed, as the app holds the history)
+    pub site: Option<Box<WeatherApp>>,
+    pub statistics: Vec<Statistics>,
+    pub days: Vec<DailyTemperatures>,
+    // Whether the site holds only each day's lowest and highest temperature observation; the
+    // statistics and days are always of every observation
+    pub downsampled: bool,
+    pub error: Option<String>,
+}
+
+impl History {
+    pub fn new(location: &str, today: NaiveDate) -> Self {
+        let mut history = Self {
+            location: location.to_string(),
+            start_input: String::new(),
+            end_input: String::new(),
+            site: None,
+            statistics: Vec::new(),
+            days: Vec::new(),
+            downsampled: false,
+            error: None,
+        };
+        history.set_last_days(DEFAULT_DAYS, today);
+        history
+    }
+
+    // The `days` days up to and including `today`
+    pub fn set_last_days(&mut self, days: i64, today: NaiveDate) {
+        self.start_input = (today - Duration::days(days - 1)).to_string();
+        self.end_input = today.to_string();
+    }
+
+    // Move the range by its own length, back for negative `steps`
+    pub fn shift(&mut self, steps: i64) {
+        if let Ok((start, end)) = self.range() {
+            let length = (end - start).num_days() + 1;
+            self.start_input = (start + Duration::days(steps * length)).to_string();
+            self.end_input = (end + Duration::days(steps * length)).to_string();
+        }
+    }
+
+    pub fn range(&self) -> Result<(NaiveDate, NaiveDate), String> {
+        let parse = |input: &str| {
+            NaiveDate::parse_from_str(input.trim(), "%Y-%m-%d")
+                .map_err(|_| format!("\"{}\" isn't a date like 2026-10-01", input.trim()))
+        };
+        let (start, end) = (parse(&self.start_input)?, parse(&self.end_input)?);
+        if start > end {
+            return Err("The range starts after it ends".to_string());
+        }
+        Ok((start, end))
+    }
+
+    // Read the range back from the archive, from local midnight of the first day to the end of
+    // the last
+    pub fn load(&mut self, archive: &Archive, config: &Config) {
+        self.site = None;
+        self.statistics.clear();
+        self.days.clear();
+        self.downsampled = false;
+        self.error = None;
+
+        let (start, end) = match self.range() {
+            Ok(range) => range,
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+        let midnight = |date: NaiveDate| {
+            date.and_hms_opt(0, 0, 0)
+                .and_then(|time| time.and_local_timezone(Local).earliest())
+                .map_or(0, |time| time.timestamp())
+        };
+        let range = midnight(start)..midnight(end + Duration::days(1));
+
+        let weather = match archive.weather(&self.location, weather::PROVIDER, range) {
+            Ok(Some(weather)) if !weather.observations_instant.is_empty() => weather,
+            Ok(_) => {
+                self.error = Some(format!(
+                    "No observations archived for {} from {} to {}",
+                    self.location, start, end
+                ));
+                return;
+            }
+            Err(e) => {
+                self.error = Some(e);
+                return;
+            }
+        };
+
+        // Observations only: the forecasts archived for the range are what was expected, not
+        // what happened
+        let weather = ApiWeatherData {
+            forecasts_instant: Vec::new(),
+            forecasts_precip_1hr: Vec::new(),
+            forecasts_precip_6hr: Vec::new(),
+            ..weather
+        };
+        let mut site = WeatherApp {
+            current_city: self.location.clone(),
+            weather_data: Some(weather),
+            config: config.clone(),
+            selected_weather_elements: Vec::new(),
+            ..WeatherApp::default()
+        };
+        site.build_timeline();
+
+        if let Some(weather) = &mut site.weather_data {
+            self.statistics = statistics::statistics(weather, &site.qc_flags);
+            self.days = statistics::daily_temperatures(weather, &site.qc_flags, &Local);
+
+            if (end - start).num_days() + 1 > HOURLY_DAYS {
+                weather.observations_instant =
+                    statistics::daily_extremes(weather, &site.qc_flags, &Local);
+                // The flags of every observation, as the checks between hours don't hold
+                // between a day's low and high
+                let flags = std::mem::take(&mut site.qc_flags);
+                site.build_timeline();
+                site.qc_flags = flags;
+                self.downsampled = true;
+            }
+        }
+        self.site = Some(Box::new(site));
+    }
+}
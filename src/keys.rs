@@ -0,0 +1,134 @@
+use iced::keyboard::{Key, Modifiers};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// Something a key can be bound to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Action {
+    PreviousHour,
+    NextHour,
+    PreviousDay,
+    NextDay,
+    Start,
+    End,
+    Now,
+    NextElement,
+    PreviousElement,
+    FocusSearch,
+    ToggleHelp,
+    CloseHelp,
+    // Index into the saved locations, 0-based
+    SelectLocation(usize),
+    // Removes a default binding
+    Unbound,
+}
+
+impl Action {
+    pub fn description(&self) -> String {
+        match self {
+            Action::PreviousHour => "Previous hour".to_string(),
+            Action::NextHour => "Next hour".to_string(),
+            Action::PreviousDay => "Back one day".to_string(),
+            Action::NextDay => "Forward one day".to_string(),
+            Action::Start => "Start of timeline".to_string(),
+            Action::End => "End of timeline".to_string(),
+            Action::Now => "Go to now".to_string(),
+            Action::NextElement => "Next weather element".to_string(),
+            Action::PreviousElement => "Previous weather element".to_string(),
+            Action::FocusSearch => "Focus location search".to_string(),
+            Action::ToggleHelp => "Show/hide this help".to_string(),
+            Action::CloseHelp => "Close help".to_string(),
+            Action::SelectLocation(i) => format!("Saved location {}", i + 1),
+            Action::Unbound => "Unbound".to_string(),
+        }
+    }
+}
+
+// Key names like "ArrowLeft", "PageUp", "n" or "Shift+Tab", mapped to actions
+pub type KeyBindings = BTreeMap<String, Action>;
+
+pub fn default_bindings() -> KeyBindings {
+    let mut bindings = BTreeMap::from([
+        ("ArrowLeft".to_string(), Action::PreviousHour),
+        ("ArrowRight".to_string(), Action::NextHour),
+        ("PageUp".to_string(), Action::PreviousDay),
+        ("PageDown".to_string(), Action::NextDay),
+        ("Home".to_string(), Action::Start),
+        ("End".to_string(), Action::End),
+        ("n".to_string(), Action::Now),
+        ("Tab".to_string(), Action::NextElement),
+        ("Shift+Tab".to_string(), Action::PreviousElement),
+        ("/".to_string(), Action::FocusSearch),
+        ("?".to_string(), Action::ToggleHelp),
+        ("F1".to_string(), Action::ToggleHelp),
+        ("Escape".to_string(), Action::CloseHelp),
+    ]);
+    for i in 0..9 {
+        bindings.insert((i + 1).to_string(), Action::SelectLocation(i));
+    }
+    bindings
+}
+
+// The defaults with the config's bindings layered on top
+pub fn effective_bindings(overrides: &KeyBindings) -> KeyBindings {
+    let mut bindings = default_bindings();
+    for (key, action) in overrides {
+        bindings.insert(normalize(key), *action);
+    }
+    bindings.retain(|_, action| *action != Action::Unbound);
+    bindings
+}
+
+// Name a key press the way bindings are written. Shift only counts for named keys,
+// since it's already reflected in the character typed ("?" rather than "Shift+/").
+pub fn key_name(key: Key, modifiers: Modifiers) -> Option<String> {
+    let (name, named) = match key.as_ref() {
+        Key::Named(named) => (format!("{:?}", named), true),
+        Key::Character(c) => (c.to_lowercase(), false),
+        Key::Unidentified => return None,
+    };
+
+    let mut parts = Vec::new();
+    if modifiers.control() {
+        parts.push("Ctrl");
+    }
+    if modifiers.alt() {
+        parts.push("Alt");
+    }
+    if modifiers.logo() {
+        parts.push("Super");
+    }
+    if named && modifiers.shift() {
+        parts.push("Shift");
+    }
+    parts.push(&name);
+    Some(parts.join("+"))
+}
+
+// Put a key name from the config file in the same form as `key_name`
+fn normalize(key: &str) -> String {
+    let mut modifiers = Vec::new();
+    let mut name = key;
+    for part in key.split('+') {
+        match part.to_lowercase().as_str() {
+            "ctrl" | "control" => modifiers.push("Ctrl"),
+            "alt" => modifiers.push("Alt"),
+            "super" | "logo" => modifiers.push("Super"),
+            "shift" => modifiers.push("Shift"),
+            _ => name = part,
+        }
+    }
+
+    let name = if name.chars().count() == 1 {
+        name.to_lowercase()
+    } else {
+        name.to_string()
+    };
+
+    let mut parts: Vec<&str> = ["Ctrl", "Alt", "Super", "Shift"]
+        .into_iter()
+        .filter(|m| modifiers.contains(m))
+        .collect();
+    parts.push(&name);
+    parts.join("+")
+}
@@ -0,0 +1,33 @@
+//! Weather data, derived quantities and forecast text behind the rusty-wx app.
+//!
+//! This crate has no GUI dependencies when built with `default-features = false`; the
+//! `gui` feature only adds what the `rusty-wx` binary needs.
+//!
+//! ```no_run
+//! use rusty_wx::element::WeatherElement;
+//! use rusty_wx::{timeline, weather};
+//!
+//! # async fn example() -> Result<(), String> {
+//! let data = weather::fetch_weather(39.74, -104.99).await?;
+//! let points = timeline::build_timeline(&data);
+//! let now = timeline::closest_index(&points, chrono::Utc::now());
+//! let feels_like = timeline::element_value(&data, &points[now], &WeatherElement::ApparentTemperature);
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "archive")]
+pub mod archive;
+pub mod calendar;
+pub mod daily;
+pub mod data_export;
+pub mod element;
+#[cfg(feature = "mock")]
+pub mod mock;
+pub mod qc;
+pub mod run_changes;
+pub mod statistics;
+pub mod summary;
+pub mod timeline;
+pub mod verification;
+pub mod weather;
@@ -1,12 +1,43 @@
+mod alerts;
+mod api;
 mod app;
+mod cache;
+mod cli;
+mod color_ramp;
+mod comparison;
 mod components;
+mod config;
+mod export;
+mod forecast_history;
+mod history;
+mod keys;
 mod meteogram;
+mod metrics;
+mod mqtt;
+mod panels;
+mod render;
+#[cfg(test)]
+mod snapshots;
+mod statusbar;
+mod table;
+mod theme;
 mod view;
-mod weather;
 
 use app::WeatherApp;
+use clap::Parser;
 use iced::{Application, Settings};
 
 fn main() -> iced::Result {
+    let cli = cli::Cli::parse();
+
+    // Subcommands run headless; the GUI only starts without one
+    if let Some(command) = cli.command {
+        if let Err(e) = cli::run(command) {
+            eprintln!("Error: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     WeatherApp::run(Settings::default())
-}
\ No newline at end of file
+}
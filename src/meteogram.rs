@@ -1,310 +1,124 @@
-use crate::app::{Message, WeatherElement};
-use crate::weather::{self, ForecastPrecip, WeatherDataPoint};
-use chrono::{DateTime, Datelike, Local, Timelike};
+use crate::app::Message;
+use crate::color_ramp::ColorRamp;
+use crate::render::DrawTarget;
+use crate::theme::{ChartColors, ElementColor};
+use chrono::{DateTime, Datelike, FixedOffset, Local, Timelike, Utc};
 use iced::{
-    widget::canvas::{self, Frame, Text},
-    Color, Point, Rectangle, Theme,
+    alignment::{Horizontal, Vertical},
+    widget::canvas::{self, Frame},
+    Color, Point, Rectangle, Size, Theme,
 };
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::{self, QcFlag};
+use rusty_wx::timeline;
+use rusty_wx::weather::{ApiWeatherData, WeatherDataPoint};
+
+// An overlay line and the value range of its own (secondary) axis
+pub struct OverlaySeries {
+    pub element: WeatherElement,
+    pub min: f64,
+    pub max: f64,
+}
 
 pub struct Meteogram {
     timeline: Vec<WeatherDataPoint>,
     selected_index: usize,
-    selected_element: WeatherElement,
-    precip_1hr: Vec<ForecastPrecip>,
-    precip_6hr: Vec<ForecastPrecip>,
+    overlays: Vec<OverlaySeries>,
+    // The fetch the timeline was built from, for the overlay values
+    weather: Option<ApiWeatherData>,
     unified_temp_min: f64,
     unified_temp_max: f64,
+    color_ramp: ColorRamp,
+    alert_spans: Vec<(i64, i64)>,
+    qc_flags: Vec<QcFlag>,
+    // Temperatures of earlier forecast runs as (timestamp, °F), newest run first
+    previous_runs: Vec<Vec<(i64, f64)>>,
+    now: i64,
+    // Days and hours are drawn in this offset instead of the local time zone
+    zone: Option<FixedOffset>,
+    // Message for a click, given the index of the closest point
+    on_click: fn(usize) -> Message,
 }
 
 impl Meteogram {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         timeline: Vec<WeatherDataPoint>,
         selected_index: usize,
-        selected_element: WeatherElement,
-        precip_1hr: Vec<ForecastPrecip>,
-        precip_6hr: Vec<ForecastPrecip>,
+        overlays: Vec<OverlaySeries>,
+        weather: Option<ApiWeatherData>,
         unified_temp_min: f64,
         unified_temp_max: f64,
+        color_ramp: ColorRamp,
     ) -> Self {
         Self {
             timeline,
             selected_index,
-            selected_element,
-            precip_1hr,
-            precip_6hr,
+            overlays,
+            weather,
             unified_temp_min,
             unified_temp_max,
+            color_ramp,
+            alert_spans: Vec::new(),
+            qc_flags: Vec::new(),
+            previous_runs: Vec::new(),
+            now: Utc::now().timestamp(),
+            zone: None,
+            on_click: Message::MeteogramClicked,
         }
     }
 
-    fn get_precipitation_probability(&self, target_time: &str) -> Option<f64> {
-        if let Ok(target_dt) = DateTime::parse_from_rfc3339(target_time) {
-            let target_timestamp = target_dt.timestamp();
-
-            // First try 1hr forecasts for more granular data
-            for precip in &self.precip_1hr {
-                if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
-                    let valid_timestamp = valid_dt.timestamp();
-                    if target_timestamp <= valid_timestamp
-                        && target_timestamp > valid_timestamp - 3600
-                    {
-                        return Some(precip.prob_precip_pct);
-                    }
-                }
-            }
-
-            // Fallback to 6hr forecasts
-            for precip in &self.precip_6hr {
-                if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
-                    let valid_timestamp = valid_dt.timestamp();
-                    if target_timestamp <= valid_timestamp
-                        && target_timestamp > valid_timestamp - 21600
-                    {
-                        return Some(precip.prob_precip_pct);
-                    }
-                }
-            }
-        }
-        None
+    // Highlight the (start, end) timestamps of triggered alerts
+    pub fn with_alerts(mut self, alert_spans: Vec<(i64, i64)>) -> Self {
+        self.alert_spans = alert_spans;
+        self
     }
 
-    fn get_element_value(&self, point: &WeatherDataPoint) -> Option<f64> {
-        match self.selected_element {
-            WeatherElement::Temperature => point.temperature(),
-            WeatherElement::WBGT => match point {
-                WeatherDataPoint::Forecast(fc) => fc.wbg_temp_2m_f,
-                WeatherDataPoint::Observation(_) => None,
-            },
-            WeatherElement::WindSpeed => match point {
-                WeatherDataPoint::Observation(obs) => obs.wind_spd_10m_mph,
-                WeatherDataPoint::Forecast(fc) => fc.wind_spd_10m_mph,
-            },
-            WeatherElement::Pressure => match point {
-                WeatherDataPoint::Observation(obs) => obs.pressure_h_pa,
-                WeatherDataPoint::Forecast(_) => None,
-            },
-            WeatherElement::Humidity => match point {
-                WeatherDataPoint::Observation(obs) => {
-                    if let Some(humidity) = obs.specific_humidity_2m_dg_kg {
-                        Some(humidity / 10.0)
-                    } else if let (Some(dewpoint), Some(temp)) =
-                        (obs.dewpoint_2m_f, obs.temperature_2m_f)
-                    {
-                        Some(weather::dewpoint_to_relative_humidity(dewpoint, temp))
-                    } else {
-                        None
-                    }
-                }
-                WeatherDataPoint::Forecast(fc) => {
-                    if let (Some(dewpoint), Some(temp)) = (fc.dewpoint_2m_f, fc.temperature_2m_f) {
-                        Some(weather::dewpoint_to_relative_humidity(dewpoint, temp))
-                    } else {
-                        None
-                    }
-                }
-            },
-            WeatherElement::Dewpoint => match point {
-                WeatherDataPoint::Observation(obs) => {
-                    if let Some(dewpoint) = obs.dewpoint_2m_f {
-                        Some(dewpoint)
-                    } else if let (Some(humidity), Some(temp)) =
-                        (obs.specific_humidity_2m_dg_kg, obs.temperature_2m_f)
-                    {
-                        Some(weather::relative_humidity_to_dewpoint(
-                            humidity / 10.0,
-                            temp,
-                        ))
-                    } else {
-                        None
-                    }
-                }
-                WeatherDataPoint::Forecast(fc) => fc.dewpoint_2m_f,
-            },
-            WeatherElement::CloudCover => match point {
-                WeatherDataPoint::Observation(obs) => obs.cloud_cover_pct,
-                WeatherDataPoint::Forecast(fc) => fc.cloud_cover_pct,
-            },
-            WeatherElement::Visibility => match point {
-                WeatherDataPoint::Observation(obs) => obs.visibility_m.map(|v| v / 1609.34),
-                WeatherDataPoint::Forecast(fc) => fc.visibility_m.map(|v| v / 1609.34),
-            },
-            WeatherElement::SolarFlux => match point {
-                WeatherDataPoint::Observation(obs) => obs.solar_flux_w_m2,
-                WeatherDataPoint::Forecast(fc) => fc.solar_flux_w_m2,
-            },
-            WeatherElement::ThunderstormProbability => match point {
-                WeatherDataPoint::Observation(_) => None,
-                WeatherDataPoint::Forecast(fc) => fc.prob_thunderstorm_pct,
-            },
-            WeatherElement::CAPE => match point {
-                WeatherDataPoint::Observation(_) => None,
-                WeatherDataPoint::Forecast(fc) => fc.cape_surface_j_kg,
-            },
-            WeatherElement::ApparentTemperature => {
-                let temp = point.temperature()?;
-                let (humidity, wind) = match point {
-                    WeatherDataPoint::Observation(obs) => {
-                        let humidity = if let Some(h) = obs.specific_humidity_2m_dg_kg {
-                            h / 10.0
-                        } else if let Some(d) = obs.dewpoint_2m_f {
-                            weather::dewpoint_to_relative_humidity(d, temp)
-                        } else {
-                            50.0
-                        };
-                        (humidity, obs.wind_spd_10m_mph.unwrap_or(0.0))
-                    }
-                    WeatherDataPoint::Forecast(fc) => {
-                        let humidity = if let Some(d) = fc.dewpoint_2m_f {
-                            weather::dewpoint_to_relative_humidity(d, temp)
-                        } else {
-                            50.0
-                        };
-                        (humidity, fc.wind_spd_10m_mph.unwrap_or(0.0))
-                    }
-                };
-                Some(weather::calculate_apparent_temperature(
-                    temp, humidity, wind,
-                ))
-            }
-            WeatherElement::PrecipitationProbability => {
-                self.get_precipitation_probability(point.valid_date())
-            }
-        }
+    // Mark these values instead of plotting them
+    pub fn with_qc_flags(mut self, qc_flags: Vec<QcFlag>) -> Self {
+        self.qc_flags = qc_flags;
+        self
     }
-}
-
-impl canvas::Program<Message> for Meteogram {
-    type State = ();
-
-    fn update(
-        &self,
-        _state: &mut Self::State,
-        event: canvas::Event,
-        bounds: Rectangle,
-        cursor: iced::mouse::Cursor,
-    ) -> (canvas::event::Status, Option<Message>) {
-        match event {
-            canvas::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left)) => {
-                if let Some(cursor_position) = cursor.position_in(bounds) {
-                    if !self.timeline.is_empty() {
-                        let start_time = if let Ok(dt) = DateTime::parse_from_rfc3339(
-                            self.timeline.first().unwrap().valid_date(),
-                        ) {
-                            dt.timestamp()
-                        } else {
-                            return (canvas::event::Status::Ignored, None);
-                        };
-
-                        let end_time = if let Ok(dt) =
-                            DateTime::parse_from_rfc3339(self.timeline.last().unwrap().valid_date())
-                        {
-                            dt.timestamp()
-                        } else {
-                            return (canvas::event::Status::Ignored, None);
-                        };
 
-                        let time_range = end_time - start_time;
-                        let left_margin = 5.0;
-                        let right_margin = 10.0;
-                        let graph_width = bounds.width - left_margin - right_margin;
-
-                        if cursor_position.x >= left_margin
-                            && cursor_position.x <= bounds.width - right_margin
-                        {
-                            let click_ratio = (cursor_position.x - left_margin) / graph_width;
-                            let clicked_time =
-                                start_time + (click_ratio * time_range as f32) as i64;
-
-                            let mut closest_index = 0;
-                            let mut min_diff = i64::MAX;
-
-                            for (i, point) in self.timeline.iter().enumerate() {
-                                if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                                    let diff = (dt.timestamp() - clicked_time).abs();
-                                    if diff < min_diff {
-                                        min_diff = diff;
-                                        closest_index = i;
-                                    }
-                                }
-                            }
-
-                            return (
-                                canvas::event::Status::Captured,
-                                Some(Message::MeteogramClicked(closest_index)),
-                            );
-                        }
-                    }
-                }
-                (canvas::event::Status::Ignored, None)
-            }
-            _ => (canvas::event::Status::Ignored, None),
-        }
+    // Draw these earlier runs faded behind the temperature line
+    pub fn with_previous_runs(mut self, previous_runs: Vec<Vec<(i64, f64)>>) -> Self {
+        self.previous_runs = previous_runs;
+        self
     }
 
-    fn draw(
-        &self,
-        _state: &Self::State,
-        renderer: &iced::Renderer,
-        _theme: &Theme,
-        bounds: Rectangle,
-        _cursor: iced::mouse::Cursor,
-    ) -> Vec<canvas::Geometry> {
-        let mut frame = Frame::new(renderer, bounds.size());
-
-        // Background
-        frame.fill_rectangle(
-            Point::ORIGIN,
-            bounds.size(),
-            Color::from_rgb(0.95, 0.95, 0.95),
-        );
+    // Send `message` instead of MeteogramClicked when a point is clicked
+    pub fn on_click(mut self, message: fn(usize) -> Message) -> Self {
+        self.on_click = message;
+        self
+    }
 
-        // Draw alternating day backgrounds
-        if !self.timeline.is_empty() {
-            let start_time = if let Ok(dt) =
-                DateTime::parse_from_rfc3339(self.timeline.first().unwrap().valid_date())
-            {
-                dt.timestamp()
-            } else {
-                return vec![frame.into_geometry()];
-            };
+    fn is_flagged(&self, point: &WeatherDataPoint, element: &WeatherElement) -> bool {
+        qc::flag_for(&self.qc_flags, point, element).is_some()
+    }
 
-            let end_time = if let Ok(dt) =
-                DateTime::parse_from_rfc3339(self.timeline.last().unwrap().valid_date())
-            {
-                dt.timestamp()
-            } else {
-                return vec![frame.into_geometry()];
-            };
+    // Draw the "now" line at this timestamp instead of the current time
+    #[cfg(test)]
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
 
-            let time_range = end_time - start_time;
-            let left_margin = 5.0;
-            let right_margin = 10.0;
-            let graph_width = bounds.width - left_margin - right_margin;
+    // Draw days and hours at this offset from UTC, whatever the machine's time zone
+    #[cfg(test)]
+    pub fn with_zone(mut self, zone: FixedOffset) -> Self {
+        self.zone = Some(zone);
+        self
+    }
 
-            let mut current_day: i32 = -1;
-            for (_i, point) in self.timeline.iter().enumerate() {
-                if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                    let local_time: DateTime<Local> = dt.with_timezone(&Local);
-                    let day = local_time.ordinal();
+    fn get_element_value(&self, point: &WeatherDataPoint, element: &WeatherElement) -> Option<f64> {
+        timeline::element_value(self.weather.as_ref()?, point, element)
+    }
 
-                    if current_day != -1 && day != current_day as u32 {
-                        let x_pos = left_margin
-                            + ((dt.timestamp() - start_time) as f32 / time_range as f32)
-                                * graph_width;
-                        frame.fill_rectangle(
-                            Point::new(x_pos, 0.0),
-                            iced::Size::new(bounds.width, bounds.height),
-                            if day % 2 == 0 {
-                                Color::from_rgb(0.9, 0.9, 0.9)
-                            } else {
-                                Color::from_rgb(0.95, 0.95, 0.95)
-                            },
-                        );
-                    }
-                    current_day = day as i32;
-                }
-            }
-        }
+    // Draw the meteogram onto any target, so the on-screen canvas and exports share geometry
+    pub fn render<T: DrawTarget>(&self, target: &mut T, colors: &ChartColors, bounds: Size) {
+        // Background and alternating day shading
+        draw_day_backgrounds(target, colors, &self.timeline, self.zone, bounds);
+        draw_alert_spans(target, colors, &self.timeline, &self.alert_spans, bounds);
 
         // Draw temperature line
         if !self.timeline.is_empty() {
@@ -313,7 +127,7 @@ impl canvas::Program<Message> for Meteogram {
             {
                 dt.timestamp()
             } else {
-                return vec![frame.into_geometry()];
+                return;
             };
 
             let end_time = if let Ok(dt) =
@@ -321,7 +135,7 @@ impl canvas::Program<Message> for Meteogram {
             {
                 dt.timestamp()
             } else {
-                return vec![frame.into_geometry()];
+                return;
             };
 
             let time_range = end_time - start_time;
@@ -336,52 +150,26 @@ impl canvas::Program<Message> for Meteogram {
             let max_temp = self.unified_temp_max;
 
             if min_temp != f64::MAX && max_temp != f64::MIN {
-                // Draw selected element line first (behind temperature) if different from temperature
-                if self.selected_element != WeatherElement::Temperature {
-                    // For temperature-related elements (WBGT, ApparentTemperature), use the same scale as temperature
-                    let use_temp_scale = matches!(
-                        self.selected_element,
-                        WeatherElement::WBGT | WeatherElement::ApparentTemperature
-                    );
+                // Draw overlay lines first (behind temperature), each in its own color
+                for series in &self.overlays {
+                    if series.element == WeatherElement::Temperature {
+                        continue;
+                    }
 
-                    let (min_element, max_element) = if use_temp_scale {
-                        // Use the same temperature scale
+                    // Elements sharing units with temperature use the temperature scale
+                    let (min_element, max_element) = if series.element.shares_temp_axis() {
                         (min_temp, max_temp)
                     } else {
-                        // Calculate separate scale for non-temperature elements
-                        let element_values: Vec<Option<f64>> = self
-                            .timeline
-                            .iter()
-                            .map(|point| self.get_element_value(point))
-                            .collect();
-                        let mut min_elem = f64::MAX;
-                        let mut max_elem = f64::MIN;
-                        for value in &element_values {
-                            if let Some(v) = value {
-                                min_elem = min_elem.min(*v);
-                                max_elem = max_elem.max(*v);
-                            }
-                        }
-
-                        if min_elem != f64::MAX && max_elem != f64::MIN {
-                            let element_range = max_elem - min_elem;
-                            if element_range > 0.0 {
-                                min_elem -= element_range * 0.1;
-                                max_elem += element_range * 0.1;
-                            } else {
-                                min_elem -= 1.0;
-                                max_elem += 1.0;
-                            }
-                        }
-                        (min_elem, max_elem)
+                        (series.min, series.max)
                     };
 
                     if min_element != f64::MAX && max_element != f64::MIN {
                         let mut element_points = Vec::new();
+                        let mut flagged_points = Vec::new();
                         for point in &self.timeline {
                             if let (Ok(dt), Some(value)) = (
                                 DateTime::parse_from_rfc3339(point.valid_date()),
-                                self.get_element_value(point),
+                                self.get_element_value(point, &series.element),
                             ) {
                                 let x = left_margin
                                     + ((dt.timestamp() - start_time) as f32 / time_range as f32)
@@ -391,30 +179,68 @@ impl canvas::Program<Message> for Meteogram {
                                         - (value - min_element) as f32
                                             / (max_element - min_element) as f32)
                                         * graph_height;
-                                element_points.push(Point::new(x, y));
+                                if self.is_flagged(point, &series.element) {
+                                    let y = y.clamp(top_margin, top_margin + graph_height);
+                                    flagged_points.push(Point::new(x, y));
+                                } else {
+                                    element_points.push(Point::new(x, y));
+                                }
                             }
                         }
 
-                        // Draw element line in grey
                         for i in 1..element_points.len() {
-                            frame.stroke(
-                                &canvas::Path::line(element_points[i - 1], element_points[i]),
-                                canvas::Stroke::default()
-                                    .with_width(2.0)
-                                    .with_color(Color::from_rgb(0.5, 0.5, 0.5)),
+                            target.line(
+                                element_points[i - 1],
+                                element_points[i],
+                                colors.series(series.element.color()),
+                                2.0,
                             );
                         }
+                        for point in flagged_points {
+                            draw_qc_marker(target, colors, point);
+                        }
+                    }
+                }
+
+                // Earlier runs as a faded spaghetti set, the oldest faintest; hours missing
+                // from a run break its line
+                let run_count = self.previous_runs.len() as f32;
+                for (age, run) in self.previous_runs.iter().enumerate() {
+                    let color = Color {
+                        a: 0.6 * (1.0 - age as f32 / (run_count + 1.0)),
+                        ..colors.muted
+                    };
+                    let points: Vec<(i64, Point)> = run
+                        .iter()
+                        .map(|(time, temp)| {
+                            let x = left_margin
+                                + ((time - start_time) as f32 / time_range as f32) * graph_width;
+                            let y = top_margin
+                                + (1.0 - ((temp - min_temp) / (max_temp - min_temp)) as f32)
+                                    * graph_height;
+                            (*time, Point::new(x, y))
+                        })
+                        .collect();
+                    for pair in points.windows(2) {
+                        if pair[1].0 - pair[0].0 <= 3 * 3600 {
+                            target.line(pair[0].1, pair[1].1, color, 1.5);
+                        }
                     }
                 }
 
                 // Draw temperature line with color-coded segments - create smooth curve with many small segments
                 let segments_per_section = 10; // Number of interpolated segments between each data point
 
+                // Flagged temperatures break the line like missing ones
+                let temperature_at = |i: usize| {
+                    let point = &self.timeline[i];
+                    point
+                        .temperature()
+                        .filter(|_| !self.is_flagged(point, &WeatherElement::Temperature))
+                };
+
                 for i in 0..self.timeline.len() - 1 {
-                    if let (Some(temp1), Some(temp2)) = (
-                        self.timeline[i].temperature(),
-                        self.timeline[i + 1].temperature(),
-                    ) {
+                    if let (Some(temp1), Some(temp2)) = (temperature_at(i), temperature_at(i + 1)) {
                         if let (Ok(dt1), Ok(dt2)) = (
                             DateTime::parse_from_rfc3339(self.timeline[i].valid_date()),
                             DateTime::parse_from_rfc3339(self.timeline[i + 1].valid_date()),
@@ -446,54 +272,68 @@ impl canvas::Program<Message> for Meteogram {
                                 // Interpolate temperature for this micro-segment
                                 let temp_mid = temp1 + ((t1 + t2) / 2.0) as f64 * (temp2 - temp1);
 
-                                // Use actual temperature for realistic color scale
-                                let color = match temp_mid {
-                                    t if t < 32.0 => Color::from_rgb(0.0, 0.0, 1.0), // Blue - freezing
-                                    t if t < 50.0 => Color::from_rgb(0.0, 0.5, 1.0), // Light blue - cold
-                                    t if t < 65.0 => Color::from_rgb(0.0, 0.8, 0.8), // Cyan - cool
-                                    t if t < 75.0 => Color::from_rgb(0.0, 1.0, 0.0), // Green - comfortable
-                                    t if t < 85.0 => Color::from_rgb(1.0, 1.0, 0.0), // Yellow - warm
-                                    t if t < 95.0 => Color::from_rgb(1.0, 0.5, 0.0), // Orange - hot
-                                    _ => Color::from_rgb(1.0, 0.0, 0.0), // Red - very hot
-                                };
+                                let color = self.color_ramp.color_at(temp_mid);
 
                                 // Draw micro-segment
-                                frame.stroke(
-                                    &canvas::Path::line(
-                                        Point::new(x_start, y_start),
-                                        Point::new(x_end, y_end),
-                                    ),
-                                    canvas::Stroke::default().with_color(color).with_width(4.0),
+                                target.line(
+                                    Point::new(x_start, y_start),
+                                    Point::new(x_end, y_end),
+                                    color,
+                                    4.0,
                                 );
                             }
                         }
                     }
                 }
 
-                // Draw day of week labels centered over each day
+                for point in &self.timeline {
+                    if let (Ok(dt), Some(temp)) = (
+                        DateTime::parse_from_rfc3339(point.valid_date()),
+                        point.temperature(),
+                    ) {
+                        if self.is_flagged(point, &WeatherElement::Temperature) {
+                            let x = left_margin
+                                + ((dt.timestamp() - start_time) as f32 / time_range as f32)
+                                    * graph_width;
+                            let y = top_margin
+                                + (1.0 - ((temp - min_temp) / (max_temp - min_temp)) as f32)
+                                    * graph_height;
+                            let y = y.clamp(top_margin, top_margin + graph_height);
+                            draw_qc_marker(target, colors, Point::new(x, y));
+                        }
+                    }
+                }
+
+                // Draw day of week labels centered over each day, skipping days too narrow for
+                // one, as over a long history range
                 let mut labeled_days = std::collections::HashSet::new();
+                let mut last_day_label_x = f32::NEG_INFINITY;
                 for point in &self.timeline {
                     if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                        let local_dt: DateTime<Local> = dt.with_timezone(&Local);
-                        let day = local_dt.ordinal();
+                        let local_dt = in_zone(dt, self.zone);
+                        let day = local_dt.date_naive();
 
                         if !labeled_days.contains(&day) {
                             // Find the center of this day by looking for noon (12:00)
-                            let noon_time = local_dt
-                                .date_naive()
-                                .and_hms_opt(12, 0, 0)
-                                .unwrap()
-                                .and_local_timezone(Local)
-                                .single()
-                                .unwrap();
-
-                            let noon_timestamp = noon_time.timestamp();
+                            let noon = local_dt.date_naive().and_hms_opt(12, 0, 0).unwrap();
+                            let noon_timestamp = match self.zone {
+                                Some(zone) => noon.and_local_timezone(zone).single(),
+                                None => noon
+                                    .and_local_timezone(Local)
+                                    .single()
+                                    .map(|time| time.fixed_offset()),
+                            }
+                            .unwrap()
+                            .timestamp();
                             let time_offset = noon_timestamp - start_time;
                             let x = left_margin
                                 + (time_offset as f32 / time_range as f32) * graph_width;
 
                             // Only draw if the center is within our time range
-                            if time_offset >= 0 && time_offset <= time_range {
+                            if time_offset >= 0
+                                && time_offset <= time_range
+                                && x - last_day_label_x >= MIN_DAY_LABEL_SPACING
+                            {
                                 // Day of week label with date (abbreviated)
                                 let weekday_name = match local_dt.weekday() {
                                     chrono::Weekday::Mon => "Mon",
@@ -511,19 +351,17 @@ impl canvas::Program<Message> for Meteogram {
                                     local_dt.day()
                                 );
 
-                                frame.fill_text(Text {
-                                    content: day_label.to_string(),
-                                    position: Point::new(x, 2.0),
-                                    size: 14.0.into(),
-                                    color: Color::from_rgb(0.2, 0.2, 0.2),
-                                    font: iced::Font::default(),
-                                    horizontal_alignment: iced::alignment::Horizontal::Center,
-                                    vertical_alignment: iced::alignment::Vertical::Top,
-                                    line_height: iced::widget::text::LineHeight::default(),
-                                    shaping: iced::widget::text::Shaping::default(),
-                                });
+                                target.text(
+                                    &day_label,
+                                    Point::new(x, 2.0),
+                                    14.0,
+                                    colors.label,
+                                    Horizontal::Center,
+                                    Vertical::Top,
+                                );
 
                                 labeled_days.insert(day);
+                                last_day_label_x = x;
                             }
                         }
                     }
@@ -531,11 +369,11 @@ impl canvas::Program<Message> for Meteogram {
 
                 // Draw time labels approximately every 4 hours
                 let mut last_labeled_hour: Option<u32> = None;
+                let mut last_hour_label_x = f32::NEG_INFINITY;
 
                 for point in &self.timeline {
                     if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                        let local_dt: DateTime<Local> = dt.with_timezone(&Local);
-                        let hour = local_dt.hour();
+                        let hour = in_zone(dt, self.zone).hour();
 
                         // Show labels roughly every 4 hours, but adapt to available data
                         let should_show_label = match last_labeled_hour {
@@ -551,11 +389,11 @@ impl canvas::Program<Message> for Meteogram {
                             }
                         };
 
-                        if should_show_label {
-                            let time_offset = dt.timestamp() - start_time;
-                            let x = left_margin
-                                + (time_offset as f32 / time_range as f32) * graph_width;
+                        let time_offset = dt.timestamp() - start_time;
+                        let x =
+                            left_margin + (time_offset as f32 / time_range as f32) * graph_width;
 
+                        if should_show_label && x - last_hour_label_x >= MIN_HOUR_LABEL_SPACING {
                             // Time label
                             let time_label = if hour == 0 {
                                 "12A".to_string()
@@ -567,59 +405,55 @@ impl canvas::Program<Message> for Meteogram {
                                 format!("{}P", hour - 12)
                             };
 
-                            frame.fill_text(Text {
-                                content: time_label,
-                                position: Point::new(x, 18.0),
-                                size: 12.0.into(),
-                                color: Color::BLACK,
-                                font: iced::Font::default(),
-                                horizontal_alignment: iced::alignment::Horizontal::Center,
-                                vertical_alignment: iced::alignment::Vertical::Top,
-                                line_height: iced::widget::text::LineHeight::default(),
-                                shaping: iced::widget::text::Shaping::default(),
-                            });
+                            target.text(
+                                &time_label,
+                                Point::new(x, 18.0),
+                                12.0,
+                                colors.text,
+                                Horizontal::Center,
+                                Vertical::Top,
+                            );
 
                             last_labeled_hour = Some(hour);
+                            last_hour_label_x = x;
                         }
                     }
                 }
 
-                // Draw vertical line for "now" (current time)
-                let now = Local::now();
-                let mut closest_now_index = 0;
-                let mut min_diff = i64::MAX;
-
-                for (i, point) in self.timeline.iter().enumerate() {
-                    if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
-                        let local_dt: DateTime<Local> = dt.with_timezone(&Local);
-                        let diff = (now.timestamp() - local_dt.timestamp()).abs();
-                        if diff < min_diff {
-                            min_diff = diff;
-                            closest_now_index = i;
+                // Draw vertical line for "now" (current time), unless the chart is all past or
+                // all future, as in the history view
+                if (start_time..=end_time).contains(&self.now) {
+                    let mut closest_now_index = 0;
+                    let mut min_diff = i64::MAX;
+
+                    for (i, point) in self.timeline.iter().enumerate() {
+                        if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
+                            let diff = (self.now - dt.timestamp()).abs();
+                            if diff < min_diff {
+                                min_diff = diff;
+                                closest_now_index = i;
+                            }
                         }
                     }
-                }
 
-                let now_x = if let Some(now_point) = self.timeline.get(closest_now_index) {
-                    if let Ok(dt) = DateTime::parse_from_rfc3339(now_point.valid_date()) {
-                        let time_offset = dt.timestamp() - start_time;
-                        left_margin + (time_offset as f32 / time_range as f32) * graph_width
+                    let now_x = if let Some(now_point) = self.timeline.get(closest_now_index) {
+                        if let Ok(dt) = DateTime::parse_from_rfc3339(now_point.valid_date()) {
+                            let time_offset = dt.timestamp() - start_time;
+                            left_margin + (time_offset as f32 / time_range as f32) * graph_width
+                        } else {
+                            left_margin
+                        }
                     } else {
                         left_margin
-                    }
-                } else {
-                    left_margin
-                };
+                    };
 
-                frame.stroke(
-                    &canvas::Path::line(
+                    target.line(
                         Point::new(now_x, top_margin),
                         Point::new(now_x, bounds.height - 20.0),
-                    ),
-                    canvas::Stroke::default()
-                        .with_color(Color::from_rgb(0.0, 0.0, 1.0)) // Blue for "now"
-                        .with_width(2.0),
-                );
+                        colors.accent,
+                        2.0,
+                    );
+                }
 
                 // Draw selected point indicator
                 if let Some(selected_point) = self.timeline.get(self.selected_index) {
@@ -633,15 +467,195 @@ impl canvas::Program<Message> for Meteogram {
                         let y = top_margin
                             + (1.0 - (temp - min_temp) as f32 / (max_temp - min_temp) as f32)
                                 * graph_height;
-                        frame.fill(
-                            &canvas::Path::circle(Point::new(x, y), 4.0),
-                            Color::from_rgb(0.0, 0.0, 1.0),
+                        target.fill_circle(Point::new(x, y), 4.0, colors.accent);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl canvas::Program<Message> for Meteogram {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        match event {
+            canvas::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left)) => {
+                if let Some(cursor_position) = cursor.position_in(bounds) {
+                    if let Some(index) =
+                        closest_index_at(&self.timeline, cursor_position.x, bounds.width)
+                    {
+                        return (
+                            canvas::event::Status::Captured,
+                            Some((self.on_click)(index)),
                         );
                     }
                 }
+                (canvas::event::Status::Ignored, None)
             }
+            _ => (canvas::event::Status::Ignored, None),
         }
+    }
 
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        self.render(&mut frame, &ChartColors::from_theme(theme), bounds.size());
         vec![frame.into_geometry()]
     }
 }
+
+// Horizontal margins shared by every canvas drawn against the meteogram time axis
+pub const LEFT_MARGIN: f32 = 5.0;
+pub const RIGHT_MARGIN: f32 = 10.0;
+
+// Closest that label centers come, so a long range drawn narrow doesn't stack them on top of
+// each other
+const MIN_DAY_LABEL_SPACING: f32 = 80.0;
+const MIN_HOUR_LABEL_SPACING: f32 = 30.0;
+
+// First and last timestamps of the timeline
+pub fn time_bounds(timeline: &[WeatherDataPoint]) -> Option<(i64, i64)> {
+    let start_time = DateTime::parse_from_rfc3339(timeline.first()?.valid_date())
+        .ok()?
+        .timestamp();
+    let end_time = DateTime::parse_from_rfc3339(timeline.last()?.valid_date())
+        .ok()?
+        .timestamp();
+    Some((start_time, end_time))
+}
+
+// Map a timestamp to an x position on a canvas of the given width
+pub fn time_to_x(timestamp: i64, start_time: i64, end_time: i64, width: f32) -> f32 {
+    let graph_width = width - LEFT_MARGIN - RIGHT_MARGIN;
+    let time_range = (end_time - start_time).max(1);
+    LEFT_MARGIN + ((timestamp - start_time) as f32 / time_range as f32) * graph_width
+}
+
+// Timeline index closest to a clicked x position, if the click is inside the graph area
+pub fn closest_index_at(timeline: &[WeatherDataPoint], x: f32, width: f32) -> Option<usize> {
+    let (start_time, end_time) = time_bounds(timeline)?;
+    let time_range = end_time - start_time;
+    let graph_width = width - LEFT_MARGIN - RIGHT_MARGIN;
+
+    if x < LEFT_MARGIN || x > width - RIGHT_MARGIN {
+        return None;
+    }
+
+    let click_ratio = (x - LEFT_MARGIN) / graph_width;
+    let clicked_time = start_time + (click_ratio * time_range as f32) as i64;
+
+    let mut closest_index = 0;
+    let mut min_diff = i64::MAX;
+
+    for (i, point) in timeline.iter().enumerate() {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
+            let diff = (dt.timestamp() - clicked_time).abs();
+            if diff < min_diff {
+                min_diff = diff;
+                closest_index = i;
+            }
+        }
+    }
+
+    Some(closest_index)
+}
+
+// Shade the hours covered by triggered alerts, with a solid strip along the top
+pub fn draw_alert_spans<T: DrawTarget>(
+    target: &mut T,
+    colors: &ChartColors,
+    timeline: &[WeatherDataPoint],
+    spans: &[(i64, i64)],
+    bounds: Size,
+) {
+    let Some((start_time, end_time)) = time_bounds(timeline) else {
+        return;
+    };
+
+    for (start, end) in spans {
+        // Each forecast hour covers half an hour either side of its point
+        let x1 = time_to_x(start - 1800, start_time, end_time, bounds.width).max(0.0);
+        let x2 = time_to_x(end + 1800, start_time, end_time, bounds.width).min(bounds.width);
+        if x2 <= x1 {
+            continue;
+        }
+        target.fill_rect(
+            Point::new(x1, 0.0),
+            Size::new(x2 - x1, bounds.height),
+            Color {
+                a: 0.15,
+                ..colors.alert
+            },
+        );
+        target.fill_rect(Point::new(x1, 0.0), Size::new(x2 - x1, 3.0), colors.alert);
+    }
+}
+
+// A value that failed the quality checks, drawn in place of the data point. Off-scale
+// values are pinned to the edge of the plot.
+pub fn draw_qc_marker<T: DrawTarget>(target: &mut T, colors: &ChartColors, center: Point) {
+    target.stroke_circle(center, 5.0, colors.qc, 2.0);
+    target.line(
+        Point::new(center.x - 3.0, center.y - 3.0),
+        Point::new(center.x + 3.0, center.y + 3.0),
+        colors.qc,
+        1.5,
+    );
+}
+
+// `time` in the local time zone, or at `zone` when one is given
+fn in_zone(time: DateTime<FixedOffset>, zone: Option<FixedOffset>) -> DateTime<FixedOffset> {
+    match zone {
+        Some(zone) => time.with_timezone(&zone),
+        None => time.with_timezone(&Local).fixed_offset(),
+    }
+}
+
+// Fill the canvas background, shading every other day, local unless `zone` is given
+pub fn draw_day_backgrounds<T: DrawTarget>(
+    target: &mut T,
+    colors: &ChartColors,
+    timeline: &[WeatherDataPoint],
+    zone: Option<FixedOffset>,
+    bounds: Size,
+) {
+    target.fill_rect(Point::ORIGIN, bounds, colors.background);
+
+    let Some((start_time, end_time)) = time_bounds(timeline) else {
+        return;
+    };
+
+    let mut current_day: i32 = -1;
+    for point in timeline {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
+            let day = in_zone(dt, zone).ordinal();
+
+            if current_day != -1 && day != current_day as u32 {
+                let x_pos = time_to_x(dt.timestamp(), start_time, end_time, bounds.width);
+                target.fill_rect(
+                    Point::new(x_pos, 0.0),
+                    Size::new(bounds.width, bounds.height),
+                    if day.is_multiple_of(2) {
+                        colors.day_shade
+                    } else {
+                        colors.background
+                    },
+                );
+            }
+            current_day = day as i32;
+        }
+    }
+}
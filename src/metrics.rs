@@ -0,0 +1,315 @@
+use crate::cache::{CachedSite, SiteCache};
+use chrono::{DateTime, Utc};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::timeline;
+use rusty_wx::weather::WeatherDataPoint;
+use std::convert::Infallible;
+use std::fmt::Write;
+use std::net::SocketAddr;
+
+// Forecast lead times exported alongside the latest observation
+const HORIZONS_H: [i64; 5] = [1, 3, 6, 12, 24];
+
+fn metric_name(element: &WeatherElement) -> &'static str {
+    match element {
+        WeatherElement::Temperature => "rustywx_temperature_f",
+        WeatherElement::ApparentTemperature => "rustywx_apparent_temperature_f",
+        WeatherElement::WBGT => "rustywx_wbgt_f",
+        WeatherElement::WindSpeed => "rustywx_wind_speed_mph",
+        WeatherElement::WindGust => "rustywx_wind_gust_mph",
+        WeatherElement::Pressure => "rustywx_pressure_hpa",
+        WeatherElement::Humidity => "rustywx_relative_humidity_pct",
+        WeatherElement::Dewpoint => "rustywx_dewpoint_f",
+        WeatherElement::CloudCover => "rustywx_cloud_cover_pct",
+        WeatherElement::Visibility => "rustywx_visibility_mi",
+        WeatherElement::SolarFlux => "rustywx_solar_flux_w_m2",
+        WeatherElement::ThunderstormProbability => "rustywx_thunderstorm_prob_pct",
+        WeatherElement::CAPE => "rustywx_cape_j_kg",
+        WeatherElement::PrecipitationProbability => "rustywx_precip_prob_pct",
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+// The latest observation, then the forecast nearest each horizon, as (labels, point)
+fn sampled_points(
+    timeline: &[WeatherDataPoint],
+    now: DateTime<Utc>,
+) -> Vec<(String, &WeatherDataPoint)> {
+    let mut points = Vec::new();
+    if let Some(obs) = timeline
+        .iter()
+        .rev()
+        .find(|point| matches!(point, WeatherDataPoint::Observation(_)))
+    {
+        points.push(("kind=\"observation\"".to_string(), obs));
+    }
+
+    for hours in HORIZONS_H {
+        let target = now.timestamp() + hours * 3600;
+        let nearest = timeline
+            .iter()
+            .filter(|point| matches!(point, WeatherDataPoint::Forecast(_)))
+            .filter_map(|point| {
+                let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+                Some(((time.timestamp() - target).abs(), point))
+            })
+            .min_by_key(|(distance, _)| *distance);
+        // Skip horizons past the end of the forecast or between widely spaced points
+        if let Some((_, point)) = nearest.filter(|(distance, _)| *distance <= 5400) {
+            points.push((format!("kind=\"forecast\",horizon=\"{}h\"", hours), point));
+        }
+    }
+    points
+}
+
+// Prometheus text exposition format, one family at a time
+pub fn render(sites: &[CachedSite], now: DateTime<Utc>) -> String {
+    let mut out = String::new();
+    let timelines: Vec<(String, Vec<WeatherDataPoint>)> = sites
+        .iter()
+        .map(|site| {
+            let timeline = site
+                .data
+                .as_ref()
+                .map(timeline::build_timeline)
+                .unwrap_or_default();
+            (escape_label(&site.location.name), timeline)
+        })
+        .collect();
+
+    for element in WeatherElement::ALL {
+        let name = metric_name(&element);
+        let _ = writeln!(out, "# HELP {} {}", name, element.label());
+        let _ = writeln!(out, "# TYPE {} gauge", name);
+        for (site, (location, timeline)) in sites.iter().zip(&timelines) {
+            let Some(data) = &site.data else {
+                continue;
+            };
+            for (labels, point) in sampled_points(timeline, now) {
+                if let Some(value) = timeline::element_value(data, point, &element) {
+                    let _ = writeln!(
+                        out,
+                        "{}{{location=\"{}\",{}}} {}",
+                        name, location, labels, value
+                    );
+                }
+            }
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP rustywx_fetch_total Weather API fetches by result"
+    );
+    let _ = writeln!(out, "# TYPE rustywx_fetch_total counter");
+    for (site, (location, _)) in sites.iter().zip(&timelines) {
+        let _ = writeln!(
+            out,
+            "rustywx_fetch_total{{location=\"{}\",result=\"success\"}} {}",
+            location, site.successes
+        );
+        let _ = writeln!(
+            out,
+            "rustywx_fetch_total{{location=\"{}\",result=\"error\"}} {}",
+            location, site.failures
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP rustywx_fetch_duration_seconds Weather API fetch latency"
+    );
+    let _ = writeln!(out, "# TYPE rustywx_fetch_duration_seconds summary");
+    for (site, (location, _)) in sites.iter().zip(&timelines) {
+        let _ = writeln!(
+            out,
+            "rustywx_fetch_duration_seconds_sum{{location=\"{}\"}} {}",
+            location, site.latency_sum
+        );
+        let _ = writeln!(
+            out,
+            "rustywx_fetch_duration_seconds_count{{location=\"{}\"}} {}",
+            location,
+            site.successes + site.failures
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP rustywx_last_success_timestamp_seconds Time of the last successful fetch"
+    );
+    let _ = writeln!(out, "# TYPE rustywx_last_success_timestamp_seconds gauge");
+    for (site, (location, _)) in sites.iter().zip(&timelines) {
+        if let Some(fetched_at) = site.fetched_at {
+            let _ = writeln!(
+                out,
+                "rustywx_last_success_timestamp_seconds{{location=\"{}\"}} {}",
+                location,
+                fetched_at.timestamp()
+            );
+        }
+    }
+
+    out
+}
+
+async fn handle(cache: SiteCache, request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let response = match (request.method(), request.uri().path()) {
+        (&Method::GET, "/metrics") => Response::builder()
+            .header("Content-Type", "text/plain; version=0.0.4")
+            .body(Body::from(render(&cache.snapshot(), Utc::now()))),
+        _ => Response::builder()
+            .status(StatusCode::NOT_FOUND)
+            .body(Body::from("Not found; metrics are at /metrics\n")),
+    };
+    Ok(response.unwrap_or_else(|_| Response::new(Body::empty())))
+}
+
+// Serve /metrics until the process is stopped, refreshing the cache in the background
+pub async fn serve(
+    addr: SocketAddr,
+    cache: SiteCache,
+    interval: std::time::Duration,
+) -> Result<(), String> {
+    tokio::spawn(cache.clone().refresh_every(interval));
+
+    let make_service = make_service_fn(move |_connection| {
+        let cache = cache.clone();
+        async move { Ok::<_, Infallible>(service_fn(move |request| handle(cache.clone(), request))) }
+    });
+    let server = Server::try_bind(&addr)
+        .map_err(|e| format!("{}: {}", addr, e))?
+        .serve(make_service);
+    eprintln!("Serving metrics on http://{}/metrics", addr);
+    server.await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SavedLocation;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    fn time(rfc3339: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(rfc3339)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    // Observations up to 2026-10-18T12Z, forecasts from 13Z to 2026-10-21T12Z
+    fn site(name: &str, fetched: bool) -> CachedSite {
+        CachedSite {
+            id: "site".to_string(),
+            location: SavedLocation {
+                name: name.to_string(),
+                lat: 39.74,
+                lon: -104.99,
+            },
+            data: fetched.then(|| serde_json::from_str(FIXTURE).unwrap()),
+            fetched_at: fetched.then(|| time("2026-10-18T12:20:00Z")),
+            last_error: (!fetched).then(|| "timed out".to_string()),
+            successes: u64::from(fetched),
+            failures: 2,
+            latency_sum: 1.5,
+            last_latency: Some(0.5),
+        }
+    }
+
+    fn lines(text: &str, prefix: &str) -> Vec<String> {
+        text.lines()
+            .filter(|line| line.starts_with(prefix))
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn exposition_of_the_fixture() {
+        let text = render(&[site("Denver", true)], time("2026-10-18T12:30:00Z"));
+        assert_eq!(
+            lines(&text, "rustywx_temperature_f{"),
+            [
+                "rustywx_temperature_f{location=\"Denver\",kind=\"observation\"} 63.48528137423857",
+                "rustywx_temperature_f{location=\"Denver\",kind=\"forecast\",horizon=\"1h\"} 70.12435565298215",
+                "rustywx_temperature_f{location=\"Denver\",kind=\"forecast\",horizon=\"3h\"} 72",
+                "rustywx_temperature_f{location=\"Denver\",kind=\"forecast\",horizon=\"6h\"} 67.89949493661166",
+                "rustywx_temperature_f{location=\"Denver\",kind=\"forecast\",horizon=\"12h\"} 48.10050506338833",
+                "rustywx_temperature_f{location=\"Denver\",kind=\"forecast\",horizon=\"24h\"} 67.89949493661166",
+            ]
+        );
+        // Observations have no WBGT and forecasts no pressure
+        assert_eq!(lines(&text, "rustywx_wbgt_f{").len(), 5);
+        assert_eq!(
+            lines(&text, "rustywx_pressure_hpa{"),
+            ["rustywx_pressure_hpa{location=\"Denver\",kind=\"observation\"} 1012.198669330795"]
+        );
+
+        // Every family is declared once, even without samples
+        for element in WeatherElement::ALL {
+            let name = metric_name(&element);
+            assert_eq!(lines(&text, &format!("# TYPE {} ", name)).len(), 1);
+        }
+        assert_eq!(
+            lines(&text, "rustywx_fetch"),
+            [
+                "rustywx_fetch_total{location=\"Denver\",result=\"success\"} 1",
+                "rustywx_fetch_total{location=\"Denver\",result=\"error\"} 2",
+                "rustywx_fetch_duration_seconds_sum{location=\"Denver\"} 1.5",
+                "rustywx_fetch_duration_seconds_count{location=\"Denver\"} 3",
+            ]
+        );
+        assert!(text.contains(
+            "\nrustywx_last_success_timestamp_seconds{location=\"Denver\"} 1792326000\n"
+        ));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        let text = render(
+            &[site("Back\\slash \"quoted\"\nname", true)],
+            time("2026-10-18T12:30:00Z"),
+        );
+        assert!(text.contains(
+            "rustywx_fetch_total{location=\"Back\\\\slash \\\"quoted\\\"\\nname\",result=\"success\"} 1\n"
+        ));
+        // Every sample stays on one line
+        assert!(text
+            .lines()
+            .all(|line| line.starts_with('#') || line.starts_with("rustywx_")));
+    }
+
+    // Horizons past the end of the forecast are left out, as are sites not fetched yet
+    // except for their fetch counters
+    #[test]
+    fn horizons_within_the_forecast() {
+        let text = render(
+            &[site("Denver", true), site("Boulder", false)],
+            time("2026-10-21T00:00:00Z"),
+        );
+        let horizons: Vec<String> = lines(&text, "rustywx_wind_speed_mph{location=\"Denver\"")
+            .iter()
+            .filter_map(|line| {
+                Some(
+                    line.split("horizon=\"")
+                        .nth(1)?
+                        .split('"')
+                        .next()?
+                        .to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(horizons, ["1h", "3h", "6h", "12h"]);
+
+        assert!(!text.contains("rustywx_temperature_f{location=\"Boulder\""));
+        assert!(text.contains("rustywx_fetch_total{location=\"Boulder\",result=\"error\"} 2\n"));
+        assert!(!text.contains("rustywx_last_success_timestamp_seconds{location=\"Boulder\"}"));
+    }
+}
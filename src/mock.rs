@@ -0,0 +1,223 @@
+//! A stand-in for the weather API that serves recorded responses, for tests and offline
+//! development. Needs the `mock` feature and a Tokio runtime.
+//!
+//! ```no_run
+//! # async fn example(fixture: rusty_wx::weather::ApiWeatherData) -> Result<(), String> {
+//! use rusty_wx::mock::{MockResponse, MockServer};
+//! use rusty_wx::weather;
+//!
+//! let server = MockServer::start(MockResponse::json(&fixture)).await?;
+//! let data = weather::fetch_weather_from(&server.base_url(), 39.74, -104.99).await?;
+//!
+//! server.set_response(MockResponse::status(503));
+//! assert!(weather::fetch_weather_from(&server.base_url(), 39.74, -104.99).await.is_err());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::weather::ApiWeatherData;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Request, Response, Server, StatusCode};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// What the server answers to `GET /weather`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub body: String,
+    /// Wait this long before answering
+    pub delay: Duration,
+}
+
+impl MockResponse {
+    /// A successful response with `data` as JSON.
+    pub fn json(data: &ApiWeatherData) -> Self {
+        Self::raw(serde_json::to_string(data).unwrap_or_default())
+    }
+
+    /// A successful response with `body` as is, e.g. a recorded fixture file.
+    pub fn raw(body: impl Into<String>) -> Self {
+        Self {
+            status: 200,
+            body: body.into(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    /// An error status with a short plain-text body.
+    pub fn status(status: u16) -> Self {
+        Self {
+            status,
+            body: format!("mock error {}", status),
+            delay: Duration::ZERO,
+        }
+    }
+
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Cut the body in half, like a connection dropped mid-response.
+    pub fn truncated(mut self) -> Self {
+        let mut end = self.body.len() / 2;
+        while !self.body.is_char_boundary(end) {
+            end -= 1;
+        }
+        self.body.truncate(end);
+        self
+    }
+
+    /// Remove these keys from every object in a JSON body, at any depth, e.g.
+    /// `"temperature2mF"` from every observation and forecast. Non-JSON bodies are unchanged.
+    pub fn without_fields(mut self, fields: &[&str]) -> Self {
+        if let Ok(mut value) = serde_json::from_str::<Value>(&self.body) {
+            remove_fields(&mut value, fields);
+            self.body = value.to_string();
+        }
+        self
+    }
+}
+
+fn remove_fields(value: &mut Value, fields: &[&str]) {
+    match value {
+        Value::Object(object) => {
+            object.retain(|key, _| !fields.contains(&key.as_str()));
+            object
+                .values_mut()
+                .for_each(|value| remove_fields(value, fields));
+        }
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| remove_fields(value, fields)),
+        _ => {}
+    }
+}
+
+#[derive(Debug)]
+struct MockState {
+    // Answered once each, in order, before falling back to `response`
+    queued: VecDeque<MockResponse>,
+    response: MockResponse,
+    requests: Vec<String>,
+}
+
+/// A running mock API; stops when dropped.
+#[derive(Debug)]
+pub struct MockServer {
+    addr: SocketAddr,
+    state: Arc<Mutex<MockState>>,
+    shutdown: Option<oneshot::Sender<()>>,
+}
+
+impl MockServer {
+    /// Serve `response` on a free local port.
+    pub async fn start(response: MockResponse) -> Result<MockServer, String> {
+        Self::bind(([127, 0, 0, 1], 0).into(), response).await
+    }
+
+    /// Serve `response` on `addr`.
+    pub async fn bind(addr: SocketAddr, response: MockResponse) -> Result<MockServer, String> {
+        let state = Arc::new(Mutex::new(MockState {
+            queued: VecDeque::new(),
+            response,
+            requests: Vec::new(),
+        }));
+        let service_state = state.clone();
+        let make_service = make_service_fn(move |_connection| {
+            let state = service_state.clone();
+            async move { Ok::<_, Infallible>(service_fn(move |request| handle(state.clone(), request))) }
+        });
+
+        let server = Server::try_bind(&addr)
+            .map_err(|e| format!("{}: {}", addr, e))?
+            .serve(make_service);
+        let addr = server.local_addr();
+        let (shutdown, stopped) = oneshot::channel::<()>();
+        tokio::spawn(server.with_graceful_shutdown(async {
+            let _ = stopped.await;
+        }));
+
+        Ok(MockServer {
+            addr,
+            state,
+            shutdown: Some(shutdown),
+        })
+    }
+
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// The URL to pass to [`crate::weather::fetch_weather_from`] or set as `RUSTY_WX_API_URL`.
+    pub fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer every following request with `response`, dropping any queued ones.
+    pub fn set_response(&self, response: MockResponse) {
+        if let Ok(mut state) = self.state.lock() {
+            state.queued.clear();
+            state.response = response;
+        }
+    }
+
+    /// Answer the next request with `response`, then carry on as before.
+    pub fn push_response(&self, response: MockResponse) {
+        if let Ok(mut state) = self.state.lock() {
+            state.queued.push_back(response);
+        }
+    }
+
+    /// Path and query of every request so far, e.g. `/weather?lat=39.74&lon=-104.99`.
+    pub fn requests(&self) -> Vec<String> {
+        self.state
+            .lock()
+            .map(|state| state.requests.clone())
+            .unwrap_or_default()
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown.take() {
+            let _ = shutdown.send(());
+        }
+    }
+}
+
+async fn handle(
+    state: Arc<Mutex<MockState>>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let uri = request.uri();
+    let path = uri
+        .path_and_query()
+        .map_or(uri.path().to_string(), |p| p.to_string());
+    let response = state.lock().ok().map(|mut state| {
+        state.requests.push(path);
+        if uri.path() == "/weather" {
+            let next = state.queued.pop_front();
+            next.unwrap_or_else(|| state.response.clone())
+        } else {
+            MockResponse::status(404)
+        }
+    });
+    let response = response.unwrap_or_else(|| MockResponse::status(500));
+
+    if !response.delay.is_zero() {
+        tokio::time::sleep(response.delay).await;
+    }
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let built = Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(response.body));
+    Ok(built.unwrap_or_else(|_| Response::new(Body::empty())))
+}
@@ -0,0 +1,399 @@
+use crate::cache::{CachedSite, SiteCache};
+use crate::config::MqttConfig;
+use chrono::{DateTime, Local, Utc};
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::weather::{self, ApiWeatherData, WeatherDataPoint};
+use rusty_wx::{summary, timeline};
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+
+// Home Assistant's state length limit
+const MAX_STATE_LEN: usize = 255;
+
+fn device_class(element: &WeatherElement) -> Option<&'static str> {
+    match element {
+        WeatherElement::Temperature
+        | WeatherElement::ApparentTemperature
+        | WeatherElement::WBGT
+        | WeatherElement::Dewpoint => Some("temperature"),
+        WeatherElement::WindSpeed | WeatherElement::WindGust => Some("wind_speed"),
+        WeatherElement::Pressure => Some("atmospheric_pressure"),
+        WeatherElement::Humidity => Some("humidity"),
+        WeatherElement::Visibility => Some("distance"),
+        WeatherElement::SolarFlux => Some("irradiance"),
+        WeatherElement::CloudCover
+        | WeatherElement::ThunderstormProbability
+        | WeatherElement::CAPE
+        | WeatherElement::PrecipitationProbability => None,
+    }
+}
+
+fn unit(element: &WeatherElement) -> &'static str {
+    match element {
+        WeatherElement::Temperature
+        | WeatherElement::ApparentTemperature
+        | WeatherElement::WBGT
+        | WeatherElement::Dewpoint => "°F",
+        WeatherElement::WindSpeed | WeatherElement::WindGust => "mph",
+        WeatherElement::Pressure => "hPa",
+        WeatherElement::Humidity
+        | WeatherElement::CloudCover
+        | WeatherElement::ThunderstormProbability
+        | WeatherElement::PrecipitationProbability => "%",
+        WeatherElement::Visibility => "mi",
+        WeatherElement::SolarFlux => "W/m²",
+        WeatherElement::CAPE => "J/kg",
+    }
+}
+
+fn state_topic(config: &MqttConfig, site: &CachedSite) -> String {
+    format!("{}/{}/state", config.base_topic, site.id)
+}
+
+fn availability_topic(config: &MqttConfig) -> String {
+    format!("{}/status", config.base_topic)
+}
+
+// Retained discovery configs for one location: a sensor per element plus the forecast text,
+// all grouped under one device
+pub fn discovery_messages(config: &MqttConfig, site: &CachedSite) -> Vec<(String, Value)> {
+    let id = format!("rusty_wx_{}", site.id);
+    let device = json!({
+        "identifiers": [id],
+        "name": format!("Weather {}", site.location.name),
+        "manufacturer": "rusty-wx",
+        "model": weather::PROVIDER,
+    });
+    let state_topic = state_topic(config, site);
+    let availability_topic = availability_topic(config);
+
+    let mut messages: Vec<(String, Value)> = WeatherElement::ALL
+        .iter()
+        .map(|element| {
+            let mut payload = json!({
+                "name": element.label(),
+                "unique_id": format!("{}_{}", id, element.key()),
+                "state_topic": state_topic,
+                "value_template": format!("{{{{ value_json.{} }}}}", element.key()),
+                "unit_of_measurement": unit(element),
+                "state_class": "measurement",
+                "availability_topic": availability_topic,
+                "device": device,
+            });
+            if let Some(class) = device_class(element) {
+                payload["device_class"] = json!(class);
+            }
+            (
+                format!(
+                    "{}/sensor/{}/{}/config",
+                    config.discovery_prefix,
+                    id,
+                    element.key()
+                ),
+                payload,
+            )
+        })
+        .collect();
+
+    messages.push((
+        format!("{}/sensor/{}/forecast/config", config.discovery_prefix, id),
+        json!({
+            "name": "Forecast",
+            "unique_id": format!("{}_forecast", id),
+            "state_topic": state_topic,
+            "value_template": "{{ value_json.forecast }}",
+            "json_attributes_topic": state_topic,
+            "json_attributes_template": "{{ {'periods': value_json.forecast_periods} | tojson }}",
+            "icon": "mdi:weather-partly-cloudy",
+            "availability_topic": availability_topic,
+            "device": device,
+        }),
+    ));
+    messages
+}
+
+// Current conditions: each element from the latest observation, or from the forecast nearest
+// now for the ones observations don't report (WBGT, storm and precipitation chances).
+// Missing values are null, which Home Assistant shows as unknown.
+pub fn state_payload(data: &ApiWeatherData, now: DateTime<Utc>) -> Value {
+    let timeline = timeline::build_timeline(data);
+    let observation = timeline
+        .iter()
+        .rev()
+        .find(|point| matches!(point, WeatherDataPoint::Observation(_)));
+    let nearest = timeline.get(timeline::closest_index(&timeline, now));
+
+    let mut state = Map::new();
+    for element in WeatherElement::ALL {
+        let value = [observation, nearest]
+            .into_iter()
+            .flatten()
+            .find_map(|point| timeline::element_value(data, point, &element));
+        state.insert(
+            element.key().to_string(),
+            json!(value.map(|v| (v * 10.0).round() / 10.0)),
+        );
+    }
+
+    let periods = summary::forecast_summary(
+        &timeline,
+        &data.forecasts_precip_1hr,
+        &data.forecasts_precip_6hr,
+        now,
+        &Local,
+    );
+    let mut headline = periods.first().cloned().unwrap_or_default();
+    if headline.len() > MAX_STATE_LEN {
+        let mut end = MAX_STATE_LEN - 1;
+        while !headline.is_char_boundary(end) {
+            end -= 1;
+        }
+        headline.truncate(end);
+        headline.push('…');
+    }
+    state.insert("forecast".to_string(), json!(headline));
+    state.insert("forecast_periods".to_string(), json!(periods));
+    state.insert("updated".to_string(), json!(now.to_rfc3339()));
+    Value::Object(state)
+}
+
+// Publish discovery once, then states for every cached location after each refresh, every
+// `interval`. With `once`, waits for the broker to acknowledge everything and returns.
+pub async fn run(
+    config: MqttConfig,
+    cache: SiteCache,
+    interval: Duration,
+    once: bool,
+) -> Result<(), String> {
+    let availability = availability_topic(&config);
+    let mut options = MqttOptions::new(&config.client_id, &config.host, config.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        &availability,
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(username) = &config.username {
+        options.set_credentials(username, config.password.clone().unwrap_or_default());
+    }
+
+    let (client, mut eventloop) = AsyncClient::new(options, 64);
+    let (acks_tx, mut acks_rx) = tokio::sync::mpsc::unbounded_channel();
+    let online_client = client.clone();
+    let online_topic = availability.clone();
+    tokio::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                // Also after reconnecting, since the broker will have sent the last will
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    eprintln!("Connected to MQTT broker");
+                    let _ =
+                        online_client.try_publish(&online_topic, QoS::AtLeastOnce, true, "online");
+                }
+                Ok(Event::Incoming(Packet::PubAck(_))) if once => {
+                    let _ = acks_tx.send(());
+                }
+                Ok(_) => {}
+                // The event loop reconnects on the next poll
+                Err(e) => {
+                    eprintln!("MQTT connection error: {}", e);
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                }
+            }
+        }
+    });
+
+    let mut published = 0;
+    for site in cache.snapshot() {
+        for (topic, payload) in discovery_messages(&config, &site) {
+            publish(&client, topic, payload).await?;
+            published += 1;
+        }
+    }
+
+    loop {
+        cache.refresh().await;
+        for site in cache.snapshot() {
+            if let Some(data) = &site.data {
+                publish(
+                    &client,
+                    state_topic(&config, &site),
+                    state_payload(data, Utc::now()),
+                )
+                .await?;
+                published += 1;
+            }
+        }
+
+        if once {
+            // The online message is acknowledged too
+            for _ in 0..=published {
+                tokio::time::timeout(Duration::from_secs(10), acks_rx.recv())
+                    .await
+                    .map_err(|_| "timed out waiting for the MQTT broker".to_string())?;
+            }
+            return client.disconnect().await.map_err(|e| e.to_string());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+// Everything is retained so Home Assistant picks it up whenever it connects
+async fn publish(client: &AsyncClient, topic: String, payload: Value) -> Result<(), String> {
+    client
+        .publish(topic, QoS::AtLeastOnce, true, payload.to_string())
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// `run` against a broker faked in-process with rumqttc's own packet codec, and the mock API
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::SavedLocation;
+    use bytes::BytesMut;
+    use rumqttc::mqttbytes::v4::{self, ConnAck, ConnectReturnCode, PingResp, PubAck};
+    use rusty_wx::mock::{MockResponse, MockServer};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    // Accept one client, acknowledge its connection and QoS 1 publishes, and return what was
+    // published, as (topic, retained, payload), once it disconnects
+    async fn fake_broker(listener: TcpListener) -> Vec<(String, bool, String)> {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let mut input = BytesMut::new();
+        let mut published = Vec::new();
+        loop {
+            let packet = match v4::read(&mut input, 1 << 20) {
+                Ok(packet) => packet,
+                Err(rumqttc::mqttbytes::Error::InsufficientBytes(_)) => {
+                    if stream.read_buf(&mut input).await.unwrap() == 0 {
+                        return published;
+                    }
+                    continue;
+                }
+                Err(e) => panic!("bad packet: {:?}", e),
+            };
+            let mut output = BytesMut::new();
+            match packet {
+                Packet::Connect(_) => {
+                    ConnAck::new(ConnectReturnCode::Success, false)
+                        .write(&mut output)
+                        .unwrap();
+                }
+                Packet::Publish(publish) => {
+                    published.push((
+                        publish.topic.clone(),
+                        publish.retain,
+                        String::from_utf8(publish.payload.to_vec()).unwrap(),
+                    ));
+                    PubAck::new(publish.pkid).write(&mut output).unwrap();
+                }
+                Packet::PingReq => {
+                    PingResp.write(&mut output).unwrap();
+                }
+                Packet::Disconnect => return published,
+                _ => {}
+            }
+            stream.write_all(&output).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_discovery_and_state_once() {
+        let api = MockServer::start(MockResponse::raw(FIXTURE))
+            .await
+            .expect("mock server");
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = MqttConfig {
+            port: listener.local_addr().unwrap().port(),
+            host: "127.0.0.1".to_string(),
+            ..MqttConfig::default()
+        };
+        let broker = tokio::spawn(fake_broker(listener));
+
+        let denver = SavedLocation {
+            name: "Denver".to_string(),
+            lat: 39.74,
+            lon: -104.99,
+        };
+        let cache = SiteCache::new(&[denver]).with_api_url(&api.base_url());
+        tokio::time::timeout(
+            Duration::from_secs(20),
+            run(config, cache, Duration::from_secs(600), true),
+        )
+        .await
+        .expect("run finished")
+        .expect("published");
+        let published = broker.await.unwrap();
+
+        // Everything is retained
+        assert!(published.iter().all(|(_, retained, _)| *retained));
+        assert!(published
+            .iter()
+            .any(|(topic, _, payload)| topic == "rusty-wx/status" && payload == "online"));
+
+        let configs: Vec<&str> = published
+            .iter()
+            .map(|(topic, _, _)| topic.as_str())
+            .filter(|topic| topic.starts_with("homeassistant/sensor/rusty_wx_denver/"))
+            .collect();
+        assert_eq!(configs.len(), WeatherElement::ALL.len() + 1);
+        assert!(configs.contains(&"homeassistant/sensor/rusty_wx_denver/forecast/config"));
+
+        let (_, _, state) = published
+            .iter()
+            .find(|(topic, _, _)| topic == "rusty-wx/denver/state")
+            .expect("state published");
+        let state: Value = serde_json::from_str(state).unwrap();
+        assert!(state["temperature"].is_number());
+        assert!(state["forecast"].is_string());
+        assert_eq!(api.requests(), ["/weather?lat=39.74&lon=-104.99"]);
+    }
+
+    #[test]
+    fn every_location_has_its_own_device_and_topic() {
+        let location = |name: &str| SavedLocation {
+            name: name.to_string(),
+            lat: 0.0,
+            lon: 0.0,
+        };
+        let cache = SiteCache::new(&[
+            location("Springfield"),
+            location("Springfield"),
+            location("東京"),
+            location("Zürich"),
+        ]);
+        let config = MqttConfig::default();
+        let sites = cache.snapshot();
+
+        let topics: Vec<String> = sites
+            .iter()
+            .map(|site| state_topic(&config, site))
+            .collect();
+        assert_eq!(
+            topics,
+            [
+                "rusty-wx/springfield/state",
+                "rusty-wx/springfield_2/state",
+                "rusty-wx/location_3/state",
+                "rusty-wx/z_rich/state"
+            ]
+        );
+
+        let mut unique_ids: Vec<String> = sites
+            .iter()
+            .flat_map(|site| discovery_messages(&config, site))
+            .map(|(_, payload)| payload["unique_id"].as_str().unwrap().to_string())
+            .collect();
+        let count = unique_ids.len();
+        unique_ids.sort();
+        unique_ids.dedup();
+        assert_eq!(unique_ids.len(), count);
+        assert_eq!(count, sites.len() * (WeatherElement::ALL.len() + 1));
+    }
+}
@@ -0,0 +1,528 @@
+use crate::app::Message;
+use crate::config::MeteogramPanel;
+use crate::meteogram::{self, closest_index_at, draw_qc_marker, time_bounds, time_to_x};
+use crate::render::DrawTarget;
+use crate::theme::{ChartColors, ElementColor};
+use chrono::{DateTime, FixedOffset, Utc};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    widget::canvas::{self, Frame},
+    Color, Point, Rectangle, Size, Theme, Vector,
+};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::{self, QcFlag};
+use rusty_wx::timeline;
+use rusty_wx::weather::{ForecastPrecip, WeatherDataPoint};
+
+pub const PANEL_HEIGHT: f32 = 110.0;
+const TOP_MARGIN: f32 = 10.0;
+const BOTTOM_MARGIN: f32 = 10.0;
+
+// A secondary meteogram panel drawn against the same time axis as the main meteogram
+pub struct PanelCanvas {
+    panel: MeteogramPanel,
+    timeline: Vec<WeatherDataPoint>,
+    selected_index: usize,
+    precip_1hr: Vec<ForecastPrecip>,
+    precip_6hr: Vec<ForecastPrecip>,
+    alert_spans: Vec<(i64, i64)>,
+    qc_flags: Vec<QcFlag>,
+    now: i64,
+    // Days are shaded in this offset instead of the local time zone
+    zone: Option<FixedOffset>,
+}
+
+impl PanelCanvas {
+    pub fn new(
+        panel: MeteogramPanel,
+        timeline: Vec<WeatherDataPoint>,
+        selected_index: usize,
+        precip_1hr: Vec<ForecastPrecip>,
+        precip_6hr: Vec<ForecastPrecip>,
+    ) -> Self {
+        Self {
+            panel,
+            timeline,
+            selected_index,
+            precip_1hr,
+            precip_6hr,
+            alert_spans: Vec::new(),
+            qc_flags: Vec::new(),
+            now: Utc::now().timestamp(),
+            zone: None,
+        }
+    }
+
+    // Highlight the (start, end) timestamps of triggered alerts
+    pub fn with_alerts(mut self, alert_spans: Vec<(i64, i64)>) -> Self {
+        self.alert_spans = alert_spans;
+        self
+    }
+
+    // Mark these values instead of plotting them
+    pub fn with_qc_flags(mut self, qc_flags: Vec<QcFlag>) -> Self {
+        self.qc_flags = qc_flags;
+        self
+    }
+
+    fn is_flagged(&self, point: &WeatherDataPoint, element: &WeatherElement) -> bool {
+        qc::flag_for(&self.qc_flags, point, element).is_some()
+    }
+
+    // Draw the "now" line at this timestamp instead of the current time
+    #[cfg(test)]
+    pub fn with_now(mut self, now: i64) -> Self {
+        self.now = now;
+        self
+    }
+
+    // Shade days at this offset from UTC, whatever the machine's time zone
+    #[cfg(test)]
+    pub fn with_zone(mut self, zone: FixedOffset) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
+    // Value range of the panel's primary axis, shared with the scale labels
+    pub fn value_range(panel: MeteogramPanel, timeline: &[WeatherDataPoint]) -> (f64, f64) {
+        match panel {
+            MeteogramPanel::Precipitation | MeteogramPanel::CloudCover => (0.0, 100.0),
+            MeteogramPanel::Wind => {
+                let max_wind = timeline
+                    .iter()
+                    .filter_map(|p| wind_values(p).1.or(wind_values(p).0))
+                    .fold(0.0_f64, f64::max);
+                (0.0, (max_wind * 1.1).max(10.0))
+            }
+            MeteogramPanel::PressureCape => {
+                let mut min_p = f64::MAX;
+                let mut max_p = f64::MIN;
+                for point in timeline {
+                    if let WeatherDataPoint::Observation(obs) = point {
+                        if let Some(p) = obs.pressure_h_pa {
+                            min_p = min_p.min(p);
+                            max_p = max_p.max(p);
+                        }
+                    }
+                }
+                if min_p == f64::MAX {
+                    (1000.0, 1030.0)
+                } else {
+                    (min_p - 2.0, max_p + 2.0)
+                }
+            }
+            MeteogramPanel::Temperature => (0.0, 1.0),
+        }
+    }
+
+    fn value_to_y(&self, value: f64, min: f64, max: f64, height: f32) -> f32 {
+        let graph_height = height - TOP_MARGIN - BOTTOM_MARGIN;
+        let ratio = if max > min {
+            ((value - min) / (max - min)) as f32
+        } else {
+            0.5
+        };
+        TOP_MARGIN + (1.0 - ratio.clamp(0.0, 1.0)) * graph_height
+    }
+
+    fn draw_precipitation<T: DrawTarget>(
+        &self,
+        target: &mut T,
+        colors: &ChartColors,
+        bounds: Size,
+        start: i64,
+        end: i64,
+    ) {
+        let bar_width = (time_to_x(start + 3600, start, end, bounds.width)
+            - time_to_x(start, start, end, bounds.width))
+        .max(2.0)
+            * 0.8;
+        let base_y = self.value_to_y(0.0, 0.0, 100.0, bounds.height);
+
+        for point in &self.timeline {
+            let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+                continue;
+            };
+            if let Some(prob) = timeline::precipitation_probability_from(
+                &self.precip_1hr,
+                &self.precip_6hr,
+                point.valid_date(),
+            ) {
+                let x = time_to_x(dt.timestamp(), start, end, bounds.width);
+                let y = self.value_to_y(prob, 0.0, 100.0, bounds.height);
+                target.fill_rect(
+                    Point::new(x - bar_width / 2.0, y),
+                    Size::new(bar_width, base_y - y),
+                    colors.series(WeatherElement::PrecipitationProbability.color()),
+                );
+            }
+        }
+    }
+
+    fn draw_wind<T: DrawTarget>(
+        &self,
+        target: &mut T,
+        colors: &ChartColors,
+        bounds: Size,
+        start: i64,
+        end: i64,
+    ) {
+        let (min, max) = Self::value_range(MeteogramPanel::Wind, &self.timeline);
+        let mut speed_points = Vec::new();
+        let mut gust_points = Vec::new();
+        let mut flagged_points = Vec::new();
+
+        for (i, point) in self.timeline.iter().enumerate() {
+            let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+                continue;
+            };
+            let x = time_to_x(dt.timestamp(), start, end, bounds.width);
+            let (speed, gust, direction) = wind_values(point);
+
+            for (value, element, points) in [
+                (speed, WeatherElement::WindSpeed, &mut speed_points),
+                (gust, WeatherElement::WindGust, &mut gust_points),
+            ] {
+                if let Some(value) = value {
+                    let p = Point::new(x, self.value_to_y(value, min, max, bounds.height));
+                    if self.is_flagged(point, &element) {
+                        flagged_points.push(p);
+                    } else {
+                        points.push(p);
+                    }
+                }
+            }
+
+            // A barb every third hour keeps them readable
+            if i % 3 == 0 {
+                if let (Some(spd), Some(dir)) = (speed, direction) {
+                    draw_wind_barb(
+                        target,
+                        colors.label,
+                        Point::new(x, bounds.height / 2.0),
+                        spd,
+                        dir,
+                    );
+                }
+            }
+        }
+
+        stroke_polyline(
+            target,
+            &gust_points,
+            colors.series(WeatherElement::WindGust.color()),
+            1.5,
+        );
+        stroke_polyline(
+            target,
+            &speed_points,
+            colors.series(WeatherElement::WindSpeed.color()),
+            2.0,
+        );
+        for p in flagged_points {
+            draw_qc_marker(target, colors, p);
+        }
+    }
+
+    fn draw_cloud_cover<T: DrawTarget>(
+        &self,
+        target: &mut T,
+        colors: &ChartColors,
+        bounds: Size,
+        start: i64,
+        end: i64,
+    ) {
+        let base_y = self.value_to_y(0.0, 0.0, 100.0, bounds.height);
+        let cloud_color = colors.series(WeatherElement::CloudCover.color());
+        let mut points = Vec::new();
+        let mut flagged_points = Vec::new();
+
+        for point in &self.timeline {
+            let cloud = match point {
+                WeatherDataPoint::Observation(obs) => obs.cloud_cover_pct,
+                WeatherDataPoint::Forecast(fc) => fc.cloud_cover_pct,
+            };
+            if let (Ok(dt), Some(cloud)) = (DateTime::parse_from_rfc3339(point.valid_date()), cloud)
+            {
+                let x = time_to_x(dt.timestamp(), start, end, bounds.width);
+                let p = Point::new(x, self.value_to_y(cloud, 0.0, 100.0, bounds.height));
+                if self.is_flagged(point, &WeatherElement::CloudCover) {
+                    flagged_points.push(p);
+                } else {
+                    points.push(p);
+                }
+            }
+        }
+
+        if let (Some(first), Some(last)) = (points.first(), points.last()) {
+            let mut area = vec![Point::new(first.x, base_y)];
+            area.extend_from_slice(&points);
+            area.push(Point::new(last.x, base_y));
+            target.fill_polygon(
+                &area,
+                Color {
+                    a: 0.4,
+                    ..cloud_color
+                },
+            );
+        }
+        stroke_polyline(target, &points, cloud_color, 1.5);
+        for p in flagged_points {
+            draw_qc_marker(target, colors, p);
+        }
+    }
+
+    fn draw_pressure_cape<T: DrawTarget>(
+        &self,
+        target: &mut T,
+        colors: &ChartColors,
+        bounds: Size,
+        start: i64,
+        end: i64,
+    ) {
+        // CAPE bars on their own 0..max axis, pressure line on the panel's primary axis
+        let max_cape = self
+            .timeline
+            .iter()
+            .filter_map(|p| match p {
+                WeatherDataPoint::Forecast(fc) => fc.cape_surface_j_kg,
+                WeatherDataPoint::Observation(_) => None,
+            })
+            .fold(0.0_f64, f64::max)
+            .max(500.0);
+        let base_y = self.value_to_y(0.0, 0.0, max_cape, bounds.height);
+        let bar_width = (time_to_x(start + 3600, start, end, bounds.width)
+            - time_to_x(start, start, end, bounds.width))
+        .max(2.0)
+            * 0.6;
+
+        let (min_p, max_p) = Self::value_range(MeteogramPanel::PressureCape, &self.timeline);
+        let mut pressure_points = Vec::new();
+        let mut flagged_points = Vec::new();
+
+        for point in &self.timeline {
+            let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+                continue;
+            };
+            let x = time_to_x(dt.timestamp(), start, end, bounds.width);
+            match point {
+                WeatherDataPoint::Forecast(fc) => {
+                    if let Some(cape) = fc.cape_surface_j_kg {
+                        let y = self.value_to_y(cape, 0.0, max_cape, bounds.height);
+                        target.fill_rect(
+                            Point::new(x - bar_width / 2.0, y),
+                            Size::new(bar_width, base_y - y),
+                            Color {
+                                a: 0.6,
+                                ..colors.series(WeatherElement::CAPE.color())
+                            },
+                        );
+                    }
+                }
+                WeatherDataPoint::Observation(obs) => {
+                    if let Some(p) = obs.pressure_h_pa {
+                        let p = Point::new(x, self.value_to_y(p, min_p, max_p, bounds.height));
+                        if self.is_flagged(point, &WeatherElement::Pressure) {
+                            flagged_points.push(p);
+                        } else {
+                            pressure_points.push(p);
+                        }
+                    }
+                }
+            }
+        }
+
+        stroke_polyline(
+            target,
+            &pressure_points,
+            colors.series(WeatherElement::Pressure.color()),
+            2.0,
+        );
+        for p in flagged_points {
+            draw_qc_marker(target, colors, p);
+        }
+    }
+
+    // Draw the panel onto any target, so the on-screen canvas and exports share geometry
+    pub fn render<T: DrawTarget>(&self, target: &mut T, colors: &ChartColors, bounds: Size) {
+        meteogram::draw_day_backgrounds(target, colors, &self.timeline, self.zone, bounds);
+        meteogram::draw_alert_spans(target, colors, &self.timeline, &self.alert_spans, bounds);
+
+        let Some((start, end)) = time_bounds(&self.timeline) else {
+            return;
+        };
+
+        match self.panel {
+            MeteogramPanel::Precipitation => {
+                self.draw_precipitation(target, colors, bounds, start, end)
+            }
+            MeteogramPanel::Wind => self.draw_wind(target, colors, bounds, start, end),
+            MeteogramPanel::CloudCover => self.draw_cloud_cover(target, colors, bounds, start, end),
+            MeteogramPanel::PressureCape => {
+                self.draw_pressure_cape(target, colors, bounds, start, end)
+            }
+            MeteogramPanel::Temperature => {}
+        }
+
+        // "Now" line, matching the main meteogram
+        if (start..=end).contains(&self.now) {
+            let now_x = time_to_x(self.now, start, end, bounds.width);
+            target.line(
+                Point::new(now_x, 0.0),
+                Point::new(now_x, bounds.height),
+                colors.accent,
+                2.0,
+            );
+        }
+
+        // Selected time marker
+        if let Some(selected) = self.timeline.get(self.selected_index) {
+            if let Ok(dt) = DateTime::parse_from_rfc3339(selected.valid_date()) {
+                let x = time_to_x(dt.timestamp(), start, end, bounds.width);
+                target.line(
+                    Point::new(x, 0.0),
+                    Point::new(x, bounds.height),
+                    colors.marker,
+                    1.0,
+                );
+            }
+        }
+
+        // Panel title in the top-left corner
+        target.text(
+            self.panel.label(),
+            Point::new(meteogram::LEFT_MARGIN + 4.0, 2.0),
+            11.0,
+            colors.muted,
+            Horizontal::Left,
+            Vertical::Top,
+        );
+    }
+}
+
+impl canvas::Program<Message> for PanelCanvas {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: iced::mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        if let canvas::Event::Mouse(iced::mouse::Event::ButtonPressed(iced::mouse::Button::Left)) =
+            event
+        {
+            if let Some(cursor_position) = cursor.position_in(bounds) {
+                if let Some(index) =
+                    closest_index_at(&self.timeline, cursor_position.x, bounds.width)
+                {
+                    return (
+                        canvas::event::Status::Captured,
+                        Some(Message::MeteogramClicked(index)),
+                    );
+                }
+            }
+        }
+        (canvas::event::Status::Ignored, None)
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &iced::Renderer,
+        theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        self.render(&mut frame, &ChartColors::from_theme(theme), bounds.size());
+        vec![frame.into_geometry()]
+    }
+}
+
+// (speed, gust, direction) for either kind of data point
+fn wind_values(point: &WeatherDataPoint) -> (Option<f64>, Option<f64>, Option<f64>) {
+    match point {
+        WeatherDataPoint::Observation(obs) => (
+            obs.wind_spd_10m_mph,
+            obs.wind_gust_10m_mph,
+            obs.wind_dir_10m_deg_fm_n,
+        ),
+        WeatherDataPoint::Forecast(fc) => (
+            fc.wind_spd_10m_mph,
+            fc.wind_gust_10m_mph,
+            fc.wind_dir_10m_deg_fm_n,
+        ),
+    }
+}
+
+fn stroke_polyline<T: DrawTarget>(target: &mut T, points: &[Point], color: Color, width: f32) {
+    for pair in points.windows(2) {
+        target.line(pair[0], pair[1], color, width);
+    }
+}
+
+// Standard station-model barb: staff points into the wind, 50 kt pennants, 10 kt barbs, 5 kt half barbs
+fn draw_wind_barb<T: DrawTarget>(
+    target: &mut T,
+    color: Color,
+    center: Point,
+    speed_mph: f64,
+    direction_deg: f64,
+) {
+    let knots = (speed_mph * 0.868976 / 5.0).round() as i32 * 5;
+
+    if knots < 5 {
+        target.stroke_circle(center, 3.0, color, 1.0);
+        return;
+    }
+
+    let staff_length = 28.0;
+    let angle = (direction_deg as f32).to_radians();
+    // Unit vector from the station towards where the wind comes from (screen y points down)
+    let along = Vector::new(angle.sin(), -angle.cos());
+    // Barbs sit on the clockwise side of the staff
+    let across = Vector::new(-along.y, along.x);
+    let tip = center + along * staff_length;
+
+    let width = 1.2;
+    target.line(center, tip, color, width);
+
+    let mut remaining = knots;
+    let mut offset = 0.0;
+    let spacing = 4.0;
+    let barb_length = 10.0;
+
+    while remaining >= 50 {
+        let base = tip - along * offset;
+        let next = tip - along * (offset + spacing * 1.5);
+        target.fill_polygon(&[base, base + across * barb_length, next], color);
+        offset += spacing * 2.0;
+        remaining -= 50;
+    }
+    while remaining >= 10 {
+        let base = tip - along * offset;
+        target.line(
+            base,
+            base + across * barb_length + along * 2.0,
+            color,
+            width,
+        );
+        offset += spacing;
+        remaining -= 10;
+    }
+    if remaining >= 5 {
+        // A lone half barb is set back from the tip so it isn't mistaken for a full one
+        if offset == 0.0 {
+            offset = spacing;
+        }
+        let base = tip - along * offset;
+        target.line(
+            base,
+            base + across * (barb_length / 2.0) + along,
+            color,
+            width,
+        );
+    }
+}
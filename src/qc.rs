@@ -0,0 +1,262 @@
+//! Quality control for observations: range, step, consistency and stuck-sensor checks that
+//! flag suspect values so they can be marked instead of plotted as if they were real.
+//!
+//! Only observations are checked; forecasts come out of a model and don't have sensor faults.
+
+use crate::element::WeatherElement;
+use crate::timeline::{self, element_value};
+use crate::weather::{ApiWeatherData, WeatherDataPoint};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+
+/// Limits for the step and stuck-sensor checks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QcRules {
+    /// Largest believable temperature change per hour (°F)
+    pub max_temperature_step_f: f64,
+    /// Largest believable dewpoint change per hour (°F)
+    pub max_dewpoint_step_f: f64,
+    /// Largest believable pressure change per hour (hPa)
+    pub max_pressure_step_hpa: f64,
+    /// Identical temperature, dewpoint or pressure readings for this many hours
+    /// are flagged as a stuck sensor
+    pub stuck_hours: f64,
+}
+
+impl Default for QcRules {
+    fn default() -> Self {
+        Self {
+            max_temperature_step_f: 15.0,
+            max_dewpoint_step_f: 20.0,
+            max_pressure_step_hpa: 6.0,
+            stuck_hours: 6.0,
+        }
+    }
+}
+
+/// Which check a value failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum QcCheck {
+    /// Outside the physically plausible range for the element
+    Range,
+    /// Dewpoint above the temperature
+    Consistency,
+    /// Changed faster than the per-hour limit since the last good value
+    Step,
+    /// Unchanged for [`QcRules::stuck_hours`] or longer
+    Stuck,
+}
+
+/// A suspect observed value.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QcFlag {
+    /// `validDate` of the observation
+    pub valid_date: String,
+    pub element: WeatherElement,
+    pub value: f64,
+    pub check: QcCheck,
+    /// Why it was flagged, e.g. "changed +18.2 in 1 h"
+    pub reason: String,
+}
+
+/// The elements observations report, in the order they are checked.
+pub const CHECKED_ELEMENTS: [WeatherElement; 9] = [
+    WeatherElement::Temperature,
+    WeatherElement::Dewpoint,
+    WeatherElement::Humidity,
+    WeatherElement::WindSpeed,
+    WeatherElement::WindGust,
+    WeatherElement::Pressure,
+    WeatherElement::CloudCover,
+    WeatherElement::Visibility,
+    WeatherElement::SolarFlux,
+];
+
+// Rounding in the feed can put a saturated dewpoint slightly above the temperature
+const DEWPOINT_TOLERANCE_F: f64 = 1.0;
+
+/// Plausible values of `element`, in the units of [`element_value`].
+pub fn valid_range(element: &WeatherElement) -> Option<(f64, f64)> {
+    match element {
+        WeatherElement::Temperature => Some((-80.0, 135.0)),
+        WeatherElement::Dewpoint => Some((-90.0, 95.0)),
+        WeatherElement::Humidity | WeatherElement::CloudCover => Some((0.0, 100.0)),
+        WeatherElement::WindSpeed => Some((0.0, 150.0)),
+        WeatherElement::WindGust => Some((0.0, 220.0)),
+        WeatherElement::Pressure => Some((870.0, 1085.0)),
+        WeatherElement::Visibility => Some((0.0, 100.0)),
+        WeatherElement::SolarFlux => Some((0.0, 1500.0)),
+        _ => None,
+    }
+}
+
+fn max_step(element: &WeatherElement, rules: &QcRules) -> Option<f64> {
+    match element {
+        WeatherElement::Temperature => Some(rules.max_temperature_step_f),
+        WeatherElement::Dewpoint => Some(rules.max_dewpoint_step_f),
+        WeatherElement::Pressure => Some(rules.max_pressure_step_hpa),
+        _ => None,
+    }
+}
+
+// Calm wind, clear skies, fog at 100% humidity and night-time solar flux stay put for
+// hours legitimately
+fn can_stick(element: &WeatherElement) -> bool {
+    matches!(
+        element,
+        WeatherElement::Temperature | WeatherElement::Dewpoint | WeatherElement::Pressure
+    )
+}
+
+// The value the station reported, leaving out humidity and dewpoint derived from each other,
+// which can't be wrong in ways of their own
+fn observed_value(
+    weather: &ApiWeatherData,
+    point: &WeatherDataPoint,
+    element: &WeatherElement,
+) -> Option<f64> {
+    let WeatherDataPoint::Observation(obs) = point else {
+        return None;
+    };
+    let reported = match element {
+        WeatherElement::Humidity => obs.specific_humidity_2m_dg_kg.is_some(),
+        WeatherElement::Dewpoint => obs.dewpoint_2m_f.is_some(),
+        _ => true,
+    };
+    element_value(weather, point, element).filter(|_| reported)
+}
+
+/// Run every check over the observations in `weather`, returning the flagged values oldest
+/// first. A value is flagged once, for the first check it fails in [`QcCheck`] order, and
+/// flagged values are not used as the reference for the step check.
+pub fn check_observations(weather: &ApiWeatherData, rules: &QcRules) -> Vec<QcFlag> {
+    let observations: Vec<(i64, WeatherDataPoint)> = timeline::build_timeline(weather)
+        .into_iter()
+        .filter(|point| matches!(point, WeatherDataPoint::Observation(_)))
+        .filter_map(|point| {
+            let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+            Some((time.timestamp(), point))
+        })
+        .collect();
+
+    // (observation index, flag) so the result can be put back in time order
+    let mut flags: Vec<(usize, QcFlag)> = Vec::new();
+    for element in &CHECKED_ELEMENTS {
+        let values: Vec<(usize, i64, f64)> = observations
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (time, point))| {
+                Some((i, *time, observed_value(weather, point, element)?))
+            })
+            .collect();
+        let mut checks: Vec<Option<(QcCheck, String)>> = vec![None; values.len()];
+
+        if let Some((min, max)) = valid_range(element) {
+            for (check, (_, _, value)) in checks.iter_mut().zip(&values) {
+                if !(min..=max).contains(value) {
+                    *check = Some((
+                        QcCheck::Range,
+                        format!("{:.1} is outside {} to {}", value, min, max),
+                    ));
+                }
+            }
+        }
+
+        if *element == WeatherElement::Dewpoint {
+            for (check, (i, _, dewpoint)) in checks.iter_mut().zip(&values) {
+                let temperature = observations[*i].1.temperature();
+                if let Some(temperature) = temperature {
+                    if check.is_none() && *dewpoint > temperature + DEWPOINT_TOLERANCE_F {
+                        *check = Some((
+                            QcCheck::Consistency,
+                            format!("above the temperature ({:.1})", temperature),
+                        ));
+                    }
+                }
+            }
+        }
+
+        if let Some(limit) = max_step(element, rules) {
+            let mut reference: Option<(i64, f64)> = None;
+            for (check, (_, time, value)) in checks.iter_mut().zip(&values) {
+                if check.is_some() {
+                    continue;
+                }
+                if let Some((last_time, last_value)) = reference {
+                    // Gaps under an hour count as one, so sub-hourly noise isn't scaled up
+                    let hours = ((time - last_time) as f64 / 3600.0).max(1.0);
+                    let change = value - last_value;
+                    if change.abs() / hours > limit {
+                        *check = Some((
+                            QcCheck::Step,
+                            format!("changed {:+.1} in {} h", change, hours.round()),
+                        ));
+                        continue;
+                    }
+                }
+                reference = Some((*time, *value));
+            }
+        }
+
+        if can_stick(element) {
+            let mut run_start = 0;
+            for end in 1..=values.len() {
+                if end < values.len() && values[end].2 == values[run_start].2 {
+                    continue;
+                }
+                let hours = (values[end - 1].1 - values[run_start].1) as f64 / 3600.0;
+                if hours >= rules.stuck_hours {
+                    for check in &mut checks[run_start..end] {
+                        check.get_or_insert_with(|| {
+                            (QcCheck::Stuck, format!("unchanged for {} h", hours.round()))
+                        });
+                    }
+                }
+                run_start = end;
+            }
+        }
+
+        for ((i, _, value), check) in values.iter().zip(checks) {
+            if let Some((check, reason)) = check {
+                flags.push((
+                    *i,
+                    QcFlag {
+                        valid_date: observations[*i].1.valid_date().to_string(),
+                        element: element.clone(),
+                        value: *value,
+                        check,
+                        reason,
+                    },
+                ));
+            }
+        }
+    }
+
+    // Stable, so flags at the same time stay in element order
+    flags.sort_by_key(|(i, _)| *i);
+    flags.into_iter().map(|(_, flag)| flag).collect()
+}
+
+/// The flag on `element` at `point`, if any. Apparent temperature is derived, so it carries
+/// the flag of any of its inputs. Forecast points are never flagged.
+pub fn flag_for<'a>(
+    flags: &'a [QcFlag],
+    point: &WeatherDataPoint,
+    element: &WeatherElement,
+) -> Option<&'a QcFlag> {
+    if !matches!(point, WeatherDataPoint::Observation(_)) {
+        return None;
+    }
+    let inputs: &[WeatherElement] = match element {
+        WeatherElement::ApparentTemperature => &[
+            WeatherElement::Temperature,
+            WeatherElement::Humidity,
+            WeatherElement::WindSpeed,
+        ],
+        other => std::slice::from_ref(other),
+    };
+    flags
+        .iter()
+        .find(|flag| flag.valid_date == point.valid_date() && inputs.contains(&flag.element))
+}
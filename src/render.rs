@@ -0,0 +1,395 @@
+use iced::{
+    alignment::{Horizontal, Vertical},
+    widget::canvas::{Frame, Path, Stroke, Text},
+    Color, Point, Size,
+};
+use std::fmt::Write;
+
+// The drawing primitives the meteogram needs, so the same geometry can be rendered
+// on screen through an iced canvas or offscreen into an SVG document
+pub trait DrawTarget {
+    fn fill_rect(&mut self, top_left: Point, size: Size, color: Color);
+    fn line(&mut self, from: Point, to: Point, color: Color, width: f32);
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color);
+    fn stroke_circle(&mut self, center: Point, radius: f32, color: Color, width: f32);
+    fn fill_polygon(&mut self, points: &[Point], color: Color);
+    fn text(
+        &mut self,
+        content: &str,
+        position: Point,
+        size: f32,
+        color: Color,
+        horizontal: Horizontal,
+        vertical: Vertical,
+    );
+}
+
+impl DrawTarget for Frame {
+    fn fill_rect(&mut self, top_left: Point, size: Size, color: Color) {
+        self.fill_rectangle(top_left, size, color);
+    }
+
+    fn line(&mut self, from: Point, to: Point, color: Color, width: f32) {
+        self.stroke(
+            &Path::line(from, to),
+            Stroke::default().with_color(color).with_width(width),
+        );
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        self.fill(&Path::circle(center, radius), color);
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, color: Color, width: f32) {
+        self.stroke(
+            &Path::circle(center, radius),
+            Stroke::default().with_color(color).with_width(width),
+        );
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let path = Path::new(|builder| {
+            builder.move_to(points[0]);
+            for p in &points[1..] {
+                builder.line_to(*p);
+            }
+            builder.close();
+        });
+        self.fill(&path, color);
+    }
+
+    fn text(
+        &mut self,
+        content: &str,
+        position: Point,
+        size: f32,
+        color: Color,
+        horizontal: Horizontal,
+        vertical: Vertical,
+    ) {
+        self.fill_text(Text {
+            content: content.to_string(),
+            position,
+            size: size.into(),
+            color,
+            horizontal_alignment: horizontal,
+            vertical_alignment: vertical,
+            ..Text::default()
+        });
+    }
+}
+
+// Offscreen SVG document built from the same primitives
+pub struct SvgDocument {
+    width: f32,
+    height: f32,
+    body: String,
+    clip_count: usize,
+}
+
+impl SvgDocument {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            body: String::new(),
+            clip_count: 0,
+        }
+    }
+
+    // Draw into a (dx, dy) offset region clipped to `size`, like a canvas placed in a layout
+    pub fn translated(&mut self, dx: f32, dy: f32, size: Size, draw: impl FnOnce(&mut Self)) {
+        self.clip_count += 1;
+        let _ = writeln!(
+            self.body,
+            r#"<clipPath id="clip{}"><rect x="0" y="0" width="{:.2}" height="{:.2}"/></clipPath>"#,
+            self.clip_count, size.width, size.height
+        );
+        let _ = writeln!(
+            self.body,
+            r#"<g transform="translate({:.2},{:.2})" clip-path="url(#clip{})">"#,
+            dx, dy, self.clip_count
+        );
+        draw(self);
+        self.body.push_str("</g>\n");
+    }
+
+    pub fn finish(self) -> String {
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                "\n",
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w:.0}" height="{h:.0}" viewBox="0 0 {w:.0} {h:.0}" font-family="sans-serif">"#,
+                "\n{body}</svg>\n"
+            ),
+            w = self.width,
+            h = self.height,
+            body = self.body
+        )
+    }
+}
+
+fn svg_color(color: Color) -> String {
+    format!(
+        "rgb({},{},{})",
+        (color.r * 255.0).round() as u8,
+        (color.g * 255.0).round() as u8,
+        (color.b * 255.0).round() as u8
+    )
+}
+
+// Alpha is written separately as SVG 1.1 doesn't support rgba()
+fn svg_opacity(attribute: &str, color: Color) -> String {
+    if color.a < 1.0 {
+        format!(r#" {}="{:.3}""#, attribute, color.a)
+    } else {
+        String::new()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl DrawTarget for SvgDocument {
+    fn fill_rect(&mut self, top_left: Point, size: Size, color: Color) {
+        let _ = writeln!(
+            self.body,
+            r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="{}"{}/>"#,
+            top_left.x,
+            top_left.y,
+            size.width.max(0.0),
+            size.height.max(0.0),
+            svg_color(color),
+            svg_opacity("fill-opacity", color)
+        );
+    }
+
+    fn line(&mut self, from: Point, to: Point, color: Color, width: f32) {
+        let _ = writeln!(
+            self.body,
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" stroke="{}" stroke-width="{:.2}"{}/>"#,
+            from.x,
+            from.y,
+            to.x,
+            to.y,
+            svg_color(color),
+            width,
+            svg_opacity("stroke-opacity", color)
+        );
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="{}"{}/>"#,
+            center.x,
+            center.y,
+            radius,
+            svg_color(color),
+            svg_opacity("fill-opacity", color)
+        );
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, color: Color, width: f32) {
+        let _ = writeln!(
+            self.body,
+            r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="none" stroke="{}" stroke-width="{:.2}"{}/>"#,
+            center.x,
+            center.y,
+            radius,
+            svg_color(color),
+            width,
+            svg_opacity("stroke-opacity", color)
+        );
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        if points.len() < 3 {
+            return;
+        }
+        let coords: Vec<String> = points
+            .iter()
+            .map(|p| format!("{:.2},{:.2}", p.x, p.y))
+            .collect();
+        let _ = writeln!(
+            self.body,
+            r#"<polygon points="{}" fill="{}"{}/>"#,
+            coords.join(" "),
+            svg_color(color),
+            svg_opacity("fill-opacity", color)
+        );
+    }
+
+    fn text(
+        &mut self,
+        content: &str,
+        position: Point,
+        size: f32,
+        color: Color,
+        horizontal: Horizontal,
+        vertical: Vertical,
+    ) {
+        let anchor = match horizontal {
+            Horizontal::Left => "start",
+            Horizontal::Center => "middle",
+            Horizontal::Right => "end",
+        };
+        let baseline = match vertical {
+            Vertical::Top => "text-before-edge",
+            Vertical::Center => "central",
+            Vertical::Bottom => "text-after-edge",
+        };
+        let _ = writeln!(
+            self.body,
+            r#"<text x="{:.2}" y="{:.2}" font-size="{:.1}" fill="{}" text-anchor="{}" dominant-baseline="{}">{}</text>"#,
+            position.x,
+            position.y,
+            size,
+            svg_color(color),
+            anchor,
+            baseline,
+            escape_xml(content)
+        );
+    }
+}
+
+// Rasterize an SVG document to PNG bytes, using the system fonts for text
+pub fn rasterize_svg(svg: &str) -> Result<Vec<u8>, String> {
+    let mut options = resvg::usvg::Options::default();
+    let fontdb = options.fontdb_mut();
+    fontdb.load_system_fonts();
+
+    // fontdb maps sans-serif to Arial, which most Linux systems don't have
+    let installed = |family: &str| {
+        fontdb
+            .faces()
+            .any(|face| face.families.iter().any(|(name, _)| name == family))
+    };
+    if let Some(family) = ["DejaVu Sans", "Noto Sans", "Liberation Sans", "Cantarell"]
+        .into_iter()
+        .find(|f| installed(f))
+    {
+        fontdb.set_sans_serif_family(family);
+    }
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options).map_err(|e| e.to_string())?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| "Invalid image size".to_string())?;
+
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::default(),
+        &mut pixmap.as_mut(),
+    );
+
+    pixmap.encode_png().map_err(|e| e.to_string())
+}
+
+// Records the primitives as text, one line each, for the snapshot tests. Coordinates are
+// rounded to a tenth of a pixel so float noise doesn't show up as a layout change.
+#[cfg(test)]
+#[derive(Default)]
+pub struct Recording {
+    lines: Vec<String>,
+}
+
+#[cfg(test)]
+impl Recording {
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+}
+
+#[cfg(test)]
+fn rec_number(value: f32) -> String {
+    let rounded = (value * 10.0).round() / 10.0;
+    // Avoid "-0.0" vs "0.0" churn
+    format!("{:.1}", if rounded == 0.0 { 0.0 } else { rounded })
+}
+
+#[cfg(test)]
+fn rec_point(point: Point) -> String {
+    format!("{},{}", rec_number(point.x), rec_number(point.y))
+}
+
+#[cfg(test)]
+fn rec_color(color: Color) -> String {
+    let [r, g, b, a] = color.into_rgba8();
+    format!("#{:02x}{:02x}{:02x}{:02x}", r, g, b, a)
+}
+
+#[cfg(test)]
+impl DrawTarget for Recording {
+    fn fill_rect(&mut self, top_left: Point, size: Size, color: Color) {
+        self.lines.push(format!(
+            "rect {} {}x{} {}",
+            rec_point(top_left),
+            rec_number(size.width),
+            rec_number(size.height),
+            rec_color(color)
+        ));
+    }
+
+    fn line(&mut self, from: Point, to: Point, color: Color, width: f32) {
+        self.lines.push(format!(
+            "line {} -> {} {} w{}",
+            rec_point(from),
+            rec_point(to),
+            rec_color(color),
+            rec_number(width)
+        ));
+    }
+
+    fn fill_circle(&mut self, center: Point, radius: f32, color: Color) {
+        self.lines.push(format!(
+            "circle {} r{} {}",
+            rec_point(center),
+            rec_number(radius),
+            rec_color(color)
+        ));
+    }
+
+    fn stroke_circle(&mut self, center: Point, radius: f32, color: Color, width: f32) {
+        self.lines.push(format!(
+            "ring {} r{} {} w{}",
+            rec_point(center),
+            rec_number(radius),
+            rec_color(color),
+            rec_number(width)
+        ));
+    }
+
+    fn fill_polygon(&mut self, points: &[Point], color: Color) {
+        let points: Vec<String> = points.iter().map(|p| rec_point(*p)).collect();
+        self.lines
+            .push(format!("polygon {} {}", rec_color(color), points.join(" ")));
+    }
+
+    fn text(
+        &mut self,
+        content: &str,
+        position: Point,
+        size: f32,
+        color: Color,
+        horizontal: Horizontal,
+        vertical: Vertical,
+    ) {
+        self.lines.push(format!(
+            "text {} {} {} {:?}/{:?} {:?}",
+            rec_point(position),
+            rec_number(size),
+            rec_color(color),
+            horizontal,
+            vertical,
+            content
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+//! Run-to-run changes: how each day's forecast moved between two forecast runs, e.g.
+//! "Tomorrow's high up 4°", to judge how settled the forecast is.
+
+use crate::timeline::precipitation_probability_from;
+use crate::weather::{ApiWeatherData, ForecastInstant};
+use chrono::{DateTime, Duration, NaiveDate, TimeZone};
+use std::collections::HashMap;
+
+/// Smallest change in a daily high or low worth reporting (°F).
+pub const TEMPERATURE_CHANGE_F: f64 = 2.0;
+/// Smallest change in the day's highest chance of precipitation worth reporting (percent).
+pub const PRECIPITATION_CHANGE_PCT: f64 = 15.0;
+/// Smallest change in the day's peak gust worth reporting (mph).
+pub const GUST_CHANGE_MPH: f64 = 5.0;
+
+// A day compared over fewer hours than this says more about the hours left out
+const MIN_COMMON_HOURS: usize = 6;
+
+/// A daily value compared between runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyValue {
+    High,
+    Low,
+    PrecipitationChance,
+    PeakGust,
+}
+
+impl DailyValue {
+    fn name(&self) -> &'static str {
+        match self {
+            DailyValue::High => "high",
+            DailyValue::Low => "low",
+            DailyValue::PrecipitationChance => "chance of precipitation",
+            DailyValue::PeakGust => "peak gusts",
+        }
+    }
+
+    fn threshold(&self) -> f64 {
+        match self {
+            DailyValue::High | DailyValue::Low => TEMPERATURE_CHANGE_F,
+            DailyValue::PrecipitationChance => PRECIPITATION_CHANGE_PCT,
+            DailyValue::PeakGust => GUST_CHANGE_MPH,
+        }
+    }
+
+    fn format(&self, value: f64) -> String {
+        match self {
+            DailyValue::High | DailyValue::Low => format!("{:.0}°", value),
+            DailyValue::PrecipitationChance => format!("{:.0}%", value),
+            DailyValue::PeakGust => format!("{:.0} mph", value),
+        }
+    }
+}
+
+/// A day's value in the previous and the current run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunChange {
+    /// Date in the time zone given to [`compare`]
+    pub date: NaiveDate,
+    pub value: DailyValue,
+    pub previous: f64,
+    pub current: f64,
+}
+
+impl RunChange {
+    /// e.g. "Tomorrow's high up 4° (61° → 65°)", naming the day relative to `today`.
+    pub fn describe(&self, today: NaiveDate) -> String {
+        let day = if self.date == today {
+            "Today".to_string()
+        } else if self.date == today + Duration::days(1) {
+            "Tomorrow".to_string()
+        } else {
+            self.date.format("%A").to_string()
+        };
+        let change = self.current - self.previous;
+        format!(
+            "{}'s {} {} {} ({} → {})",
+            day,
+            self.value.name(),
+            if change > 0.0 { "up" } else { "down" },
+            self.value.format(change.abs()),
+            self.value.format(self.previous),
+            self.value.format(self.current)
+        )
+    }
+}
+
+fn highest(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    values.flatten().reduce(f64::max)
+}
+
+fn lowest(values: impl Iterator<Item = Option<f64>>) -> Option<f64> {
+    values.flatten().reduce(f64::min)
+}
+
+/// The changes from `previous` to `current` at least as large as the thresholds above, by day
+/// and then in [`DailyValue`] order. Days are those of `tz`, usually [`chrono::Local`], and are
+/// compared over the hours both runs forecast, so a later run starting partway through today is
+/// compared fairly.
+pub fn compare<Tz: TimeZone>(
+    previous: &ApiWeatherData,
+    current: &ApiWeatherData,
+    tz: &Tz,
+) -> Vec<RunChange> {
+    let previous_by_time: HashMap<&str, &ForecastInstant> = previous
+        .forecasts_instant
+        .iter()
+        .map(|fc| (fc.valid_date.as_str(), fc))
+        .collect();
+
+    // (date, [(previous, current)]) for the hours both runs forecast
+    let mut days: Vec<(NaiveDate, Vec<(&ForecastInstant, &ForecastInstant)>)> = Vec::new();
+    for fc in &current.forecasts_instant {
+        let Some(before) = previous_by_time.get(fc.valid_date.as_str()) else {
+            continue;
+        };
+        let Ok(time) = DateTime::parse_from_rfc3339(&fc.valid_date) else {
+            continue;
+        };
+        let date = time.with_timezone(tz).date_naive();
+        match days.last_mut() {
+            Some((day, hours)) if *day == date => hours.push((before, fc)),
+            _ => days.push((date, vec![(before, fc)])),
+        }
+    }
+
+    let precipitation = |weather: &ApiWeatherData, fc: &ForecastInstant| {
+        precipitation_probability_from(
+            &weather.forecasts_precip_1hr,
+            &weather.forecasts_precip_6hr,
+            &fc.valid_date,
+        )
+    };
+
+    let mut changes = Vec::new();
+    for (date, hours) in days {
+        if hours.len() < MIN_COMMON_HOURS {
+            continue;
+        }
+        let values = [
+            (
+                DailyValue::High,
+                highest(hours.iter().map(|(p, _)| p.temperature_2m_f)),
+                highest(hours.iter().map(|(_, c)| c.temperature_2m_f)),
+            ),
+            (
+                DailyValue::Low,
+                lowest(hours.iter().map(|(p, _)| p.temperature_2m_f)),
+                lowest(hours.iter().map(|(_, c)| c.temperature_2m_f)),
+            ),
+            (
+                DailyValue::PrecipitationChance,
+                highest(hours.iter().map(|(p, _)| precipitation(previous, p))),
+                highest(hours.iter().map(|(_, c)| precipitation(current, c))),
+            ),
+            (
+                DailyValue::PeakGust,
+                highest(hours.iter().map(|(p, _)| p.wind_gust_10m_mph)),
+                highest(hours.iter().map(|(_, c)| c.wind_gust_10m_mph)),
+            ),
+        ];
+        for (value, before, now) in values {
+            if let (Some(previous), Some(current)) = (before, now) {
+                if (current - previous).abs() >= value.threshold() {
+                    changes.push(RunChange {
+                        date,
+                        value,
+                        previous,
+                        current,
+                    });
+                }
+            }
+        }
+    }
+    changes
+}
@@ -0,0 +1,301 @@
+// Golden snapshots of the meteogram and panel drawing, recorded as primitives so layout
+// regressions show up without a GPU. The expected output lives in tests/snapshots; after an
+// intended change, rerun with UPDATE_SNAPSHOTS=1 and review the diff.
+
+use crate::app::WeatherApp;
+use crate::comparison::{self, Comparison};
+use crate::config::{MeteogramPanel, SavedLocation};
+use crate::meteogram::Meteogram;
+use crate::panels::PANEL_HEIGHT;
+use crate::render::Recording;
+use crate::theme::ChartColors;
+use chrono::{DateTime, FixedOffset, NaiveDate};
+use iced::{Size, Theme};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::verification::ForecastRun;
+use rusty_wx::weather::ApiWeatherData;
+use serde_json::Value;
+use std::path::PathBuf;
+
+#[path = "../tests/common/mod.rs"]
+mod common;
+use common::{fixture, FIXTURE};
+
+const WIDTH: f32 = 1200.0;
+const METEOGRAM_HEIGHT: f32 = 300.0;
+
+// Between the last observation and the first forecast
+const NOW: &str = "2026-10-18T12:30:00Z";
+// 2026-10-18T18Z, a few hours into the forecast
+const SELECTED: usize = 29;
+
+fn now() -> i64 {
+    DateTime::parse_from_rfc3339(NOW).unwrap().timestamp()
+}
+
+// Day shading and hour labels are drawn in UTC rather than the machine's time zone
+fn utc() -> FixedOffset {
+    FixedOffset::east_opt(0).unwrap()
+}
+
+fn app_for(data: ApiWeatherData) -> WeatherApp {
+    let mut app = WeatherApp::for_location("Denver", data);
+    app.timeline_index = SELECTED;
+    app
+}
+
+fn fixture_app() -> WeatherApp {
+    app_for(fixture())
+}
+
+// The fixture with the temperature missing from a run of observations and of forecasts
+fn fixture_with_gaps() -> WeatherApp {
+    let mut fixture: Value = serde_json::from_str(FIXTURE).unwrap();
+    for (key, range) in [("observationsInstant", 6..10), ("forecastsInstant", 30..36)] {
+        for point in &mut fixture[key].as_array_mut().unwrap()[range] {
+            point["temperature2mF"] = Value::Null;
+        }
+    }
+    app_for(serde_json::from_value(fixture).unwrap())
+}
+
+// The fixture with faults for the quality checks: a temperature spike, a dewpoint above the
+// temperature and a pressure reading off the scale
+fn fixture_with_faults() -> WeatherApp {
+    let mut fixture: Value = serde_json::from_str(FIXTURE).unwrap();
+    let observations = fixture["observationsInstant"].as_array_mut().unwrap();
+    observations[5]["temperature2mF"] = 80.0.into();
+    observations[10]["dewpoint2mF"] = 60.0.into();
+    observations[15]["pressureHPa"] = 101.3.into();
+    app_for(serde_json::from_value(fixture).unwrap())
+}
+
+fn colors() -> ChartColors {
+    ChartColors::from_theme(&Theme::Light)
+}
+
+fn meteogram(meteogram: Meteogram) -> Recording {
+    let mut recording = Recording::default();
+    meteogram.with_now(now()).with_zone(utc()).render(
+        &mut recording,
+        &colors(),
+        Size::new(WIDTH, METEOGRAM_HEIGHT),
+    );
+    recording
+}
+
+fn panel(app: &WeatherApp, panel: MeteogramPanel) -> Recording {
+    let mut recording = Recording::default();
+    app.build_panel(panel)
+        .with_now(now())
+        .with_zone(utc())
+        .render(&mut recording, &colors(), Size::new(WIDTH, PANEL_HEIGHT));
+    recording
+}
+
+// Compare against tests/snapshots/<name>.txt, or rewrite it with UPDATE_SNAPSHOTS=1
+fn assert_snapshot(name: &str, recording: &Recording) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.txt", name));
+    let actual = recording.lines().join("\n") + "\n";
+
+    if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {} (run with UPDATE_SNAPSHOTS=1 to create it)",
+            path.display(),
+            e
+        )
+    });
+    if actual == expected {
+        return;
+    }
+    let (line, expected_line, actual_line) = expected
+        .lines()
+        .zip(actual.lines())
+        .enumerate()
+        .find(|(_, (expected, actual))| expected != actual)
+        .map(|(i, (expected, actual))| (i + 1, expected, actual))
+        .unwrap_or_else(|| {
+            let common = expected.lines().count().min(actual.lines().count());
+            (common + 1, "<end>", "<end>")
+        });
+    panic!(
+        "{} differs from the rendering at line {}\n  expected: {}\n  actual:   {}\n\
+         ({} lines expected, {} drawn; rerun with UPDATE_SNAPSHOTS=1 if the change is intended)",
+        path.display(),
+        line,
+        expected_line,
+        actual_line,
+        expected.lines().count(),
+        actual.lines().count()
+    );
+}
+
+#[test]
+fn meteogram_temperature() {
+    let mut app = fixture_app();
+    app.selected_weather_elements.clear();
+    assert_snapshot("meteogram_temperature", &meteogram(app.build_meteogram()));
+}
+
+#[test]
+fn meteogram_overlays() {
+    let mut app = fixture_app();
+    app.selected_weather_elements = vec![
+        WeatherElement::PrecipitationProbability,
+        WeatherElement::Dewpoint,
+        WeatherElement::WindGust,
+    ];
+    assert_snapshot("meteogram_overlays", &meteogram(app.build_meteogram()));
+}
+
+#[test]
+fn meteogram_alerts() {
+    let app = fixture_app();
+    let start = now() + 6 * 3600;
+    let alerts = vec![(start, start + 9 * 3600)];
+    assert_snapshot(
+        "meteogram_alerts",
+        &meteogram(app.build_meteogram().with_alerts(alerts)),
+    );
+}
+
+#[test]
+fn meteogram_missing_temperatures() {
+    assert_snapshot(
+        "meteogram_gaps",
+        &meteogram(fixture_with_gaps().build_meteogram()),
+    );
+}
+
+#[test]
+fn meteogram_quality_flags() {
+    let mut app = fixture_with_faults();
+    app.selected_weather_elements = vec![WeatherElement::Dewpoint];
+    assert_eq!(app.qc_flags.len(), 3);
+    assert_snapshot("meteogram_qc", &meteogram(app.build_meteogram()));
+    assert_snapshot(
+        "panel_pressure_qc",
+        &panel(&app, MeteogramPanel::PressureCape),
+    );
+}
+
+#[test]
+fn meteogram_previous_runs() {
+    let mut app = fixture_app();
+    app.selected_weather_elements.clear();
+    // Two earlier runs, 3°F warmer and 5°F colder, missing a few hours
+    for (cycle, offset) in [
+        ("2026-10-18T05:00:00Z", 3.0),
+        ("2026-10-17T23:00:00Z", -5.0),
+    ] {
+        let mut data = fixture();
+        for fc in &mut data.forecasts_instant {
+            fc.cycle_date = cycle.to_string();
+            fc.temperature_2m_f = fc.temperature_2m_f.map(|t| t + offset);
+        }
+        data.forecasts_instant.drain(40..46);
+        app.previous_runs
+            .push(ForecastRun::from_weather(&data).unwrap());
+    }
+    app.show_previous_runs = true;
+    assert_snapshot("meteogram_previous_runs", &meteogram(app.build_meteogram()));
+}
+
+#[test]
+fn meteogram_without_data() {
+    let app = WeatherApp::default();
+    assert_snapshot("meteogram_empty", &meteogram(app.build_meteogram()));
+}
+
+#[test]
+fn panels() {
+    let app = fixture_app();
+    for (name, kind) in [
+        ("panel_precipitation", MeteogramPanel::Precipitation),
+        ("panel_wind", MeteogramPanel::Wind),
+        ("panel_cloud_cover", MeteogramPanel::CloudCover),
+        ("panel_pressure_cape", MeteogramPanel::PressureCape),
+    ] {
+        assert_snapshot(name, &panel(&app, kind));
+    }
+}
+
+// Guards against a snapshot that would pass whatever is drawn
+#[test]
+fn recording_captures_layout() {
+    let recording = meteogram(fixture_app().build_meteogram());
+    let lines = recording.lines();
+    assert!(lines[0].starts_with("rect 0.0,0.0 1200.0x300.0"));
+    assert!(lines.iter().any(|line| line.starts_with("text")));
+    assert!(lines.iter().filter(|line| line.starts_with("line")).count() > 95);
+}
+
+// A chart that ends before now, like the history view, has no "now" line at its last hour
+#[test]
+fn now_line_only_within_the_chart() {
+    let now_lines = |now: i64| {
+        let mut recording = Recording::default();
+        fixture_app()
+            .build_meteogram()
+            .with_now(now)
+            .with_zone(utc())
+            .render(
+                &mut recording,
+                &colors(),
+                Size::new(WIDTH, METEOGRAM_HEIGHT),
+            );
+        recording
+            .lines()
+            .iter()
+            .filter(|line| line.contains(",40.0 -> ") && line.ends_with("w2.0"))
+            .count()
+    };
+    assert_eq!(now_lines(now()), 1);
+    assert_eq!(now_lines(now() + 30 * 86400), 0);
+    assert_eq!(now_lines(now() - 30 * 86400), 0);
+}
+
+// The comparison chart, like the meteogram, only marks now within its range, and its table
+// has a high and low for every day in the given time zone
+#[test]
+fn comparison_now_line_and_days() {
+    let location = SavedLocation {
+        name: "Denver".to_string(),
+        lat: 39.74,
+        lon: -104.99,
+    };
+    let mut comparison = Comparison::new(&[location], WeatherElement::Temperature);
+    comparison.set_result(0, Ok(fixture()));
+
+    let now_lines = |now: i64| {
+        let mut recording = Recording::default();
+        let size = Size::new(WIDTH, METEOGRAM_HEIGHT);
+        comparison
+            .chart()
+            .with_now(now)
+            .render(&mut recording, &colors(), size);
+        recording
+            .lines()
+            .iter()
+            .filter(|line| line.contains(",40.0 -> ") && line.contains(",290.0 "))
+            .count()
+    };
+    assert_eq!(now_lines(now()), 1);
+    assert_eq!(now_lines(now() + 30 * 86400), 0);
+    assert_eq!(now_lines(now() - 30 * 86400), 0);
+
+    let site = comparison.sites[0].site.as_ref().unwrap();
+    let days = comparison::daily_extremes(site, &utc());
+    let date = |day| NaiveDate::from_ymd_opt(2026, 10, day).unwrap();
+    assert_eq!(days.len(), 5);
+    assert_eq!(days[0], (date(17), 67.0, 49.0));
+    assert_eq!((days[1].0, days[1].1, days[1].2), (date(18), 72.0, 43.0));
+}
@@ -0,0 +1,200 @@
+//! Statistics over a stretch of observations, such as a date range read back from the archive,
+//! and how its temperatures compare with the normals.
+
+use crate::element::WeatherElement;
+use crate::qc::{self, QcFlag};
+use crate::timeline::element_value;
+use crate::weather::{ApiWeatherData, ObservationInstant, WeatherDataPoint};
+use chrono::{DateTime, NaiveDate, TimeZone};
+
+/// Elements observations report directly, summarized by [`statistics`].
+pub const SUMMARIZED_ELEMENTS: [WeatherElement; 8] = [
+    WeatherElement::Temperature,
+    WeatherElement::Dewpoint,
+    WeatherElement::WindSpeed,
+    WeatherElement::WindGust,
+    WeatherElement::Pressure,
+    WeatherElement::CloudCover,
+    WeatherElement::Visibility,
+    WeatherElement::SolarFlux,
+];
+
+/// Lowest, highest and mean observed value of one element, in the units of [`element_value`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Statistics {
+    pub element: WeatherElement,
+    /// Observations with a value
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+/// One day's observed temperatures next to its normal (°F).
+#[derive(Debug, Clone, PartialEq)]
+pub struct DailyTemperatures {
+    pub date: NaiveDate,
+    /// Hours observed
+    pub count: usize,
+    pub high: f64,
+    pub low: f64,
+    pub mean: f64,
+    /// Mean of the normal temperatures valid that day, if any were fetched
+    pub normal: Option<f64>,
+}
+
+impl DailyTemperatures {
+    /// Mean temperature minus the normal: positive when the day was warmer than normal.
+    pub fn departure(&self) -> Option<f64> {
+        self.normal.map(|normal| self.mean - normal)
+    }
+}
+
+fn date_in<Tz: TimeZone>(date: &str, tz: &Tz) -> Option<NaiveDate> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|time| time.with_timezone(tz).date_naive())
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+// Observations oldest first, without the values of `element` flagged in `flags`
+fn observed_values<'a>(
+    weather: &'a ApiWeatherData,
+    flags: &'a [QcFlag],
+    element: &'a WeatherElement,
+) -> impl Iterator<Item = (&'a str, f64)> + 'a {
+    weather
+        .observations_instant
+        .iter()
+        .rev()
+        .filter_map(move |obs| {
+            let point = WeatherDataPoint::Observation(obs.clone());
+            if qc::flag_for(flags, &point, element).is_some() {
+                return None;
+            }
+            Some((
+                obs.valid_date.as_str(),
+                element_value(weather, &point, element)?,
+            ))
+        })
+}
+
+/// Statistics of each of [`SUMMARIZED_ELEMENTS`] observed at least once in `weather`.
+/// Observed values with a quality flag in `flags` are left out.
+pub fn statistics(weather: &ApiWeatherData, flags: &[QcFlag]) -> Vec<Statistics> {
+    SUMMARIZED_ELEMENTS
+        .iter()
+        .filter_map(|element| {
+            let values: Vec<f64> = observed_values(weather, flags, element)
+                .map(|(_, value)| value)
+                .collect();
+            if values.is_empty() {
+                return None;
+            }
+            Some(Statistics {
+                element: element.clone(),
+                count: values.len(),
+                min: values.iter().copied().fold(f64::INFINITY, f64::min),
+                max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                mean: mean(&values),
+            })
+        })
+        .collect()
+}
+
+/// Observed high, low and mean temperature of every day in `weather`, oldest first, with the
+/// normal temperatures of the same day. Days are those of `tz`, usually [`chrono::Local`].
+/// Flagged temperatures are left out.
+pub fn daily_temperatures<Tz: TimeZone>(
+    weather: &ApiWeatherData,
+    flags: &[QcFlag],
+    tz: &Tz,
+) -> Vec<DailyTemperatures> {
+    let mut days: Vec<(NaiveDate, Vec<f64>)> = Vec::new();
+    for (date, temperature) in observed_values(weather, flags, &WeatherElement::Temperature) {
+        let Some(date) = date_in(date, tz) else {
+            continue;
+        };
+        match days.last_mut() {
+            Some((day, temperatures)) if *day == date => temperatures.push(temperature),
+            _ => days.push((date, vec![temperature])),
+        }
+    }
+
+    days.into_iter()
+        .map(|(date, temperatures)| {
+            let normals: Vec<f64> = weather
+                .normal_temperatures
+                .iter()
+                .filter(|normal| date_in(&normal.valid_date, tz) == Some(date))
+                .map(|normal| normal.temperature_2m_f)
+                .collect();
+            DailyTemperatures {
+                date,
+                count: temperatures.len(),
+                high: temperatures
+                    .iter()
+                    .copied()
+                    .fold(f64::NEG_INFINITY, f64::max),
+                low: temperatures.iter().copied().fold(f64::INFINITY, f64::min),
+                mean: mean(&temperatures),
+                normal: (!normals.is_empty()).then(|| mean(&normals)),
+            }
+        })
+        .collect()
+}
+
+/// Mean departure from normal over the days with a normal, or `None` if none has one.
+pub fn departure_from_normal(days: &[DailyTemperatures]) -> Option<f64> {
+    let departures: Vec<f64> = days.iter().filter_map(|day| day.departure()).collect();
+    (!departures.is_empty()).then(|| mean(&departures))
+}
+
+/// The observations with the lowest and highest temperature of every day in `weather`, newest
+/// first like the feed, to draw a long range as one low and one high a day rather than every
+/// hour. Days are those of `tz`; flagged temperatures are left out.
+pub fn daily_extremes<Tz: TimeZone>(
+    weather: &ApiWeatherData,
+    flags: &[QcFlag],
+    tz: &Tz,
+) -> Vec<ObservationInstant> {
+    // Indexes of each day's lowest and highest temperature, newest day first
+    let temperature = |i: usize| weather.observations_instant[i].temperature_2m_f;
+    let mut days: Vec<(NaiveDate, usize, usize)> = Vec::new();
+    for (i, obs) in weather.observations_instant.iter().enumerate() {
+        let point = WeatherDataPoint::Observation(obs.clone());
+        if obs.temperature_2m_f.is_none()
+            || qc::flag_for(flags, &point, &WeatherElement::Temperature).is_some()
+        {
+            continue;
+        }
+        let Some(date) = date_in(&obs.valid_date, tz) else {
+            continue;
+        };
+        match days.last_mut() {
+            Some((day, low, high)) if *day == date => {
+                if obs.temperature_2m_f < temperature(*low) {
+                    *low = i;
+                }
+                if obs.temperature_2m_f > temperature(*high) {
+                    *high = i;
+                }
+            }
+            _ => days.push((date, i, i)),
+        }
+    }
+
+    let mut indexes: Vec<usize> = days
+        .iter()
+        .flat_map(|(_, low, high)| [*low, *high])
+        .collect();
+    indexes.sort_unstable();
+    indexes.dedup();
+    indexes
+        .into_iter()
+        .map(|i| weather.observations_instant[i].clone())
+        .collect()
+}
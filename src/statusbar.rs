@@ -0,0 +1,248 @@
+use crate::alerts;
+use crate::app::WeatherApp;
+use chrono::{DateTime, Duration, NaiveDateTime, TimeZone, Timelike, Utc};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::weather::WeatherDataPoint;
+use serde_json::{json, Value};
+
+// One update in the waybar custom module protocol: the latest observation as text, the
+// next hours of forecast in the tooltip, the alert state or temperature band as class,
+// and the highest chance of precipitation over those hours as percentage. Hours are shown in
+// `tz`, usually Local.
+pub fn status<Tz: TimeZone>(app: &WeatherApp, hours: i64, now: DateTime<Utc>, tz: &Tz) -> Value {
+    let current = app
+        .combined_timeline
+        .iter()
+        .rev()
+        .find(|point| matches!(point, WeatherDataPoint::Observation(_)))
+        .or_else(|| app.combined_timeline.get(app.timeline_index));
+    let Some(current) = current else {
+        return error_status(&format!("{}: no data", app.current_city));
+    };
+    let value = |point: &WeatherDataPoint, element: WeatherElement| {
+        app.get_element_value_for_scale(point, &element)
+    };
+
+    let upcoming: Vec<(NaiveDateTime, &WeatherDataPoint)> = app
+        .combined_timeline
+        .iter()
+        .filter(|point| matches!(point, WeatherDataPoint::Forecast(_)))
+        .filter_map(|point| {
+            let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+            Some((time, point))
+        })
+        .filter(|(time, _)| *time > now && *time <= now + Duration::hours(hours))
+        .map(|(time, point)| (time.with_timezone(tz).naive_local(), point))
+        .collect();
+    let precip = upcoming
+        .iter()
+        .filter_map(|(_, point)| value(point, WeatherElement::PrecipitationProbability))
+        .fold(0.0, f64::max);
+
+    let temperature = current.temperature();
+    let local_hour = now.with_timezone(tz).hour();
+    let text = match temperature {
+        Some(temp) => format!("{} {:.0}°", icon(app, current, local_hour), temp),
+        None => "--".to_string(),
+    };
+
+    let mut tooltip = vec![format!("<b>{}</b>", escape_markup(&app.current_city))];
+    let mut conditions = Vec::new();
+    if let Some(apparent) = value(current, WeatherElement::ApparentTemperature) {
+        conditions.push(format!("feels like {:.0}°", apparent));
+    }
+    if let Some(humidity) = value(current, WeatherElement::Humidity) {
+        conditions.push(format!("{:.0}% humidity", humidity));
+    }
+    if let Some(wind) = value(current, WeatherElement::WindSpeed) {
+        conditions.push(format!("wind {:.0} mph", wind));
+    }
+    if !conditions.is_empty() {
+        tooltip.push(conditions.join(", "));
+    }
+    tooltip.push(String::new());
+    for (time, point) in &upcoming {
+        let temp = point
+            .temperature()
+            .map_or("--".to_string(), |t| format!("{:.0}°", t));
+        let chance = value(point, WeatherElement::PrecipitationProbability)
+            .map_or(String::new(), |p| format!("  {:.0}% precip", p));
+        tooltip.push(format!(
+            "{:>5}  {:>4}{}",
+            time.format("%-I %p"),
+            temp,
+            chance
+        ));
+    }
+
+    let triggered = alerts::evaluate(
+        &app.config.alerts,
+        &app.combined_timeline,
+        |point, element| app.get_element_value_for_scale(point, element),
+        now.timestamp(),
+    );
+    for alert in &triggered {
+        tooltip.push(format!(
+            "⚠ {} {}",
+            alert.rule.description(),
+            alert.message()
+        ));
+    }
+
+    let class = if !triggered.is_empty() {
+        "alert"
+    } else {
+        match temperature {
+            Some(t) if t < 32.0 => "freezing",
+            Some(t) if t < 50.0 => "cold",
+            Some(t) if t < 70.0 => "mild",
+            Some(t) if t < 85.0 => "warm",
+            Some(_) => "hot",
+            None => "unknown",
+        }
+    };
+
+    json!({
+        "text": text,
+        "tooltip": tooltip.join("\n"),
+        "class": class,
+        "percentage": precip.round() as i64,
+    })
+}
+
+pub fn error_status(message: &str) -> Value {
+    json!({
+        "text": "--",
+        "tooltip": escape_markup(message),
+        "class": "error",
+        "percentage": 0,
+    })
+}
+
+// Tooltips are Pango markup
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Sky glyph from the forecast nearest now, since observations lack storm and precip chances
+fn icon(app: &WeatherApp, current: &WeatherDataPoint, hour: u32) -> &'static str {
+    let nearest = app
+        .combined_timeline
+        .get(app.timeline_index)
+        .unwrap_or(current);
+    let value = |element: WeatherElement| app.get_element_value_for_scale(nearest, &element);
+    let night = !(6..18).contains(&hour);
+
+    if value(WeatherElement::ThunderstormProbability).unwrap_or(0.0) >= 30.0 {
+        "⛈"
+    } else if value(WeatherElement::PrecipitationProbability).unwrap_or(0.0) >= 50.0 {
+        "🌧"
+    } else {
+        match app.get_element_value_for_scale(current, &WeatherElement::CloudCover) {
+            Some(c) if c >= 70.0 => "☁",
+            Some(c) if c >= 30.0 && !night => "⛅",
+            _ if night => "☾",
+            _ => "☀",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alerts::{AlertRule, Condition};
+    use chrono::FixedOffset;
+    use rusty_wx::weather::ApiWeatherData;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    fn fixture() -> ApiWeatherData {
+        serde_json::from_str(FIXTURE).unwrap()
+    }
+
+    // Between the last observation, 63°F at 12Z, and the first forecast
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-10-18T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn app_with(data: ApiWeatherData) -> WeatherApp {
+        let mut app = WeatherApp::for_location("Denver", data);
+        // The first forecast hour, whatever the clock says
+        app.timeline_index = 24;
+        app
+    }
+
+    #[test]
+    fn next_hours_of_the_fixture() {
+        let status = status(&app_with(fixture()), 6, now(), &Utc);
+        assert_eq!(status["text"], "⛅ 63°");
+        assert_eq!(status["class"], "mild");
+        // The highest chance over 13Z to 18Z
+        assert_eq!(status["percentage"], 36);
+
+        let tooltip = status["tooltip"].as_str().unwrap();
+        let lines: Vec<&str> = tooltip.lines().collect();
+        assert_eq!(lines[0], "<b>Denver</b>");
+        assert_eq!(lines[1], "feels like 63°, 41% humidity, wind 9 mph");
+        assert_eq!(lines[3..].len(), 6);
+        assert_eq!(lines[3], " 1 PM   70°");
+        assert_eq!(lines[8], " 6 PM   68°  36% precip");
+
+        // Hours are those of the time zone
+        let denver = FixedOffset::west_opt(6 * 3600).unwrap();
+        let status = super::status(&app_with(fixture()), 6, now(), &denver);
+        assert!(status["tooltip"]
+            .as_str()
+            .unwrap()
+            .contains("\n 7 AM   70°\n"));
+    }
+
+    #[test]
+    fn class_is_the_temperature_band() {
+        for (temperature, class) in [
+            (Some(10.0), "freezing"),
+            (Some(31.9), "freezing"),
+            (Some(32.0), "cold"),
+            (Some(49.0), "cold"),
+            (Some(50.0), "mild"),
+            (Some(70.0), "warm"),
+            (Some(85.0), "hot"),
+            (None, "unknown"),
+        ] {
+            let mut data = fixture();
+            data.observations_instant[0].temperature_2m_f = temperature;
+            let status = status(&app_with(data), 6, now(), &Utc);
+            assert_eq!(status["class"], class, "{:?}", temperature);
+            if temperature.is_none() {
+                assert_eq!(status["text"], "--");
+            }
+        }
+    }
+
+    #[test]
+    fn alerts_take_over_the_class() {
+        let mut app = app_with(fixture());
+        app.config.alerts = vec![AlertRule {
+            element: WeatherElement::WindGust,
+            condition: Condition::Above,
+            threshold: 30.0,
+            within_hours: None,
+        }];
+        let status = status(&app, 6, now(), &Utc);
+        assert_eq!(status["class"], "alert");
+        assert!(status["tooltip"].as_str().unwrap().contains("\n⚠ "));
+        assert_eq!(status["percentage"], 36);
+    }
+
+    #[test]
+    fn errors_are_escaped() {
+        let status = error_status("Fetch <failed> & retrying");
+        assert_eq!(status["class"], "error");
+        assert_eq!(status["tooltip"], "Fetch &lt;failed&gt; &amp; retrying");
+        assert_eq!(status["percentage"], 0);
+    }
+}
@@ -0,0 +1,230 @@
+use crate::timeline::precipitation_probability_from;
+use crate::weather::{ForecastInstant, ForecastPrecip, WeatherDataPoint};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc};
+
+// Chances below this aren't worth mentioning
+const MENTION_PCT: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Half {
+    Day,
+    Night,
+}
+
+// One forecast period: 6 AM-6 PM for day, 6 PM-6 AM for night, keyed by the date it starts on.
+// Times are the wall-clock times of the summary's time zone.
+struct Period<'a> {
+    date: NaiveDate,
+    half: Half,
+    points: Vec<(NaiveDateTime, &'a WeatherDataPoint)>,
+}
+
+// A plain-language forecast, one line per period, for people who can't read the meteogram.
+// Periods, their names and times are those of `tz`, usually Local.
+pub fn forecast_summary<Tz: TimeZone>(
+    timeline: &[WeatherDataPoint],
+    precip_1hr: &[ForecastPrecip],
+    precip_6hr: &[ForecastPrecip],
+    now: DateTime<Utc>,
+    tz: &Tz,
+) -> Vec<String> {
+    let mut periods: Vec<Period> = Vec::new();
+
+    for point in timeline {
+        let WeatherDataPoint::Forecast(_) = point else {
+            continue;
+        };
+        let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) else {
+            continue;
+        };
+        if dt < now - Duration::minutes(30) {
+            continue;
+        }
+        let local = dt.with_timezone(tz).naive_local();
+
+        let (date, half) = match local.hour() {
+            6..=17 => (local.date(), Half::Day),
+            18..=23 => (local.date(), Half::Night),
+            _ => (local.date() - Duration::days(1), Half::Night),
+        };
+        match periods.last_mut() {
+            Some(period) if period.date == date && period.half == half => {
+                period.points.push((local, point))
+            }
+            _ => periods.push(Period {
+                date,
+                half,
+                points: vec![(local, point)],
+            }),
+        }
+    }
+
+    let now = now.with_timezone(tz).naive_local();
+    periods
+        .iter()
+        // A couple of leftover hours at the end of the forecast don't make a period
+        .filter(|period| period.points.len() >= 3 || period.date <= now.date())
+        .map(|period| {
+            format!(
+                "{}: {}",
+                period_name(period, now),
+                describe(period, precip_1hr, precip_6hr)
+            )
+        })
+        .collect()
+}
+
+fn period_name(period: &Period, now: NaiveDateTime) -> String {
+    let today = now.date();
+    match period.half {
+        Half::Day if period.date == today => {
+            if now.hour() >= 12 {
+                "This afternoon".to_string()
+            } else {
+                "Today".to_string()
+            }
+        }
+        Half::Night if period.date < today => "Overnight".to_string(),
+        Half::Night if period.date == today => "Tonight".to_string(),
+        Half::Day if period.date == today + Duration::days(1) => "Tomorrow".to_string(),
+        Half::Night if period.date == today + Duration::days(1) => "Tomorrow night".to_string(),
+        Half::Day => period.date.format("%A").to_string(),
+        Half::Night => period.date.format("%A night").to_string(),
+    }
+}
+
+fn describe(
+    period: &Period,
+    precip_1hr: &[ForecastPrecip],
+    precip_6hr: &[ForecastPrecip],
+) -> String {
+    let forecasts: Vec<_> = period
+        .points
+        .iter()
+        .filter_map(|(time, point)| match point {
+            WeatherDataPoint::Forecast(fc) => Some((*time, fc)),
+            WeatherDataPoint::Observation(_) => None,
+        })
+        .collect();
+    let mut parts = Vec::new();
+
+    // Sky
+    let clouds: Vec<f64> = forecasts
+        .iter()
+        .filter_map(|(_, fc)| fc.cloud_cover_pct)
+        .collect();
+    if !clouds.is_empty() {
+        let mean = clouds.iter().sum::<f64>() / clouds.len() as f64;
+        parts.push(sky_description(mean, period.half).to_string());
+    }
+
+    // Temperature: the high by day, the low by night
+    let temps = forecasts.iter().filter_map(|(_, fc)| fc.temperature_2m_f);
+    let extreme = match period.half {
+        Half::Day => temps.fold(None, |acc: Option<f64>, t| {
+            Some(acc.map_or(t, |a| a.max(t)))
+        }),
+        Half::Night => temps.fold(None, |acc: Option<f64>, t| {
+            Some(acc.map_or(t, |a| a.min(t)))
+        }),
+    };
+    if let Some(temp) = extreme {
+        let label = match period.half {
+            Half::Day => "high",
+            Half::Night => "low",
+        };
+        parts.push(format!("{} {:.0}°", label, temp));
+    }
+
+    // Wind: prevailing direction, strongest sustained speed and gust
+    let speeds: Vec<f64> = forecasts
+        .iter()
+        .filter_map(|(_, fc)| fc.wind_spd_10m_mph)
+        .collect();
+    if let Some(max_speed) = speeds.iter().copied().reduce(f64::max) {
+        if max_speed < 5.0 {
+            parts.push("light winds".to_string());
+        } else {
+            let mut wind = "winds".to_string();
+            if let Some(direction) = prevailing_direction(&forecasts) {
+                wind.push(' ');
+                wind.push_str(direction);
+            }
+            wind.push_str(&format!(" {:.0}", max_speed));
+            let max_gust = forecasts
+                .iter()
+                .filter_map(|(_, fc)| fc.wind_gust_10m_mph)
+                .reduce(f64::max);
+            if let Some(gust) = max_gust {
+                if gust >= max_speed + 5.0 {
+                    wind.push_str(&format!(" gusting {:.0}", gust));
+                }
+            }
+            parts.push(wind);
+        }
+    }
+
+    // Precipitation, with when it becomes likely if that's not from the start
+    let chances: Vec<(NaiveDateTime, f64)> = forecasts
+        .iter()
+        .filter_map(|(time, fc)| {
+            precipitation_probability_from(precip_1hr, precip_6hr, &fc.valid_date)
+                .map(|p| (*time, p))
+        })
+        .collect();
+    if let Some(max_chance) = chances.iter().map(|(_, p)| *p).reduce(f64::max) {
+        if max_chance >= MENTION_PCT {
+            let mut precip = format!("{:.0}% chance of precipitation", max_chance);
+            let onset = chances.iter().position(|(_, p)| *p >= MENTION_PCT);
+            if let Some(i) = onset.filter(|i| *i > 0) {
+                precip.push_str(&format!(" after {}", chances[i].0.format("%-I %p")));
+            }
+            parts.push(precip);
+        }
+    }
+
+    let thunder = forecasts
+        .iter()
+        .filter_map(|(_, fc)| fc.prob_thunderstorm_pct)
+        .reduce(f64::max);
+    if let Some(thunder) = thunder.filter(|t| *t >= MENTION_PCT) {
+        parts.push(format!("{:.0}% chance of thunderstorms", thunder));
+    }
+
+    if parts.is_empty() {
+        "No forecast details available.".to_string()
+    } else {
+        let mut text = parts.join(", ");
+        text.push('.');
+        text
+    }
+}
+
+fn sky_description(cloud_cover_pct: f64, half: Half) -> &'static str {
+    match (cloud_cover_pct, half) {
+        (c, Half::Day) if c < 15.0 => "Sunny",
+        (c, Half::Night) if c < 15.0 => "Clear",
+        (c, Half::Day) if c < 40.0 => "Mostly sunny",
+        (c, Half::Night) if c < 40.0 => "Mostly clear",
+        (c, _) if c < 70.0 => "Partly cloudy",
+        (c, _) if c < 90.0 => "Mostly cloudy",
+        _ => "Cloudy",
+    }
+}
+
+// Speed-weighted mean of the wind directions, as an 8-point compass direction
+fn prevailing_direction(forecasts: &[(NaiveDateTime, &ForecastInstant)]) -> Option<&'static str> {
+    let (mut x, mut y) = (0.0, 0.0);
+    for (_, fc) in forecasts {
+        if let (Some(dir), Some(speed)) = (fc.wind_dir_10m_deg_fm_n, fc.wind_spd_10m_mph) {
+            x += speed * dir.to_radians().sin();
+            y += speed * dir.to_radians().cos();
+        }
+    }
+    if x == 0.0 && y == 0.0 {
+        return None;
+    }
+    let degrees = x.atan2(y).to_degrees().rem_euclid(360.0);
+    const POINTS: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    Some(POINTS[((degrees / 45.0).round() as usize) % 8])
+}
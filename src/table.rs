@@ -0,0 +1,285 @@
+use crate::app::WeatherApp;
+use chrono::{DateTime, Local};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::weather::WeatherDataPoint;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+// Columns of the hourly table, in display order
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TableColumn {
+    Temperature,
+    Dewpoint,
+    Humidity,
+    Apparent,
+    WBGT,
+    WindSpeed,
+    WindGust,
+    WindDirection,
+    CloudCover,
+    Ceiling,
+    Visibility,
+    Pressure,
+    SolarFlux,
+    Thunderstorm,
+    CAPE,
+    Precipitation,
+}
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 16] = [
+        TableColumn::Temperature,
+        TableColumn::Dewpoint,
+        TableColumn::Humidity,
+        TableColumn::Apparent,
+        TableColumn::WBGT,
+        TableColumn::WindSpeed,
+        TableColumn::WindGust,
+        TableColumn::WindDirection,
+        TableColumn::CloudCover,
+        TableColumn::Ceiling,
+        TableColumn::Visibility,
+        TableColumn::Pressure,
+        TableColumn::SolarFlux,
+        TableColumn::Thunderstorm,
+        TableColumn::CAPE,
+        TableColumn::Precipitation,
+    ];
+
+    pub fn header(&self) -> &'static str {
+        match self {
+            TableColumn::Temperature => "Temp °F",
+            TableColumn::Dewpoint => "Dewpt °F",
+            TableColumn::Humidity => "RH %",
+            TableColumn::Apparent => "Feels °F",
+            TableColumn::WBGT => "WBGT °F",
+            TableColumn::WindSpeed => "Wind mph",
+            TableColumn::WindGust => "Gust mph",
+            TableColumn::WindDirection => "Dir °",
+            TableColumn::CloudCover => "Cloud %",
+            TableColumn::Ceiling => "Ceil ft",
+            TableColumn::Visibility => "Vis mi",
+            TableColumn::Pressure => "Pres hPa",
+            TableColumn::SolarFlux => "Solar W/m²",
+            TableColumn::Thunderstorm => "T-Storm %",
+            TableColumn::CAPE => "CAPE J/kg",
+            TableColumn::Precipitation => "Precip %",
+        }
+    }
+
+    // CSV header, with the units spelled out
+    pub fn key(&self) -> &'static str {
+        match self {
+            TableColumn::Temperature => "temperature_f",
+            TableColumn::Dewpoint => "dewpoint_f",
+            TableColumn::Humidity => "humidity_pct",
+            TableColumn::Apparent => "apparent_temperature_f",
+            TableColumn::WBGT => "wbgt_f",
+            TableColumn::WindSpeed => "wind_speed_mph",
+            TableColumn::WindGust => "wind_gust_mph",
+            TableColumn::WindDirection => "wind_direction_deg",
+            TableColumn::CloudCover => "cloud_cover_pct",
+            TableColumn::Ceiling => "cloud_ceiling_ft",
+            TableColumn::Visibility => "visibility_mi",
+            TableColumn::Pressure => "pressure_hpa",
+            TableColumn::SolarFlux => "solar_flux_w_m2",
+            TableColumn::Thunderstorm => "thunderstorm_pct",
+            TableColumn::CAPE => "cape_j_kg",
+            TableColumn::Precipitation => "precipitation_pct",
+        }
+    }
+
+    fn element(&self) -> Option<WeatherElement> {
+        match self {
+            TableColumn::Temperature => Some(WeatherElement::Temperature),
+            TableColumn::Dewpoint => Some(WeatherElement::Dewpoint),
+            TableColumn::Humidity => Some(WeatherElement::Humidity),
+            TableColumn::Apparent => Some(WeatherElement::ApparentTemperature),
+            TableColumn::WBGT => Some(WeatherElement::WBGT),
+            TableColumn::WindSpeed => Some(WeatherElement::WindSpeed),
+            TableColumn::WindGust => Some(WeatherElement::WindGust),
+            TableColumn::CloudCover => Some(WeatherElement::CloudCover),
+            TableColumn::Visibility => Some(WeatherElement::Visibility),
+            TableColumn::Pressure => Some(WeatherElement::Pressure),
+            TableColumn::SolarFlux => Some(WeatherElement::SolarFlux),
+            TableColumn::Thunderstorm => Some(WeatherElement::ThunderstormProbability),
+            TableColumn::CAPE => Some(WeatherElement::CAPE),
+            TableColumn::Precipitation => Some(WeatherElement::PrecipitationProbability),
+            TableColumn::WindDirection | TableColumn::Ceiling => None,
+        }
+    }
+
+    // Values use the same derivations as the cards and meteogram
+    pub fn value(&self, app: &WeatherApp, point: &WeatherDataPoint) -> Option<f64> {
+        if let Some(element) = self.element() {
+            return app.get_element_value_for_scale(point, &element);
+        }
+        match (self, point) {
+            (TableColumn::WindDirection, WeatherDataPoint::Observation(obs)) => {
+                obs.wind_dir_10m_deg_fm_n
+            }
+            (TableColumn::WindDirection, WeatherDataPoint::Forecast(fc)) => {
+                fc.wind_dir_10m_deg_fm_n
+            }
+            (TableColumn::Ceiling, WeatherDataPoint::Observation(obs)) => {
+                obs.cloud_ceiling_m.map(|m| m * 3.28084)
+            }
+            (TableColumn::Ceiling, WeatherDataPoint::Forecast(fc)) => {
+                fc.cloud_ceiling_m.map(|m| m * 3.28084)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn format(&self, value: f64) -> String {
+        match self {
+            TableColumn::Visibility => format!("{:.1}", value),
+            _ => format!("{:.0}", value),
+        }
+    }
+}
+
+// One hour of the combined timeline with every column's value
+pub struct TableRow {
+    pub valid_date: String,
+    pub time: DateTime<Local>,
+    pub observation: bool,
+    values: Vec<Option<f64>>,
+}
+
+impl TableRow {
+    pub fn value(&self, column: TableColumn) -> Option<f64> {
+        let index = TableColumn::ALL.iter().position(|c| *c == column)?;
+        self.values[index]
+    }
+}
+
+// Sort by a column, ascending or descending; None keeps time order
+pub type TableSort = Option<(TableColumn, bool)>;
+
+pub fn rows(app: &WeatherApp, sort: TableSort) -> Vec<TableRow> {
+    let mut rows: Vec<TableRow> = app
+        .combined_timeline
+        .iter()
+        .filter_map(|point| {
+            let time = DateTime::parse_from_rfc3339(point.valid_date()).ok()?;
+            Some(TableRow {
+                valid_date: point.valid_date().to_string(),
+                time: time.with_timezone(&Local),
+                observation: matches!(point, WeatherDataPoint::Observation(_)),
+                values: TableColumn::ALL
+                    .iter()
+                    .map(|column| column.value(app, point))
+                    .collect(),
+            })
+        })
+        .collect();
+
+    if let Some((column, ascending)) = sort {
+        // Missing values always go last
+        rows.sort_by(|a, b| match (a.value(column), b.value(column)) {
+            (Some(x), Some(y)) if ascending => x.total_cmp(&y),
+            (Some(x), Some(y)) => y.total_cmp(&x),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+    }
+    rows
+}
+
+// The rows as CSV, with blank cells for missing values
+pub fn to_csv(rows: &[TableRow], columns: &[TableColumn]) -> String {
+    let mut csv = String::from("valid_date,kind");
+    for column in columns {
+        csv.push(',');
+        csv.push_str(column.key());
+    }
+    csv.push('\n');
+
+    for row in rows {
+        csv.push_str(&row.valid_date);
+        csv.push_str(if row.observation {
+            ",observation"
+        } else {
+            ",forecast"
+        });
+        for column in columns {
+            csv.push(',');
+            if let Some(value) = row.value(*column) {
+                csv.push_str(&column.format(value));
+            }
+        }
+        csv.push('\n');
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIXTURE: &str = include_str!("../tests/fixtures/denver.json");
+
+    // 24 observations, then 72 forecasts
+    fn app() -> WeatherApp {
+        WeatherApp::for_location("Denver", serde_json::from_str(FIXTURE).unwrap())
+    }
+
+    #[test]
+    fn rows_in_time_order() {
+        let rows = rows(&app(), None);
+        assert_eq!(rows.len(), 96);
+        assert_eq!(rows[0].valid_date, "2026-10-17T13:00:00Z");
+        assert!(rows[..24].iter().all(|row| row.observation));
+        assert!(rows[24..].iter().all(|row| !row.observation));
+        assert!(rows.windows(2).all(|pair| pair[0].time < pair[1].time));
+    }
+
+    // Only forecasts have a WBGT, so observations end up last both ways
+    #[test]
+    fn missing_values_sort_last() {
+        let app = app();
+        for ascending in [true, false] {
+            let rows = rows(&app, Some((TableColumn::WBGT, ascending)));
+            let values: Vec<Option<f64>> = rows
+                .iter()
+                .map(|row| row.value(TableColumn::WBGT))
+                .collect();
+            assert!(values[..72].iter().all(Option::is_some));
+            assert!(values[72..].iter().all(Option::is_none));
+            assert!(values[..72].windows(2).all(|pair| if ascending {
+                pair[0] <= pair[1]
+            } else {
+                pair[0] >= pair[1]
+            }));
+        }
+    }
+
+    #[test]
+    fn csv_cells() {
+        let app = app();
+        let columns = [TableColumn::WBGT, TableColumn::Visibility];
+        let csv = to_csv(&rows(&app, None), &columns);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "valid_date,kind,wbgt_f,visibility_mi");
+        assert_eq!(lines.len(), 1 + 96);
+        // Nothing needs quoting: every line has one cell per column, blank when missing
+        assert!(lines.iter().all(|line| line.split(',').count() == 4));
+
+        let observation: Vec<&str> = lines[1].split(',').collect();
+        assert_eq!(
+            observation[..3],
+            ["2026-10-17T13:00:00Z", "observation", ""]
+        );
+        let first = &app.combined_timeline[0];
+        assert_eq!(
+            observation[3],
+            format!("{:.1}", TableColumn::Visibility.value(&app, first).unwrap())
+        );
+        let forecast: Vec<&str> = lines[25].split(',').collect();
+        assert_eq!(forecast[1], "forecast");
+        assert!(forecast[2].parse::<f64>().is_ok());
+    }
+}
@@ -0,0 +1,194 @@
+use iced::{Color, Theme};
+use rusty_wx::element::WeatherElement;
+use serde::{Deserialize, Serialize};
+use zbus::zvariant::{OwnedValue, Value};
+
+// Light/dark preference; Auto follows the desktop's color scheme
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ThemeMode {
+    Light,
+    Dark,
+    #[default]
+    Auto,
+}
+
+impl ThemeMode {
+    pub const ALL: [ThemeMode; 3] = [ThemeMode::Light, ThemeMode::Dark, ThemeMode::Auto];
+
+    // The iced theme for this mode, given the last known system preference
+    pub fn theme(&self, system_dark: bool) -> Theme {
+        let dark = match self {
+            ThemeMode::Light => false,
+            ThemeMode::Dark => true,
+            ThemeMode::Auto => system_dark,
+        };
+        if dark {
+            Theme::Dark
+        } else {
+            Theme::Light
+        }
+    }
+}
+
+impl std::fmt::Display for ThemeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            ThemeMode::Light => "Light",
+            ThemeMode::Dark => "Dark",
+            ThemeMode::Auto => "Auto",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Whether the desktop prefers a dark color scheme. Asks the XDG desktop portal first
+// (COSMIC, GNOME and KDE all implement it), then falls back to local settings.
+pub async fn system_prefers_dark() -> bool {
+    match portal_color_scheme().await {
+        // 1 = prefer dark, 2 = prefer light, 0 = no preference
+        Some(1) => true,
+        Some(2) => false,
+        _ => local_prefers_dark(),
+    }
+}
+
+async fn portal_color_scheme() -> Option<u32> {
+    let connection = zbus::Connection::session().await.ok()?;
+    let args = ("org.freedesktop.appearance", "color-scheme");
+
+    // ReadOne is the current method; older portals only have the deprecated Read,
+    // which wraps the value in an extra variant
+    for method in ["ReadOne", "Read"] {
+        let reply = connection
+            .call_method(
+                Some("org.freedesktop.portal.Desktop"),
+                "/org/freedesktop/portal/desktop",
+                Some("org.freedesktop.portal.Settings"),
+                method,
+                &args,
+            )
+            .await;
+        if let Ok(reply) = reply {
+            let value: OwnedValue = reply.body().deserialize().ok()?;
+            return color_scheme_value(&value);
+        }
+    }
+    None
+}
+
+fn color_scheme_value(value: &Value) -> Option<u32> {
+    match value {
+        Value::U32(scheme) => Some(*scheme),
+        Value::Value(inner) => color_scheme_value(inner),
+        _ => None,
+    }
+}
+
+// Without a portal: COSMIC's own theme setting, then a GTK_THEME like "Adwaita:dark"
+fn local_prefers_dark() -> bool {
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(std::path::PathBuf::from)
+        .or_else(|_| std::env::var("HOME").map(|home| std::path::Path::new(&home).join(".config")));
+    if let Ok(config_home) = config_home {
+        let cosmic = config_home.join("cosmic/com.system76.CosmicTheme.Mode/v1/is_dark");
+        if let Ok(contents) = std::fs::read_to_string(cosmic) {
+            return contents.trim() == "true";
+        }
+    }
+
+    std::env::var("GTK_THEME")
+        .map(|theme| theme.to_lowercase().ends_with(":dark"))
+        .unwrap_or(false)
+}
+
+// Observed values that failed the quality checks, on the charts and the cards
+pub const QC_COLOR: Color = Color::from_rgb(0.9, 0.55, 0.0);
+
+// Colors for the meteogram canvases, derived from the active theme
+#[derive(Debug, Clone, Copy)]
+pub struct ChartColors {
+    pub background: Color,
+    // Background of every other day
+    pub day_shade: Color,
+    pub text: Color,
+    // Day labels, wind barbs
+    pub label: Color,
+    // Secondary text like the forecast cycle and panel titles
+    pub muted: Color,
+    // The "now" line and selected point
+    pub accent: Color,
+    // The selected hour's vertical marker
+    pub marker: Color,
+    // Hours that trigger an alert rule
+    pub alert: Color,
+    // Observed values that failed the quality checks
+    pub qc: Color,
+    dark: bool,
+}
+
+impl ChartColors {
+    pub fn from_theme(theme: &Theme) -> Self {
+        let palette = theme.extended_palette();
+        let background = palette.background.base.color;
+        let text = palette.background.base.text;
+        Self {
+            background: mix(background, text, 0.05),
+            day_shade: mix(background, text, 0.1),
+            text,
+            label: mix(background, text, 0.8),
+            muted: mix(background, text, 0.5),
+            accent: palette.primary.strong.color,
+            marker: Color { a: 0.4, ..text },
+            alert: palette.danger.base.color,
+            qc: QC_COLOR,
+            dark: palette.is_dark,
+        }
+    }
+
+    // A data series color, lightened so it stays readable on dark backgrounds
+    pub fn series(&self, color: Color) -> Color {
+        if self.dark {
+            Color {
+                a: color.a,
+                ..mix(color, Color::WHITE, 0.35)
+            }
+        } else {
+            color
+        }
+    }
+}
+
+// Line color used for an element's overlay series, its legend entry and its axis labels.
+// A trait since the element type lives in the GUI-free library.
+pub trait ElementColor {
+    fn color(&self) -> Color;
+}
+
+impl ElementColor for WeatherElement {
+    fn color(&self) -> Color {
+        match self {
+            WeatherElement::Temperature => Color::from_rgb(0.8, 0.2, 0.2),
+            WeatherElement::ApparentTemperature => Color::from_rgb(0.85, 0.45, 0.1),
+            WeatherElement::WBGT => Color::from_rgb(0.6, 0.2, 0.6),
+            WeatherElement::WindSpeed => Color::from_rgb(0.3, 0.5, 0.3),
+            WeatherElement::WindGust => Color::from_rgb(0.6, 0.75, 0.6),
+            WeatherElement::Pressure => Color::from_rgb(0.4, 0.3, 0.2),
+            WeatherElement::Humidity => Color::from_rgb(0.1, 0.6, 0.6),
+            WeatherElement::Dewpoint => Color::from_rgb(0.1, 0.5, 0.2),
+            WeatherElement::CloudCover => Color::from_rgb(0.5, 0.5, 0.5),
+            WeatherElement::Visibility => Color::from_rgb(0.55, 0.55, 0.2),
+            WeatherElement::SolarFlux => Color::from_rgb(0.9, 0.7, 0.0),
+            WeatherElement::ThunderstormProbability => Color::from_rgb(0.5, 0.1, 0.7),
+            WeatherElement::CAPE => Color::from_rgb(0.7, 0.1, 0.4),
+            WeatherElement::PrecipitationProbability => Color::from_rgb(0.1, 0.4, 0.8),
+        }
+    }
+}
+
+fn mix(from: Color, to: Color, amount: f32) -> Color {
+    Color::from_rgb(
+        from.r + (to.r - from.r) * amount,
+        from.g + (to.g - from.g) * amount,
+        from.b + (to.b - from.b) * amount,
+    )
+}
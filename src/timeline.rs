@@ -0,0 +1,190 @@
+//! Building the combined observation and forecast timeline, and reading values from it.
+
+use crate::element::WeatherElement;
+use crate::weather::{
+    calculate_apparent_temperature, dewpoint_to_relative_humidity, relative_humidity_to_dewpoint,
+    ApiWeatherData, ForecastPrecip, WeatherDataPoint,
+};
+use chrono::{DateTime, Utc};
+
+/// Observations oldest first, followed by the forecasts, on one time axis.
+pub fn build_timeline(weather: &ApiWeatherData) -> Vec<WeatherDataPoint> {
+    let mut timeline = Vec::new();
+
+    // Add observations in reverse order (oldest to newest)
+    for obs in weather.observations_instant.iter().rev() {
+        timeline.push(WeatherDataPoint::Observation(obs.clone()));
+    }
+
+    // Add forecasts (already in chronological order)
+    for fc in &weather.forecasts_instant {
+        timeline.push(WeatherDataPoint::Forecast(fc.clone()));
+    }
+
+    timeline
+}
+
+/// Index of the point closest to `now`, or 0 for an empty timeline.
+pub fn closest_index(timeline: &[WeatherDataPoint], now: DateTime<Utc>) -> usize {
+    let mut closest_index = 0;
+    let mut smallest_diff = i64::MAX;
+
+    for (i, point) in timeline.iter().enumerate() {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(point.valid_date()) {
+            let diff = (dt.timestamp() - now.timestamp()).abs();
+            if diff < smallest_diff {
+                smallest_diff = diff;
+                closest_index = i;
+            }
+        }
+    }
+
+    closest_index
+}
+
+/// The probability of precipitation for the period covering `target_time` (RFC 3339),
+/// preferring the hourly forecasts over the 6-hour ones.
+pub fn precipitation_probability_from(
+    precip_1hr: &[ForecastPrecip],
+    precip_6hr: &[ForecastPrecip],
+    target_time: &str,
+) -> Option<f64> {
+    let target_timestamp = DateTime::parse_from_rfc3339(target_time).ok()?.timestamp();
+
+    // First try 1hr forecasts for more granular data
+    for precip in precip_1hr {
+        if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
+            let valid_timestamp = valid_dt.timestamp();
+            // Check if target time is within the forecast period (up to 1 hour before valid time)
+            if target_timestamp <= valid_timestamp && target_timestamp > valid_timestamp - 3600 {
+                return Some(precip.prob_precip_pct);
+            }
+        }
+    }
+
+    // Fallback to 6hr forecasts
+    for precip in precip_6hr {
+        if let Ok(valid_dt) = DateTime::parse_from_rfc3339(&precip.valid_date) {
+            let valid_timestamp = valid_dt.timestamp();
+            // Check if target time is within the forecast period (up to 6 hours before valid time)
+            if target_timestamp <= valid_timestamp && target_timestamp > valid_timestamp - 21600 {
+                return Some(precip.prob_precip_pct);
+            }
+        }
+    }
+
+    None
+}
+
+/// The value of `element` at a timeline point, in the units the app displays: °F, mph,
+/// hPa, %, miles, W/m² and J/kg. Humidity, dewpoint and apparent temperature are derived
+/// when not reported directly; precipitation probability comes from `weather`'s
+/// precipitation forecasts.
+pub fn element_value(
+    weather: &ApiWeatherData,
+    point: &WeatherDataPoint,
+    element: &WeatherElement,
+) -> Option<f64> {
+    match element {
+        WeatherElement::Temperature => point.temperature(),
+        WeatherElement::WBGT => match point {
+            WeatherDataPoint::Forecast(fc) => fc.wbg_temp_2m_f,
+            WeatherDataPoint::Observation(_) => None,
+        },
+        WeatherElement::WindSpeed => match point {
+            WeatherDataPoint::Observation(obs) => obs.wind_spd_10m_mph,
+            WeatherDataPoint::Forecast(fc) => fc.wind_spd_10m_mph,
+        },
+        WeatherElement::WindGust => match point {
+            WeatherDataPoint::Observation(obs) => obs.wind_gust_10m_mph,
+            WeatherDataPoint::Forecast(fc) => fc.wind_gust_10m_mph,
+        },
+        WeatherElement::Pressure => match point {
+            WeatherDataPoint::Observation(obs) => obs.pressure_h_pa,
+            WeatherDataPoint::Forecast(_) => None,
+        },
+        WeatherElement::Humidity => match point {
+            WeatherDataPoint::Observation(obs) => {
+                if let Some(humidity) = obs.specific_humidity_2m_dg_kg {
+                    Some(humidity / 10.0)
+                } else if let (Some(dewpoint), Some(temp)) =
+                    (obs.dewpoint_2m_f, obs.temperature_2m_f)
+                {
+                    Some(dewpoint_to_relative_humidity(dewpoint, temp))
+                } else {
+                    None
+                }
+            }
+            WeatherDataPoint::Forecast(fc) => {
+                if let (Some(dewpoint), Some(temp)) = (fc.dewpoint_2m_f, fc.temperature_2m_f) {
+                    Some(dewpoint_to_relative_humidity(dewpoint, temp))
+                } else {
+                    None
+                }
+            }
+        },
+        WeatherElement::Dewpoint => match point {
+            WeatherDataPoint::Observation(obs) => {
+                if let Some(dewpoint) = obs.dewpoint_2m_f {
+                    Some(dewpoint)
+                } else if let (Some(humidity), Some(temp)) =
+                    (obs.specific_humidity_2m_dg_kg, obs.temperature_2m_f)
+                {
+                    Some(relative_humidity_to_dewpoint(humidity / 10.0, temp))
+                } else {
+                    None
+                }
+            }
+            WeatherDataPoint::Forecast(fc) => fc.dewpoint_2m_f,
+        },
+        WeatherElement::CloudCover => match point {
+            WeatherDataPoint::Observation(obs) => obs.cloud_cover_pct,
+            WeatherDataPoint::Forecast(fc) => fc.cloud_cover_pct,
+        },
+        WeatherElement::Visibility => match point {
+            WeatherDataPoint::Observation(obs) => obs.visibility_m.map(|v| v / 1609.34),
+            WeatherDataPoint::Forecast(fc) => fc.visibility_m.map(|v| v / 1609.34),
+        },
+        WeatherElement::SolarFlux => match point {
+            WeatherDataPoint::Observation(obs) => obs.solar_flux_w_m2,
+            WeatherDataPoint::Forecast(fc) => fc.solar_flux_w_m2,
+        },
+        WeatherElement::ThunderstormProbability => match point {
+            WeatherDataPoint::Observation(_) => None,
+            WeatherDataPoint::Forecast(fc) => fc.prob_thunderstorm_pct,
+        },
+        WeatherElement::CAPE => match point {
+            WeatherDataPoint::Observation(_) => None,
+            WeatherDataPoint::Forecast(fc) => fc.cape_surface_j_kg,
+        },
+        WeatherElement::ApparentTemperature => {
+            let temp = point.temperature()?;
+            let (humidity, wind) = match point {
+                WeatherDataPoint::Observation(obs) => {
+                    let humidity = if let Some(h) = obs.specific_humidity_2m_dg_kg {
+                        h / 10.0
+                    } else if let Some(d) = obs.dewpoint_2m_f {
+                        dewpoint_to_relative_humidity(d, temp)
+                    } else {
+                        50.0 // Default if no humidity data
+                    };
+                    (humidity, obs.wind_spd_10m_mph.unwrap_or(0.0))
+                }
+                WeatherDataPoint::Forecast(fc) => {
+                    let humidity = if let Some(d) = fc.dewpoint_2m_f {
+                        dewpoint_to_relative_humidity(d, temp)
+                    } else {
+                        50.0 // Default if no humidity data
+                    };
+                    (humidity, fc.wind_spd_10m_mph.unwrap_or(0.0))
+                }
+            };
+            Some(calculate_apparent_temperature(temp, humidity, wind))
+        }
+        WeatherElement::PrecipitationProbability => precipitation_probability_from(
+            &weather.forecasts_precip_1hr,
+            &weather.forecasts_precip_6hr,
+            point.valid_date(),
+        ),
+    }
+}
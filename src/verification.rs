@@ -0,0 +1,245 @@
+//! Forecast verification: scoring stored forecast runs against the observations that arrived
+//! later for the same valid times, by element and lead time.
+//!
+//! The feed doesn't observe precipitation or thunder, so probability forecasts are scored by
+//! [`brier_scores`] against outcomes from another source.
+
+use crate::element::WeatherElement;
+use crate::qc::{self, QcFlag};
+use crate::timeline::element_value;
+use crate::weather::{ApiWeatherData, WeatherDataPoint};
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A forecast as fetched, kept until its valid times have been observed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForecastRun {
+    /// `cycleDate` of the forecasts, which identifies the run
+    pub cycle_date: String,
+    /// The forecasts and precipitation chances of the run, without observations or normals
+    pub weather: ApiWeatherData,
+}
+
+impl ForecastRun {
+    /// The run in a fetched response, or `None` if it has no forecasts.
+    pub fn from_weather(weather: &ApiWeatherData) -> Option<ForecastRun> {
+        let cycle_date = weather.forecasts_instant.first()?.cycle_date.clone();
+        Some(ForecastRun {
+            cycle_date,
+            weather: ApiWeatherData {
+                normal_temperatures: Vec::new(),
+                observations_instant: Vec::new(),
+                ..weather.clone()
+            },
+        })
+    }
+}
+
+/// Hours from a run's cycle to the valid time: more than `after`, up to and including `until`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LeadTime {
+    pub after: i64,
+    pub until: i64,
+}
+
+impl LeadTime {
+    /// Column heading, e.g. "6–12 h".
+    pub fn label(&self) -> String {
+        format!("{}–{} h", self.after, self.until)
+    }
+
+    fn contains(&self, hours: i64) -> bool {
+        hours > self.after && hours <= self.until
+    }
+}
+
+/// The lead times scores are grouped by.
+pub const LEAD_TIMES: [LeadTime; 5] = [
+    LeadTime { after: 0, until: 6 },
+    LeadTime {
+        after: 6,
+        until: 12,
+    },
+    LeadTime {
+        after: 12,
+        until: 24,
+    },
+    LeadTime {
+        after: 24,
+        until: 48,
+    },
+    LeadTime {
+        after: 48,
+        until: 96,
+    },
+];
+
+/// Elements both forecast and observed, scored by [`verify`].
+pub const VERIFIED_ELEMENTS: [WeatherElement; 7] = [
+    WeatherElement::Temperature,
+    WeatherElement::Dewpoint,
+    WeatherElement::WindSpeed,
+    WeatherElement::WindGust,
+    WeatherElement::CloudCover,
+    WeatherElement::Visibility,
+    WeatherElement::SolarFlux,
+];
+
+/// Probability forecasts, scored by [`brier_scores`].
+pub const PROBABILITY_ELEMENTS: [WeatherElement; 2] = [
+    WeatherElement::PrecipitationProbability,
+    WeatherElement::ThunderstormProbability,
+];
+
+/// Errors of one element's forecasts at one lead time, as forecast minus observed in the units
+/// of [`element_value`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scores {
+    pub element: WeatherElement,
+    pub lead: LeadTime,
+    /// Forecasts matched with an observation
+    pub count: usize,
+    pub bias: f64,
+    pub mae: f64,
+    pub rmse: f64,
+}
+
+/// Whether precipitation or a thunderstorm was observed at `valid_date`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Occurrence {
+    pub valid_date: String,
+    /// [`WeatherElement::PrecipitationProbability`] or [`WeatherElement::ThunderstormProbability`]
+    pub element: WeatherElement,
+    pub occurred: bool,
+}
+
+/// Brier score of one probability element at one lead time: 0 is perfect, 1 always wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BrierScore {
+    pub element: WeatherElement,
+    pub lead: LeadTime,
+    pub count: usize,
+    pub brier: f64,
+}
+
+fn timestamp(date: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|time| time.timestamp())
+}
+
+// (lead time index, forecast value, valid time) of every forecast of `element` in `runs`
+fn forecasts<'a>(
+    runs: &'a [ForecastRun],
+    element: &'a WeatherElement,
+) -> impl Iterator<Item = (usize, f64, i64)> + 'a {
+    runs.iter().flat_map(move |run| {
+        let cycle = timestamp(&run.cycle_date);
+        run.weather.forecasts_instant.iter().filter_map(move |fc| {
+            let valid = timestamp(&fc.valid_date)?;
+            let hours = ((valid - cycle?) as f64 / 3600.0).round() as i64;
+            let lead = LEAD_TIMES.iter().position(|lead| lead.contains(hours))?;
+            let point = WeatherDataPoint::Forecast(fc.clone());
+            Some((lead, element_value(&run.weather, &point, element)?, valid))
+        })
+    })
+}
+
+/// Score every run against the observations in `observed`, for each of [`VERIFIED_ELEMENTS`]
+/// and [`LEAD_TIMES`] with at least one match. Observed values with a quality flag in `flags`
+/// are left out.
+pub fn verify(runs: &[ForecastRun], observed: &ApiWeatherData, flags: &[QcFlag]) -> Vec<Scores> {
+    let observations: HashMap<i64, WeatherDataPoint> = observed
+        .observations_instant
+        .iter()
+        .filter_map(|obs| {
+            Some((
+                timestamp(&obs.valid_date)?,
+                WeatherDataPoint::Observation(obs.clone()),
+            ))
+        })
+        .collect();
+
+    let mut scores = Vec::new();
+    for element in &VERIFIED_ELEMENTS {
+        let mut errors: Vec<Vec<f64>> = vec![Vec::new(); LEAD_TIMES.len()];
+        for (lead, forecast, valid) in forecasts(runs, element) {
+            let Some(point) = observations.get(&valid) else {
+                continue;
+            };
+            if qc::flag_for(flags, point, element).is_some() {
+                continue;
+            }
+            if let Some(value) = element_value(observed, point, element) {
+                errors[lead].push(forecast - value);
+            }
+        }
+
+        for (lead, errors) in LEAD_TIMES.iter().zip(errors) {
+            if errors.is_empty() {
+                continue;
+            }
+            let n = errors.len() as f64;
+            scores.push(Scores {
+                element: element.clone(),
+                lead: *lead,
+                count: errors.len(),
+                bias: errors.iter().sum::<f64>() / n,
+                mae: errors.iter().map(|e| e.abs()).sum::<f64>() / n,
+                rmse: (errors.iter().map(|e| e * e).sum::<f64>() / n).sqrt(),
+            });
+        }
+    }
+    scores
+}
+
+/// Score the precipitation and thunderstorm chances in `runs` against observed `occurrences`,
+/// for each of [`PROBABILITY_ELEMENTS`] and [`LEAD_TIMES`] with at least one match.
+pub fn brier_scores(runs: &[ForecastRun], occurrences: &[Occurrence]) -> Vec<BrierScore> {
+    let mut scores = Vec::new();
+    for element in &PROBABILITY_ELEMENTS {
+        let outcomes: HashMap<i64, bool> = occurrences
+            .iter()
+            .filter(|occurrence| occurrence.element == *element)
+            .filter_map(|occurrence| {
+                Some((timestamp(&occurrence.valid_date)?, occurrence.occurred))
+            })
+            .collect();
+
+        let mut pairs: Vec<Vec<(f64, bool)>> = vec![Vec::new(); LEAD_TIMES.len()];
+        for (lead, probability, valid) in forecasts(runs, element) {
+            if let Some(occurred) = outcomes.get(&valid) {
+                pairs[lead].push((probability, *occurred));
+            }
+        }
+
+        for (lead, pairs) in LEAD_TIMES.iter().zip(pairs) {
+            if let Some(brier) = brier_score(&pairs) {
+                scores.push(BrierScore {
+                    element: element.clone(),
+                    lead: *lead,
+                    count: pairs.len(),
+                    brier,
+                });
+            }
+        }
+    }
+    scores
+}
+
+/// Mean squared error of probability forecasts, given as (chance in percent, whether it
+/// happened) pairs. `None` without any forecasts.
+pub fn brier_score(forecasts: &[(f64, bool)]) -> Option<f64> {
+    if forecasts.is_empty() {
+        return None;
+    }
+    let total: f64 = forecasts
+        .iter()
+        .map(|(percent, occurred)| {
+            let outcome = if *occurred { 1.0 } else { 0.0 };
+            (percent / 100.0 - outcome).powi(2)
+        })
+        .sum();
+    Some(total / forecasts.len() as f64)
+}
@@ -1,18 +1,39 @@
 use crate::app::{Message, WeatherApp};
+use crate::color_ramp::Palette;
+use crate::comparison::{self, Comparison};
 use crate::components;
-use crate::meteogram::Meteogram;
+use crate::config::MeteogramPanel;
+use crate::export::ExportFormat;
+use crate::history::History;
+use crate::keys::{self, Action};
+use crate::panels::PANEL_HEIGHT;
+use crate::table::{self, TableColumn};
+use crate::theme::{ChartColors, ElementColor, ThemeMode};
+use chrono::{DateTime, Local};
 use iced::{
     theme,
-    widget::{button, column, container, row, scrollable, text, text_input, Space},
+    widget::{
+        button, canvas::Canvas, column, container, horizontal_rule, pick_list, row, scrollable,
+        text, text_editor, text_input, Space,
+    },
     Alignment, Element, Length,
 };
+use rusty_wx::data_export::DataFormat;
+use rusty_wx::element::{format_scale_value, WeatherElement};
+use rusty_wx::{statistics, timeline, verification};
 
-pub fn view(app: &WeatherApp) -> Element<Message> {
+pub fn view(app: &WeatherApp) -> Element<'_, Message> {
     // Location selection card
     let location_card = create_location_card(app);
 
     // Weather display - always show the layout
-    let weather_display: Element<Message> = if let Some(_weather) = &app.weather_data {
+    let weather_display: Element<Message> = if app.show_help {
+        create_help_overlay(app)
+    } else if let Some(comparison) = &app.comparison {
+        create_comparison_view(app, comparison)
+    } else if let Some(history) = &app.history {
+        create_history_view(app, history)
+    } else if let Some(_weather) = &app.weather_data {
         // Get the current data point from timeline
         if let Some(data_point) = app.combined_timeline.get(app.timeline_index) {
             // Main weather card
@@ -34,9 +55,14 @@ pub fn view(app: &WeatherApp) -> Element<Message> {
             .width(Length::Fill);
 
             // Combine everything
-            column![main_content, meteogram_container,]
-                .spacing(12)
-                .into()
+            let mut content = column![main_content, meteogram_container].spacing(12);
+            if app.show_table {
+                content = content.push(create_table_view(app));
+            }
+            if app.show_accuracy {
+                content = content.push(create_accuracy_view(app));
+            }
+            content.push(create_summary_panel(app)).into()
         } else {
             text("No weather data available").size(16).into()
         }
@@ -48,56 +74,36 @@ pub fn view(app: &WeatherApp) -> Element<Message> {
     let content = column![location_card, weather_display,]
         .spacing(16)
         .padding(16)
+        .width(Length::Fill)
         .align_items(Alignment::Center);
 
-    container(content)
+    // Scrolled as a whole, so the table, accuracy and summary panels under the meteogram can
+    // be reached at any window height
+    container(scrollable(content).height(Length::Fill))
         .width(Length::Fill)
         .height(Length::Fill)
         .padding(10)
         .into()
 }
 
-fn create_location_card(app: &WeatherApp) -> Element<Message> {
-    let denver_button = button(text("Denver").size(14))
-        .on_press(Message::FetchWeather(
-            "Denver".to_string(),
-            39.7392,
-            -104.9903,
-        ))
-        .padding([8, 16])
-        .style(theme::Button::Primary);
-
-    let miami_button = button(text("Miami").size(14))
-        .on_press(Message::FetchWeather(
-            "Miami".to_string(),
-            25.7617,
-            -80.1918,
-        ))
-        .padding([8, 16])
-        .style(theme::Button::Primary);
-
-    let la_button = button(text("Los Angeles").size(14))
-        .on_press(Message::FetchWeather(
-            "Los Angeles".to_string(),
-            34.0522,
-            -118.2437,
-        ))
-        .padding([8, 16])
-        .style(theme::Button::Primary);
-
-    let ny_button = button(text("New York").size(14))
-        .on_press(Message::FetchWeather(
-            "New York".to_string(),
-            40.7128,
-            -74.0060,
-        ))
-        .padding([8, 16])
-        .style(theme::Button::Primary);
-
-    let city_buttons = row![denver_button, la_button, miami_button, ny_button].spacing(10);
+fn create_location_card(app: &WeatherApp) -> Element<'_, Message> {
+    let mut city_buttons = row![].spacing(10);
+    for location in &app.config.locations {
+        city_buttons = city_buttons.push(
+            button(text(&location.name).size(14))
+                .on_press(Message::FetchWeather(
+                    location.name.clone(),
+                    location.lat,
+                    location.lon,
+                ))
+                .padding([8, 16])
+                .style(theme::Button::Primary),
+        );
+    }
 
     // Custom coordinate inputs
     let lat_input = text_input("Latitude", &app.lat_input)
+        .id(app.search_input_id.clone())
         .on_input(Message::LatInputChanged)
         .padding(8)
         .size(14)
@@ -125,7 +131,31 @@ fn create_location_card(app: &WeatherApp) -> Element<Message> {
 
     container(
         column![
-            text("Select Location").size(18),
+            row![
+                text("Select Location").size(18),
+                Space::with_width(Length::Fixed(12.0)),
+                button(text("Compare").size(12))
+                    .on_press(Message::ToggleComparison)
+                    .padding([4, 8])
+                    .style(if app.comparison.is_some() {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Secondary
+                    }),
+                button(text("History").size(12))
+                    .on_press(Message::ToggleHistory)
+                    .padding([4, 8])
+                    .style(if app.history.is_some() {
+                        theme::Button::Primary
+                    } else {
+                        theme::Button::Secondary
+                    }),
+                button(text("Keys (?)").size(12))
+                    .on_press(Message::ToggleHelp)
+                    .padding([4, 8])
+                    .style(theme::Button::Secondary),
+            ]
+            .align_items(Alignment::Center),
             row![
                 city_buttons,
                 Space::with_width(Length::Fixed(30.0)),
@@ -143,50 +173,831 @@ fn create_location_card(app: &WeatherApp) -> Element<Message> {
     .into()
 }
 
-fn create_meteogram_container(app: &WeatherApp) -> Element<Message> {
-    let (precip_1hr, precip_6hr) = if let Some(weather) = &app.weather_data {
-        (
-            weather.forecasts_precip_1hr.clone(),
-            weather.forecasts_precip_6hr.clone(),
-        )
-    } else {
-        (Vec::new(), Vec::new())
-    };
+fn create_meteogram_container(app: &WeatherApp) -> Element<'_, Message> {
+    let mut meteogram = Some(app.build_meteogram());
 
-    let (unified_temp_min, unified_temp_max) = app.get_unified_temp_range(&app.combined_timeline);
-    let meteogram = Meteogram::new(
-        app.combined_timeline.clone(),
-        app.timeline_index,
-        app.selected_weather_element.clone(),
-        precip_1hr,
-        precip_6hr,
-        unified_temp_min,
-        unified_temp_max,
-    );
-
-    let meteogram_canvas = iced::widget::canvas::Canvas::new(meteogram)
-        .width(Length::Fixed(4000.0))
-        .height(Length::Fixed(230.0));
-
-    let meteogram_scrollable = scrollable(meteogram_canvas)
-        .id(app.meteogram_scroll_id.clone())
-        .direction(scrollable::Direction::Horizontal(
-            scrollable::Properties::default(),
-        ))
-        .width(Length::Fill);
+    // Panels share one scrollable so they keep a single time axis and scroll position;
+    // the fixed scales on either side are stacked to the same heights
+    let mut canvases = column![].spacing(0);
+    let mut left_scales = column![].spacing(0);
+    let mut right_scales = column![].spacing(0);
+    let mut visible_count = 0;
 
-    // Create fixed temperature scale on the left
-    let temp_scale = app.create_temp_scale(&app.combined_timeline);
+    for panel in app.config.visible_panels() {
+        visible_count += 1;
+        match panel {
+            MeteogramPanel::Temperature => {
+                if let Some(meteogram) = meteogram.take() {
+                    canvases = canvases.push(
+                        Canvas::new(meteogram)
+                            .width(Length::Fixed(4000.0))
+                            .height(Length::Fixed(230.0)),
+                    );
+                }
+                // Fixed temperature scale on the left, one element scale per overlay on the right
+                left_scales = left_scales.push(app.create_temp_scale(&app.combined_timeline));
+                right_scales = right_scales.push(app.create_element_scales(&app.combined_timeline));
+            }
+            _ => {
+                canvases = canvases.push(
+                    Canvas::new(app.build_panel(panel))
+                        .width(Length::Fixed(4000.0))
+                        .height(Length::Fixed(PANEL_HEIGHT)),
+                );
+                left_scales =
+                    left_scales.push(app.create_panel_scale(&app.combined_timeline, panel));
+                right_scales =
+                    right_scales.push(Space::new(Length::Fixed(50.0), Length::Fixed(PANEL_HEIGHT)));
+            }
+        }
+    }
 
-    // Create fixed element scale on the right
-    let element_scale =
-        app.create_element_scale(&app.combined_timeline, &app.selected_weather_element);
+    let panel_controls = create_panel_controls(app);
 
-    let meteogram_with_scale = row![temp_scale, meteogram_scrollable, element_scale].spacing(0);
+    let panels: Element<Message> = if visible_count == 0 {
+        text("All meteogram panels are hidden").size(14).into()
+    } else {
+        let meteogram_scrollable = scrollable(canvases)
+            .id(app.meteogram_scroll_id.clone())
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::default(),
+            ))
+            .width(Length::Fill);
 
-    container(meteogram_with_scale)
+        row![left_scales, meteogram_scrollable, right_scales]
+            .spacing(0)
+            .into()
+    };
+
+    let legend = create_meteogram_legend(app);
+
+    let mut content = column![panel_controls, legend].spacing(8);
+    if !app.triggered_alerts.is_empty() {
+        content = content.push(create_alert_list(app));
+    }
+    if let Some(changes) = create_run_changes(app) {
+        content = content.push(changes);
+    }
+
+    container(content.push(panels))
         .padding(16)
         .style(theme::Container::Box)
         .width(Length::Fill)
         .into()
 }
+
+// What moved since the previous forecast run, always when something did and otherwise only
+// while the earlier runs are drawn
+fn create_run_changes(app: &WeatherApp) -> Option<Element<'_, Message>> {
+    let previous = app.previous_runs.first()?;
+    let cycle = DateTime::parse_from_rfc3339(&previous.cycle_date)
+        .map(|time| time.format("%a %HZ").to_string())
+        .unwrap_or_else(|_| previous.cycle_date.clone());
+    let muted = ChartColors::from_theme(&app.active_theme()).muted;
+
+    if app.run_changes.is_empty() {
+        return app.show_previous_runs.then(|| {
+            text(format!("No notable changes since the {} run", cycle))
+                .size(13)
+                .style(muted)
+                .into()
+        });
+    }
+
+    let today = Local::now().date_naive();
+    let mut list = column![text(format!("Since the {} run:", cycle))
+        .size(13)
+        .style(muted)]
+    .spacing(2);
+    for change in &app.run_changes {
+        list = list.push(text(change.describe(today)).size(13));
+    }
+    Some(list.into())
+}
+
+// Triggered alert rules, matching the shaded spans on the meteogram
+fn create_alert_list(app: &WeatherApp) -> Element<'_, Message> {
+    let color = ChartColors::from_theme(&app.active_theme()).alert;
+    let mut list = column![].spacing(2);
+    for alert in &app.triggered_alerts {
+        list = list.push(
+            text(format!(
+                "⚠ {} {}",
+                alert.rule.description(),
+                alert.message()
+            ))
+            .size(13)
+            .style(color),
+        );
+    }
+    list.into()
+}
+
+fn create_panel_controls(app: &WeatherApp) -> Element<'_, Message> {
+    let mut controls = row![text("Panels:").size(12)]
+        .spacing(6)
+        .align_items(Alignment::Center);
+
+    for (i, panel_config) in app.config.meteogram_panels.iter().enumerate() {
+        let toggle = button(text(panel_config.panel.label()).size(12))
+            .on_press(Message::TogglePanel(panel_config.panel))
+            .padding([4, 8])
+            .style(if panel_config.visible {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            });
+
+        let mut move_up = button(text("↑").size(12))
+            .padding([4, 6])
+            .style(theme::Button::Secondary);
+        if i > 0 {
+            move_up = move_up.on_press(Message::MovePanelUp(panel_config.panel));
+        }
+
+        controls = controls.push(row![toggle, move_up].spacing(2));
+    }
+
+    let palette_picker = pick_list(
+        &Palette::ALL[..],
+        Some(app.config.color_ramp.palette),
+        Message::SelectPalette,
+    )
+    .text_size(12)
+    .padding([4, 8]);
+
+    let theme_picker = pick_list(
+        &ThemeMode::ALL[..],
+        Some(app.config.theme),
+        Message::SelectThemeMode,
+    )
+    .text_size(12)
+    .padding([4, 8]);
+
+    controls = controls.push(Space::with_width(Length::Fixed(12.0)));
+    controls = controls.push(text("Colors:").size(12));
+    controls = controls.push(palette_picker);
+    controls = controls.push(text("Theme:").size(12));
+    controls = controls.push(theme_picker);
+
+    // Export of the visible panels with their scales and legend
+    let export_buttons = row![
+        button(text("Past Runs").size(12))
+            .on_press(Message::TogglePreviousRuns)
+            .padding([4, 8])
+            .style(if app.show_previous_runs {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            }),
+        button(text("Table").size(12))
+            .on_press(Message::ToggleTable)
+            .padding([4, 8])
+            .style(if app.show_table {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            }),
+        button(text("Accuracy").size(12))
+            .on_press(Message::ToggleAccuracy)
+            .padding([4, 8])
+            .style(if app.show_accuracy {
+                theme::Button::Primary
+            } else {
+                theme::Button::Secondary
+            }),
+        button(text("Export SVG").size(12))
+            .on_press(Message::ExportMeteogram(ExportFormat::Svg))
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+        button(text("Export PNG").size(12))
+            .on_press(Message::ExportMeteogram(ExportFormat::Png))
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+        // The whole timeline with raw and derived values, for analysis elsewhere
+        button(text("Export CSV").size(12))
+            .on_press(Message::ExportData(DataFormat::Csv))
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+        button(text("Export NDJSON").size(12))
+            .on_press(Message::ExportData(DataFormat::Ndjson))
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+        button(text("Export ICS").size(12))
+            .on_press(Message::ExportCalendar)
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+    ]
+    .spacing(6);
+
+    controls = controls
+        .push(Space::with_width(Length::Fill))
+        .push(export_buttons);
+
+    if let Some(status) = &app.export_status {
+        controls = controls.push(text(status).size(12));
+    }
+
+    controls.into()
+}
+
+fn create_meteogram_legend(app: &WeatherApp) -> Element<'_, Message> {
+    // Temperature is always drawn; overlays follow in selection order
+    let colors = ChartColors::from_theme(&app.active_theme());
+    let mut legend = row![text("━ Temperature").size(12)].spacing(16);
+    if app.show_previous_runs && !app.previous_runs.is_empty() {
+        let runs = app.previous_runs.len();
+        legend = legend.push(
+            text(format!(
+                "━ Previous {} run{}",
+                runs,
+                if runs == 1 { "" } else { "s" }
+            ))
+            .size(12)
+            .style(colors.muted),
+        );
+    }
+
+    for element in &app.selected_weather_elements {
+        let axis_note = if element.shares_temp_axis() {
+            " (°F axis)"
+        } else {
+            ""
+        };
+        legend = legend.push(
+            text(format!("━ {}{}", element.label(), axis_note))
+                .size(12)
+                .style(colors.series(element.color())),
+        );
+    }
+
+    legend.into()
+}
+
+// All saved locations on one time axis, with a table of daily highs and lows per site
+fn create_comparison_view<'a>(
+    app: &'a WeatherApp,
+    comparison: &'a Comparison,
+) -> Element<'a, Message> {
+    let element_picker = pick_list(
+        &WeatherElement::ALL[..],
+        Some(comparison.element.clone()),
+        Message::SelectComparisonElement,
+    )
+    .text_size(12)
+    .padding([4, 8]);
+
+    let mut header = row![
+        text("Compare Locations").size(16),
+        Space::with_width(Length::Fixed(12.0)),
+        element_picker,
+    ]
+    .spacing(6)
+    .align_items(Alignment::Center);
+    if comparison.is_loading() {
+        header = header.push(text("Loading...").size(12));
+    }
+
+    // Legend, one color per site
+    let colors = ChartColors::from_theme(&app.active_theme());
+    let mut legend = row![].spacing(16);
+    for (i, entry) in comparison.sites.iter().enumerate() {
+        let label = match &entry.error {
+            Some(e) => format!("━ {} ({})", entry.location.name, e),
+            None => format!("━ {}", entry.location.name),
+        };
+        legend = legend.push(
+            text(label)
+                .size(12)
+                .style(colors.series(comparison::site_color(i))),
+        );
+    }
+
+    let chart = Canvas::new(comparison.chart())
+        .width(Length::Fill)
+        .height(Length::Fixed(300.0));
+
+    // Daily highs and lows
+    let dates = comparison.table_dates(&Local);
+    let mut table = column![].spacing(4);
+    let mut date_row = row![Space::with_width(Length::Fixed(140.0))].spacing(8);
+    for date in &dates {
+        date_row = date_row.push(
+            text(date.format("%a %-m/%-d").to_string())
+                .size(12)
+                .width(Length::Fixed(80.0)),
+        );
+    }
+    table = table.push(date_row);
+    for (i, entry) in comparison.sites.iter().enumerate() {
+        let Some(site) = &entry.site else {
+            continue;
+        };
+        let extremes = comparison::daily_extremes(site, &Local);
+        let mut site_row = row![text(&entry.location.name)
+            .size(13)
+            .width(Length::Fixed(140.0))
+            .style(colors.series(comparison::site_color(i)))]
+        .spacing(8);
+        for date in &dates {
+            let cell = match extremes.iter().find(|(d, _, _)| d == date) {
+                Some((_, high, low)) => format!("{:.0}° / {:.0}°", high, low),
+                None => "–".to_string(),
+            };
+            site_row = site_row.push(text(cell).size(13).width(Length::Fixed(80.0)));
+        }
+        table = table.push(site_row);
+    }
+
+    container(
+        column![
+            header,
+            legend,
+            chart,
+            text("Daily high / low").size(14),
+            table
+        ]
+        .spacing(8),
+    )
+    .padding(16)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+// Hourly rows of the timeline with the selected columns, sortable by clicking a header
+fn create_table_view(app: &WeatherApp) -> Element<'_, Message> {
+    const TIME_WIDTH: f32 = 120.0;
+    const KIND_WIDTH: f32 = 45.0;
+    const CELL_WIDTH: f32 = 80.0;
+
+    let mut column_toggles = row![text("Columns:").size(12)]
+        .spacing(4)
+        .align_items(Alignment::Center);
+    for column in TableColumn::ALL {
+        column_toggles = column_toggles.push(
+            button(text(column.header()).size(11))
+                .on_press(Message::ToggleTableColumn(column))
+                .padding([3, 6])
+                .style(if app.config.table_columns.contains(&column) {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Secondary
+                }),
+        );
+    }
+
+    let header = row![
+        text("Hourly Table").size(16),
+        Space::with_width(Length::Fill),
+        button(text("Copy CSV").size(12))
+            .on_press(Message::CopyTableCsv)
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+    ]
+    .align_items(Alignment::Center);
+
+    let mut column_headers = row![
+        text("Time").size(12).width(Length::Fixed(TIME_WIDTH)),
+        Space::with_width(Length::Fixed(KIND_WIDTH)),
+    ];
+    for column in &app.config.table_columns {
+        let arrow = match app.table_sort {
+            Some((c, true)) if c == *column => " ▲",
+            Some((c, false)) if c == *column => " ▼",
+            _ => "",
+        };
+        column_headers = column_headers.push(
+            button(text(format!("{}{}", column.header(), arrow)).size(12))
+                .on_press(Message::SortTable(*column))
+                .padding([2, 4])
+                .width(Length::Fixed(CELL_WIDTH))
+                .style(theme::Button::Text),
+        );
+    }
+
+    let muted = ChartColors::from_theme(&app.active_theme()).muted;
+    let rows = table::rows(app, app.table_sort);
+    let mut body = column![].spacing(0);
+    for (i, table_row) in rows.iter().enumerate() {
+        // In time order, a rule marks where observations end and the forecast begins
+        if app.table_sort.is_none() && i > 0 && rows[i - 1].observation && !table_row.observation {
+            body = body.push(horizontal_rule(2));
+        }
+
+        let kind = if table_row.observation { "Obs" } else { "Fcst" };
+        let mut cells = row![
+            text(table_row.time.format("%a %-m/%-d %-I %p").to_string())
+                .size(12)
+                .width(Length::Fixed(TIME_WIDTH)),
+            text(kind)
+                .size(12)
+                .width(Length::Fixed(KIND_WIDTH))
+                .style(muted),
+        ];
+        for column in &app.config.table_columns {
+            let cell = match table_row.value(*column) {
+                Some(value) => text(column.format(value)).size(12),
+                None => text("—").size(12).style(muted),
+            };
+            cells = cells.push(cell.width(Length::Fixed(CELL_WIDTH)));
+        }
+
+        let mut cells = container(cells).padding([2, 4]);
+        if table_row.observation {
+            cells = cells.style(theme::Container::Box);
+        }
+        body = body.push(cells);
+    }
+
+    let table_width =
+        TIME_WIDTH + KIND_WIDTH + CELL_WIDTH * app.config.table_columns.len() as f32 + 8.0;
+    let grid = column![
+        container(column_headers).padding([0, 4]),
+        scrollable(body).height(Length::Fixed(400.0)),
+    ]
+    .spacing(4)
+    .width(Length::Fixed(table_width));
+
+    container(
+        column![
+            header,
+            column_toggles,
+            scrollable(grid).direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::default(),
+            )),
+        ]
+        .spacing(8),
+    )
+    .padding(12)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+// How past forecasts for this location verified against the observations, by lead time
+fn create_accuracy_view(app: &WeatherApp) -> Element<'_, Message> {
+    const ELEMENT_WIDTH: f32 = 130.0;
+    const CELL_WIDTH: f32 = 130.0;
+
+    let muted = ChartColors::from_theme(&app.active_theme()).muted;
+    let runs = app.scored_runs;
+
+    let header = row![
+        text(format!("Forecast Accuracy — {}", app.current_city)).size(16),
+        Space::with_width(Length::Fill),
+        text(format!(
+            "{} run{} scored",
+            runs,
+            if runs == 1 { "" } else { "s" }
+        ))
+        .size(12)
+        .style(muted),
+    ]
+    .align_items(Alignment::Center);
+
+    let mut column_headers = row![text("Element").size(12).width(Length::Fixed(ELEMENT_WIDTH))];
+    for lead in &verification::LEAD_TIMES {
+        column_headers =
+            column_headers.push(text(lead.label()).size(12).width(Length::Fixed(CELL_WIDTH)));
+    }
+
+    let mut body = column![].spacing(4);
+    for element in &verification::VERIFIED_ELEMENTS {
+        let mut cells = row![
+            text(format!("{} ({})", element.label(), element_unit(element)))
+                .size(12)
+                .width(Length::Fixed(ELEMENT_WIDTH))
+        ];
+        for lead in &verification::LEAD_TIMES {
+            let scores = app
+                .forecast_scores
+                .iter()
+                .find(|s| s.element == *element && s.lead == *lead);
+            let cell: Element<Message> = match scores {
+                Some(scores) => column![
+                    text(format!(
+                        "{:+.1} / {:.1} / {:.1}",
+                        scores.bias, scores.mae, scores.rmse
+                    ))
+                    .size(12),
+                    text(format!("n = {}", scores.count)).size(10).style(muted),
+                ]
+                .into(),
+                None => text("—").size(12).style(muted).into(),
+            };
+            cells = cells.push(container(cell).width(Length::Fixed(CELL_WIDTH)));
+        }
+        body = body.push(cells);
+    }
+
+    let note = if app.forecast_scores.is_empty() {
+        "No forecasts verified yet: runs are scored once observations for their hours have been \
+         fetched."
+    } else {
+        "Bias / MAE / RMSE of forecast minus observed, over the hours observed so far. Chances of \
+         precipitation and thunderstorms aren't scored (Brier) since the feed doesn't observe them."
+    };
+
+    container(
+        column![
+            header,
+            column_headers,
+            body,
+            text(note).size(11).style(muted),
+        ]
+        .spacing(8),
+    )
+    .padding(12)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+// Archived observations of the current location over a chosen date range: the meteogram,
+// statistics of each element and daily temperatures against the normals
+fn create_history_view<'a>(app: &'a WeatherApp, history: &'a History) -> Element<'a, Message> {
+    // Wide enough per point for the meteogram's time labels, up to a width that still scrolls
+    // smoothly; ranges long enough to reach it are drawn as each day's low and high
+    const POINT_WIDTH: f32 = 16.0;
+    const MAX_CHART_WIDTH: f32 = 8000.0;
+    const LABEL_WIDTH: f32 = 130.0;
+    const CELL_WIDTH: f32 = 80.0;
+
+    let muted = ChartColors::from_theme(&app.active_theme()).muted;
+    let date_input = |placeholder, value, on_input: fn(String) -> Message| {
+        text_input(placeholder, value)
+            .on_input(on_input)
+            .on_submit(Message::LoadHistory)
+            .padding(6)
+            .size(13)
+            .width(Length::Fixed(110.0))
+    };
+    let range_button = |label, message| {
+        button(text(label).size(12))
+            .on_press(message)
+            .padding([4, 8])
+            .style(theme::Button::Secondary)
+    };
+
+    let header = row![
+        text(format!("History — {}", history.location)).size(16),
+        Space::with_width(Length::Fixed(12.0)),
+        range_button("◀", Message::HistoryShift(-1)),
+        date_input(
+            "YYYY-MM-DD",
+            &history.start_input,
+            Message::HistoryStartChanged
+        ),
+        text("to").size(13),
+        date_input("YYYY-MM-DD", &history.end_input, Message::HistoryEndChanged),
+        range_button("▶", Message::HistoryShift(1)),
+        button(text("Show").size(12))
+            .on_press(Message::LoadHistory)
+            .padding([4, 8])
+            .style(theme::Button::Primary),
+        Space::with_width(Length::Fixed(12.0)),
+        range_button("7 days", Message::HistoryLastDays(7)),
+        range_button("30 days", Message::HistoryLastDays(30)),
+        range_button("1 year", Message::HistoryLastDays(365)),
+    ]
+    .spacing(6)
+    .align_items(Alignment::Center);
+
+    let mut content = column![header].spacing(8);
+    if app.archive.is_none() {
+        content = content.push(
+            text("The archive is off, so there is no history: set archive.enabled in the config.")
+                .size(13)
+                .style(muted),
+        );
+    }
+    if let Some(error) = &history.error {
+        content = content.push(text(error).size(13).style(muted));
+    }
+    let Some(site) = &history.site else {
+        return container(content)
+            .padding(16)
+            .style(theme::Container::Box)
+            .width(Length::Fill)
+            .into();
+    };
+
+    // Meteogram of the observations, with the clicked hour read out above it
+    let points = site.combined_timeline.len();
+    let chart = Canvas::new(
+        site.build_meteogram()
+            .on_click(Message::HistoryPointClicked),
+    )
+    .width(Length::Fixed(
+        (points as f32 * POINT_WIDTH).clamp(1000.0, MAX_CHART_WIDTH),
+    ))
+    .height(Length::Fixed(230.0));
+    let chart = row![
+        site.create_temp_scale(&site.combined_timeline),
+        scrollable(chart)
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Properties::default(),
+            ))
+            .width(Length::Fill),
+    ];
+    if let Some(point) = site.combined_timeline.get(site.timeline_index) {
+        let time = DateTime::parse_from_rfc3339(point.valid_date())
+            .map(|time| {
+                time.with_timezone(&Local)
+                    .format("%a %-m/%-d %-I %p")
+                    .to_string()
+            })
+            .unwrap_or_default();
+        let weather = site.weather_data.as_ref();
+        let readout: Vec<String> = statistics::SUMMARIZED_ELEMENTS
+            .iter()
+            .filter_map(|element| {
+                let value = timeline::element_value(weather?, point, element)?;
+                Some(format!(
+                    "{} {} {}",
+                    element.label(),
+                    format_scale_value(element, value),
+                    element_unit(element)
+                ))
+            })
+            .collect();
+        content = content.push(
+            text(format!("{}: {}", time, readout.join(" · ")))
+                .size(12)
+                .style(muted),
+        );
+    }
+    if history.downsampled {
+        content = content.push(
+            text("Each day's low and high are drawn; the tables below cover every hour.")
+                .size(12)
+                .style(muted),
+        );
+    }
+    content = content.push(chart);
+
+    // Lowest, mean and highest value of each element over the range
+    let mut stats = column![row![
+        text("Element").size(12).width(Length::Fixed(LABEL_WIDTH)),
+        text("Min").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Mean").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Max").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Hours").size(12).width(Length::Fixed(CELL_WIDTH)),
+    ]]
+    .spacing(4);
+    for s in &history.statistics {
+        let cell = |value| {
+            text(format_scale_value(&s.element, value))
+                .size(12)
+                .width(Length::Fixed(CELL_WIDTH))
+        };
+        stats = stats.push(row![
+            text(format!(
+                "{} ({})",
+                s.element.label(),
+                element_unit(&s.element)
+            ))
+            .size(12)
+            .width(Length::Fixed(LABEL_WIDTH)),
+            cell(s.min),
+            cell(s.mean),
+            cell(s.max),
+            text(s.count).size(12).width(Length::Fixed(CELL_WIDTH)),
+        ]);
+    }
+
+    // Each day's temperatures against the normal
+    let mut days = column![row![
+        text("Day").size(12).width(Length::Fixed(LABEL_WIDTH)),
+        text("High").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Low").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Mean").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Normal").size(12).width(Length::Fixed(CELL_WIDTH)),
+        text("Departure").size(12).width(Length::Fixed(CELL_WIDTH)),
+    ]]
+    .spacing(4);
+    for day in &history.days {
+        let degrees = |value: Option<f64>, signed: bool| match value {
+            Some(value) if signed => text(format!("{:+.1}°", value)).size(12),
+            Some(value) => text(format!("{:.0}°", value)).size(12),
+            None => text("—").size(12).style(muted),
+        };
+        days = days.push(row![
+            text(day.date.format("%a %-m/%-d").to_string())
+                .size(12)
+                .width(Length::Fixed(LABEL_WIDTH)),
+            degrees(Some(day.high), false).width(Length::Fixed(CELL_WIDTH)),
+            degrees(Some(day.low), false).width(Length::Fixed(CELL_WIDTH)),
+            degrees(Some(day.mean), false).width(Length::Fixed(CELL_WIDTH)),
+            degrees(day.normal, false).width(Length::Fixed(CELL_WIDTH)),
+            degrees(day.departure(), true).width(Length::Fixed(CELL_WIDTH)),
+        ]);
+    }
+    let departure = match statistics::departure_from_normal(&history.days) {
+        Some(departure) => format!(
+            "{:+.1}°F from normal on average over the days with a normal temperature archived.",
+            departure
+        ),
+        None => "No normal temperatures archived for this range.".to_string(),
+    };
+
+    container(
+        content
+            .push(text("Statistics").size(14))
+            .push(stats)
+            .push(text("Daily temperatures").size(14))
+            .push(scrollable(days).height(Length::Fixed(300.0)))
+            .push(text(departure).size(11).style(muted)),
+    )
+    .padding(16)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+// Unit of an element's values in the accuracy and history tables
+fn element_unit(element: &WeatherElement) -> &'static str {
+    match element {
+        WeatherElement::WindSpeed | WeatherElement::WindGust => "mph",
+        WeatherElement::Pressure => "hPa",
+        WeatherElement::CloudCover => "%",
+        WeatherElement::Visibility => "mi",
+        WeatherElement::SolarFlux => "W/m²",
+        _ => "°F",
+    }
+}
+
+// The forecast in words, selectable so it can be read back or copied
+fn create_summary_panel(app: &WeatherApp) -> Element<'_, Message> {
+    let header = row![
+        text("Forecast Summary").size(16),
+        Space::with_width(Length::Fill),
+        button(text("Copy").size(12))
+            .on_press(Message::CopySummary)
+            .padding([4, 8])
+            .style(theme::Button::Secondary),
+    ]
+    .align_items(Alignment::Center);
+
+    container(
+        column![
+            header,
+            text_editor(&app.summary)
+                .on_action(Message::SummaryAction)
+                .padding(8),
+        ]
+        .spacing(8),
+    )
+    .padding(12)
+    .style(theme::Container::Box)
+    .width(Length::Fill)
+    .into()
+}
+
+// Key bindings, in place of the weather display until dismissed
+fn create_help_overlay(app: &WeatherApp) -> Element<'_, Message> {
+    let mut by_action: Vec<(Action, Vec<String>)> = Vec::new();
+    for (key, action) in keys::effective_bindings(&app.config.key_bindings) {
+        match by_action.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, keys)) => keys.push(key),
+            None => by_action.push((action, vec![key])),
+        }
+    }
+    by_action.sort_by_key(|(action, _)| *action);
+
+    let mut rows = column![].spacing(4);
+    for (action, keys) in by_action {
+        let description = match action {
+            Action::SelectLocation(i) => match app.config.locations.get(i) {
+                Some(location) => location.name.clone(),
+                None => continue,
+            },
+            _ => action.description(),
+        };
+        rows = rows.push(
+            row![
+                text(keys.join(", ")).size(14).width(Length::Fixed(160.0)),
+                text(description).size(14),
+            ]
+            .spacing(12),
+        );
+    }
+
+    container(
+        column![
+            text("Keyboard Shortcuts").size(18),
+            rows,
+            text("Bindings can be changed with \"key_bindings\" in the config file").size(12),
+            button(text("Close").size(14))
+                .on_press(Message::ToggleHelp)
+                .padding([6, 16])
+                .style(theme::Button::Primary),
+        ]
+        .spacing(12),
+    )
+    .padding(20)
+    .style(theme::Container::Box)
+    .center_x()
+    .into()
+}
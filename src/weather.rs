@@ -1,4 +1,3 @@
-
 use serde::{Deserialize, Serialize};
 
 // All the weather data structures
@@ -152,10 +151,10 @@ pub fn calculate_heat_index(temp_f: f64, rh_percent: f64) -> f64 {
         + 0.00122874 * t * t * rh
         + 0.00085282 * t * rh * rh
         - 0.00000199 * t * t * rh * rh;
-    if rh < 13.0 && t >= 80.0 && t <= 112.0 {
+    if rh < 13.0 && (80.0..=112.0).contains(&t) {
         let adjustment = ((13.0 - rh) / 4.0) * ((17.0 - (t - 95.0).abs()) / 17.0).sqrt();
         hi -= adjustment;
-    } else if rh > 85.0 && t >= 80.0 && t <= 87.0 {
+    } else if rh > 85.0 && (80.0..=87.0).contains(&t) {
         let adjustment = ((rh - 85.0) / 10.0) * ((87.0 - t) / 5.0);
         hi += adjustment;
     }
@@ -181,18 +180,39 @@ pub fn calculate_apparent_temperature(temp_f: f64, rh_percent: f64, wind_mph: f6
     }
 }
 
-// The async function to fetch weather data
+/// The weather API the data comes from.
+pub const PROVIDER: &str = "api.ottoweather.com";
+
+/// Where the weather API is served from, unless `RUSTY_WX_API_URL` says otherwise.
+pub const DEFAULT_BASE_URL: &str = "http://api.ottoweather.com:8001";
+
+/// The API base URL: `RUSTY_WX_API_URL` if set (e.g. a mock server), else [`DEFAULT_BASE_URL`].
+pub fn base_url() -> String {
+    match std::env::var("RUSTY_WX_API_URL") {
+        Ok(url) if !url.is_empty() => url,
+        _ => DEFAULT_BASE_URL.to_string(),
+    }
+}
+
+/// Fetch a location from the API at [`base_url`].
 pub async fn fetch_weather(lat: f64, lon: f64) -> Result<ApiWeatherData, String> {
+    fetch_weather_from(&base_url(), lat, lon).await
+}
+
+/// Fetch a location from the API served at `base_url`, e.g. `http://127.0.0.1:8001`.
+pub async fn fetch_weather_from(
+    base_url: &str,
+    lat: f64,
+    lon: f64,
+) -> Result<ApiWeatherData, String> {
     let url = format!(
-        "http://api.ottoweather.com:8001/weather?lat={}&lon={}",
-        lat, lon
+        "{}/weather?lat={}&lon={}",
+        base_url.trim_end_matches('/'),
+        lat,
+        lon
     );
-    println!("Fetching weather from: {}", url);
-    let response = reqwest::get(&url).await.map_err(|e| {
-        println!("Request failed: {}", e);
-        e.to_string()
-    })?;
-    println!("Got response with status: {}", response.status());
+    // Nothing logged: cron mails whatever a job writes, so callers report the error instead
+    let response = reqwest::get(&url).await.map_err(|e| e.to_string())?;
     if response.status().is_success() {
         response
             .json::<ApiWeatherData>()
@@ -0,0 +1,298 @@
+// The SQLite archive against the recorded Denver fixture and later fetches made up from it.
+// The fixture's observations run from 2026-10-17T13Z to 2026-10-18T12Z, its forecasts (cycle
+// 2026-10-18T11Z) from 13Z for 72 hours.
+
+mod common;
+
+use chrono::DateTime;
+use common::fixture;
+use rusty_wx::archive::{Archive, ArchivedLocation, Retention, Stored};
+use rusty_wx::weather::{ApiWeatherData, PROVIDER};
+
+fn time(date: &str) -> i64 {
+    DateTime::parse_from_rfc3339(date).unwrap().timestamp()
+}
+
+fn everything() -> std::ops::Range<i64> {
+    0..i64::MAX
+}
+
+// The fixture as fetched 6 hours later: the next cycle, 1°F warmer
+fn next_cycle() -> ApiWeatherData {
+    let mut data = fixture();
+    for fc in &mut data.forecasts_instant {
+        fc.cycle_date = "2026-10-18T17:00:00Z".to_string();
+        fc.temperature_2m_f = fc.temperature_2m_f.map(|t| t + 1.0);
+    }
+    data
+}
+
+fn archive_with_fixture() -> Archive {
+    let mut archive = Archive::open_in_memory().unwrap();
+    let stored = archive
+        .store("Denver", PROVIDER, &fixture(), time("2026-10-18T12:30:00Z"))
+        .unwrap();
+    assert_eq!(
+        stored,
+        Stored {
+            observations: 24,
+            forecasts: 72
+        }
+    );
+    archive
+}
+
+#[test]
+fn range_queries() {
+    let archive = archive_with_fixture();
+
+    let all = archive
+        .observations("Denver", PROVIDER, everything())
+        .unwrap();
+    assert_eq!(all.len(), 24);
+    assert_eq!(all[0].valid_date, "2026-10-17T13:00:00Z");
+
+    // End excluded
+    let morning = archive
+        .observations(
+            "Denver",
+            PROVIDER,
+            time("2026-10-18T06:00:00Z")..time("2026-10-18T09:00:00Z"),
+        )
+        .unwrap();
+    let dates: Vec<&str> = morning.iter().map(|o| o.valid_date.as_str()).collect();
+    assert_eq!(
+        dates,
+        [
+            "2026-10-18T06:00:00Z",
+            "2026-10-18T07:00:00Z",
+            "2026-10-18T08:00:00Z"
+        ]
+    );
+
+    let forecasts = archive
+        .forecasts("Denver", PROVIDER, time("2026-10-20T00:00:00Z")..i64::MAX)
+        .unwrap();
+    assert_eq!(forecasts.len(), 37);
+    assert_eq!(
+        archive.normals("Denver", PROVIDER, everything()).unwrap()[0].temperature_2m_f,
+        60.0
+    );
+    assert_eq!(
+        archive.cycles("Denver", PROVIDER).unwrap(),
+        [time("2026-10-18T11:00:00Z")]
+    );
+
+    // Unknown locations are empty rather than errors
+    assert!(archive
+        .observations("Boulder", PROVIDER, everything())
+        .unwrap()
+        .is_empty());
+    assert!(archive
+        .weather("Boulder", PROVIDER, everything())
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn refetch_is_deduplicated() {
+    let mut archive = archive_with_fixture();
+    let later = time("2026-10-18T12:40:00Z");
+    assert_eq!(
+        archive
+            .store("Denver", PROVIDER, &fixture(), later)
+            .unwrap(),
+        Stored::default()
+    );
+
+    // A corrected observation replaces the archived one
+    let mut corrected = fixture();
+    corrected.observations_instant[0].temperature_2m_f = Some(64.0);
+    assert_eq!(
+        archive
+            .store("Denver", PROVIDER, &corrected, later)
+            .unwrap(),
+        Stored {
+            observations: 1,
+            forecasts: 0
+        }
+    );
+    let observations = archive
+        .observations("Denver", PROVIDER, everything())
+        .unwrap();
+    assert_eq!(observations.len(), 24);
+    assert_eq!(observations[23].temperature_2m_f, Some(64.0));
+}
+
+#[test]
+fn cycles_are_kept_apart() {
+    let mut archive = archive_with_fixture();
+    let stored = archive
+        .store(
+            "Denver",
+            PROVIDER,
+            &next_cycle(),
+            time("2026-10-18T18:00:00Z"),
+        )
+        .unwrap();
+    assert_eq!(stored.forecasts, 72);
+    assert_eq!(archive.cycles("Denver", PROVIDER).unwrap().len(), 2);
+
+    // Both cycles' forecasts for the same hour
+    let hour = time("2026-10-19T00:00:00Z");
+    let forecasts = archive
+        .forecasts("Denver", PROVIDER, hour..hour + 1)
+        .unwrap();
+    let cycles: Vec<&str> = forecasts.iter().map(|f| f.cycle_date.as_str()).collect();
+    assert_eq!(cycles, ["2026-10-18T11:00:00Z", "2026-10-18T17:00:00Z"]);
+
+    // Put back together as one response, with the latest cycle for each hour
+    let weather = archive
+        .weather("Denver", PROVIDER, everything())
+        .unwrap()
+        .unwrap();
+    assert_eq!(weather.observations_instant.len(), 24);
+    assert_eq!(
+        weather.observations_instant[0].valid_date,
+        "2026-10-18T12:00:00Z"
+    );
+    assert_eq!(weather.forecasts_instant.len(), 72);
+    assert!(weather
+        .forecasts_instant
+        .iter()
+        .all(|f| f.cycle_date == "2026-10-18T17:00:00Z"));
+    assert_eq!(weather.forecasts_precip_1hr.len(), 24);
+    assert_eq!(weather.forecasts_precip_6hr.len(), 8);
+    assert_eq!(weather.normal_temperatures.len(), 1);
+    assert_eq!(
+        (weather.latitude, weather.longitude),
+        (fixture().latitude, fixture().longitude)
+    );
+}
+
+#[test]
+fn runs_by_cycle() {
+    let mut archive = archive_with_fixture();
+    archive
+        .store(
+            "Denver",
+            PROVIDER,
+            &next_cycle(),
+            time("2026-10-18T18:00:00Z"),
+        )
+        .unwrap();
+
+    let runs = archive.runs("Denver", PROVIDER, everything()).unwrap();
+    let cycles: Vec<&str> = runs.iter().map(|run| run.cycle_date.as_str()).collect();
+    assert_eq!(cycles, ["2026-10-18T11:00:00Z", "2026-10-18T17:00:00Z"]);
+    for run in &runs {
+        assert_eq!(run.weather.forecasts_instant.len(), 72);
+        assert!(run
+            .weather
+            .forecasts_instant
+            .iter()
+            .all(|fc| fc.cycle_date == run.cycle_date));
+        assert_eq!(run.weather.forecasts_precip_1hr.len(), 24);
+        assert!(run.weather.observations_instant.is_empty());
+    }
+    assert_eq!(
+        runs[1].weather.forecasts_instant[0].temperature_2m_f,
+        fixture().forecasts_instant[0]
+            .temperature_2m_f
+            .map(|t| t + 1.0)
+    );
+
+    // Cycled before 17Z
+    let earlier = archive
+        .runs("Denver", PROVIDER, 0..time("2026-10-18T17:00:00Z"))
+        .unwrap();
+    assert_eq!(earlier.len(), 1);
+    assert_eq!(earlier[0].cycle_date, "2026-10-18T11:00:00Z");
+    assert!(archive
+        .runs("Boulder", PROVIDER, everything())
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn locations_are_per_provider() {
+    let mut archive = archive_with_fixture();
+    archive
+        .store("Denver", "example.org", &fixture(), 0)
+        .unwrap();
+    let locations = archive.locations().unwrap();
+    assert_eq!(
+        locations,
+        [
+            ArchivedLocation {
+                name: "Denver".to_string(),
+                provider: PROVIDER.to_string(),
+                latitude: fixture().latitude,
+                longitude: fixture().longitude,
+            },
+            ArchivedLocation {
+                name: "Denver".to_string(),
+                provider: "example.org".to_string(),
+                latitude: fixture().latitude,
+                longitude: fixture().longitude,
+            },
+        ]
+    );
+}
+
+#[test]
+fn retention() {
+    let mut archive = archive_with_fixture();
+    archive
+        .store(
+            "Denver",
+            PROVIDER,
+            &next_cycle(),
+            time("2026-10-18T18:00:00Z"),
+        )
+        .unwrap();
+    let retention = Retention {
+        observation_days: 2,
+        forecast_days: 1,
+    };
+
+    // A day after the first cycle: it goes, observations older than two days go
+    let now = time("2026-10-19T12:00:00Z");
+    let removed = archive.prune(&retention, now).unwrap();
+    // 72 forecasts and 32 precipitation chances of the first cycle
+    assert_eq!(removed, 104);
+    assert_eq!(
+        archive.cycles("Denver", PROVIDER).unwrap(),
+        [time("2026-10-18T17:00:00Z")]
+    );
+    assert_eq!(
+        archive
+            .observations("Denver", PROVIDER, everything())
+            .unwrap()
+            .len(),
+        24
+    );
+
+    let removed = archive
+        .prune(&retention, time("2026-10-20T00:00:00Z"))
+        .unwrap();
+    // Observations before 2026-10-18T00Z and the rest of the forecasts
+    assert_eq!(removed, 11 + 72 + 32);
+    assert!(archive.cycles("Denver", PROVIDER).unwrap().is_empty());
+}
+
+#[test]
+fn survives_reopening() {
+    let path =
+        std::env::temp_dir().join(format!("rusty-wx-archive-{}.sqlite3", std::process::id()));
+    {
+        let mut archive = Archive::open(&path).unwrap();
+        archive.store("Denver", PROVIDER, &fixture(), 0).unwrap();
+    }
+    let archive = Archive::open(&path).unwrap();
+    let observations = archive
+        .observations("Denver", PROVIDER, everything())
+        .unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(observations.len(), 24);
+}
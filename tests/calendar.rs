@@ -0,0 +1,188 @@
+// Calendar events of the recorded Denver fixture, whose forecasts run from 2026-10-18T13Z to
+// 2026-10-21T12Z, and their iCalendar rendering.
+
+mod common;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use common::{date, fixture};
+use rusty_wx::calendar::{self, CalendarEvent, CalendarRules, EventTime};
+
+// Between the last observation and the first forecast
+fn now() -> DateTime<Utc> {
+    time("2026-10-18T12:30:00Z")
+}
+
+fn time(rfc3339: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(rfc3339)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn utc_offset(hours: i32) -> FixedOffset {
+    FixedOffset::east_opt(hours * 3600).unwrap()
+}
+
+fn events(rules: &CalendarRules, tz: &FixedOffset) -> Vec<CalendarEvent> {
+    calendar::events("Denver", &fixture(), rules, now(), tz)
+}
+
+fn summaries(events: &[CalendarEvent]) -> Vec<&str> {
+    events.iter().map(|event| event.summary.as_str()).collect()
+}
+
+// Only the chosen kind of span: thresholds no hour of the fixture reaches for the others
+fn rules(rain_pct: f64, freeze_f: f64) -> CalendarRules {
+    CalendarRules {
+        rain_pct,
+        freeze_f,
+        heat_wbgt_f: 200.0,
+    }
+}
+
+#[test]
+fn days_follow_the_time_zone() {
+    let days = |tz| {
+        events(&rules(101.0, -100.0), &tz)
+            .into_iter()
+            .map(|event| (event.time, event.summary))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        days(utc_offset(0)),
+        [
+            (
+                EventTime::AllDay(date(18)),
+                "High 72° / Low 43°".to_string()
+            ),
+            (
+                EventTime::AllDay(date(19)),
+                "High 72° / Low 44°".to_string()
+            ),
+            (
+                EventTime::AllDay(date(20)),
+                "High 72° / Low 44°".to_string()
+            ),
+            (
+                EventTime::AllDay(date(21)),
+                "High 68° / Low 44°".to_string()
+            ),
+        ]
+    );
+
+    // In Denver the 18th starts after the 43°F observation and only its first hours of the 21st
+    // are forecast
+    let denver = days(utc_offset(-6));
+    assert_eq!(denver[0].1, "High 72° / Low 44°");
+    assert_eq!(denver[3].1, "High 68° / Low 48°");
+}
+
+#[test]
+fn rain_hours_merge_into_spans() {
+    let events = events(&rules(60.0, -100.0), &utc_offset(0));
+    let spans: Vec<_> = events[4..].iter().map(|event| &event.time).collect();
+    // Each span ends where the first hour below the threshold starts
+    assert_eq!(
+        spans,
+        [
+            &EventTime::Span(time("2026-10-18T21:00:00Z"), time("2026-10-19T02:00:00Z")),
+            &EventTime::Span(time("2026-10-19T08:00:00Z"), time("2026-10-19T13:00:00Z")),
+            &EventTime::Span(time("2026-10-19T14:00:00Z"), time("2026-10-20T02:00:00Z")),
+            &EventTime::Span(time("2026-10-20T20:00:00Z"), time("2026-10-21T13:00:00Z")),
+        ]
+    );
+    assert_eq!(
+        summaries(&events[4..]),
+        [
+            "Rain likely 21:00–02:00",
+            "Rain likely 08:00–13:00",
+            "Rain likely 14:00–02:00",
+            "Rain likely 20:00–13:00",
+        ]
+    );
+    assert_eq!(
+        events[4].description,
+        "Denver: up to 99% chance of precipitation."
+    );
+    assert_eq!(events[4].uid, "rain-20261018T2100Z-39.74_-104.99@rusty-wx");
+
+    // Times in the summaries are those of the time zone, the span itself stays in UTC
+    let denver = self::events(&rules(60.0, -100.0), &utc_offset(-6));
+    assert_eq!(denver[4].summary, "Rain likely 15:00–20:00");
+    assert_eq!(denver[4].time, events[4].time);
+}
+
+// Freezes track the lowest temperature of each span, not the highest
+#[test]
+fn freezes_report_the_low() {
+    let events = events(&rules(101.0, 45.0), &utc_offset(0));
+    assert_eq!(
+        summaries(&events[4..]),
+        [
+            "Freeze warning, low 44°F 02:00–05:00",
+            "Freeze warning, low 44°F 02:00–05:00",
+            "Freeze warning, low 44°F 02:00–05:00",
+        ]
+    );
+    assert_eq!(
+        events[4].description,
+        "Denver: temperatures at or below 45°F."
+    );
+}
+
+fn event(summary: &str, description: &str) -> CalendarEvent {
+    CalendarEvent {
+        uid: "test@rusty-wx".to_string(),
+        time: EventTime::AllDay(date(19)),
+        summary: summary.to_string(),
+        description: description.to_string(),
+    }
+}
+
+#[test]
+fn ics_escapes_text() {
+    let ics = calendar::to_ics(
+        "Weather; Denver",
+        &[event("Rain, hail; wind", "Back\\slash\nnext line")],
+        now(),
+    );
+    let lines: Vec<&str> = ics.split("\r\n").collect();
+    assert!(lines.contains(&r"X-WR-CALNAME:Weather\; Denver"));
+    assert!(lines.contains(&r"SUMMARY:Rain\, hail\; wind"));
+    assert!(lines.contains(&r"DESCRIPTION:Back\\slash\nnext line"));
+    assert!(lines.contains(&"DTSTAMP:20261018T123000Z"));
+    assert!(lines.contains(&"DTSTART;VALUE=DATE:20261019"));
+    assert!(lines.contains(&"DTEND;VALUE=DATE:20261020"));
+    // Every line ends in CRLF, with nothing after the last
+    assert!(ics.ends_with("END:VCALENDAR\r\n"));
+    assert!(!ics.replace("\r\n", "").contains('\n'));
+}
+
+// Long lines fold at 75 octets without splitting a character, and unfold to the original
+#[test]
+fn ics_folds_long_lines() {
+    let description = format!("{}{}", "a".repeat(70), "é".repeat(60));
+    let ics = calendar::to_ics("Denver", &[event("Short", &description)], now());
+    let lines: Vec<&str> = ics.trim_end_matches("\r\n").split("\r\n").collect();
+    assert!(lines.iter().all(|line| line.len() <= 75));
+
+    let start = lines
+        .iter()
+        .position(|line| line.starts_with("DESCRIPTION:"))
+        .unwrap();
+    let continuations: Vec<&str> = lines[start + 1..]
+        .iter()
+        .take_while(|line| line.starts_with(' '))
+        .copied()
+        .collect();
+    // 12 + 70 + 120 octets: 75 on the first line, then 74 on the next since another two-octet
+    // "é" would not fit after the leading space, and the rest
+    assert_eq!(lines[start].len(), 75);
+    assert_eq!(continuations.len(), 2);
+    assert_eq!(continuations[0].len(), 74);
+    let unfolded = lines[start].to_string()
+        + &continuations
+            .iter()
+            .map(|line| &line[1..])
+            .collect::<String>();
+    assert_eq!(unfolded, format!("DESCRIPTION:{}", description));
+}
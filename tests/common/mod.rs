@@ -0,0 +1,19 @@
+// Helpers shared by the integration tests and src/snapshots.rs around the recorded Denver
+// fixture: observations from 2026-10-17T13Z to 2026-10-18T12Z, forecasts (cycle 2026-10-18T11Z)
+// from 13Z for 72 hours and one normal temperature, 60°F at 13Z. Not every test uses every
+// helper.
+#![allow(dead_code)]
+
+use chrono::NaiveDate;
+use rusty_wx::weather::ApiWeatherData;
+
+pub const FIXTURE: &str = include_str!("../fixtures/denver.json");
+
+pub fn fixture() -> ApiWeatherData {
+    serde_json::from_str(FIXTURE).unwrap()
+}
+
+// 2026-10-`day`, within the fixture's days
+pub fn date(day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(2026, 10, day).unwrap()
+}
@@ -0,0 +1,49 @@
+// Daily rows of the recorded Denver fixture's observations and forecasts, with days in UTC.
+
+mod common;
+
+use chrono::Utc;
+use common::{date, fixture};
+use rusty_wx::daily;
+use rusty_wx::element::WeatherElement;
+
+#[test]
+fn every_day_of_the_timeline() {
+    let days = daily::daily_rows(&fixture(), &Utc);
+    let dates: Vec<_> = days.iter().map(|day| day.date).collect();
+    assert_eq!(dates, [date(17), date(18), date(19), date(20), date(21)]);
+
+    // Observations only on the 17th, which has no chances forecast
+    assert_eq!((days[0].high, days[0].low), (Some(67.0), Some(49.0)));
+    assert_eq!(days[0].wind, Some(12.0));
+    assert_eq!((days[0].precipitation, days[0].thunderstorm), (None, None));
+
+    // Observations and forecasts on the 18th
+    assert_eq!((days[1].high, days[1].low), (Some(72.0), Some(43.0)));
+    assert_eq!(days[1].thunderstorm, Some(36.0));
+    assert!(days[1].precipitation.is_some());
+}
+
+#[test]
+fn json_keys_carry_the_units() {
+    let days = daily::daily_rows(&fixture(), &Utc);
+    let json = serde_json::to_value(&days[0]).unwrap();
+    assert_eq!(json["date"], "2026-10-17");
+    assert_eq!(json["high_f"], 67.0);
+    assert_eq!(json["low_f"], 49.0);
+    assert_eq!(json["wind_speed_mph"], 12.0);
+    assert!(json["wind_gust_mph"].is_number() || json["wind_gust_mph"].is_null());
+    assert!(json["precipitation_pct"].is_null());
+    assert!(json["thunderstorm_pct"].is_null());
+}
+
+#[test]
+fn elements_parse_by_key() {
+    assert_eq!(
+        "gust".parse::<WeatherElement>(),
+        Ok(WeatherElement::WindGust)
+    );
+    assert_eq!("WBGT".parse::<WeatherElement>(), Ok(WeatherElement::WBGT));
+    let error = "rain".parse::<WeatherElement>().unwrap_err();
+    assert!(error.starts_with("unknown element 'rain', expected one of: temperature, "));
+}
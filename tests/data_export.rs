@@ -0,0 +1,139 @@
+// CSV and NDJSON export of the recorded Denver fixture: 24 observations, then 72 forecasts.
+
+mod common;
+
+use common::fixture;
+use rusty_wx::data_export::{self, ExportHeader, Record, FIELDS};
+use rusty_wx::timeline;
+use serde_json::Value;
+
+fn header() -> ExportHeader {
+    ExportHeader {
+        exported_at: "2026-10-18T12:30:00+00:00".to_string(),
+        ..ExportHeader::new("Denver, CO", &fixture())
+    }
+}
+
+fn records() -> Vec<Record> {
+    let data = fixture();
+    data_export::records(&data, &timeline::build_timeline(&data))
+}
+
+fn column(key: &str) -> usize {
+    FIELDS.iter().position(|(k, _)| *k == key).unwrap()
+}
+
+#[test]
+fn a_value_for_every_field() {
+    let records = records();
+    assert_eq!(records.len(), 96);
+    assert!(records
+        .iter()
+        .all(|record| record.values.len() == FIELDS.len()));
+    assert!(records
+        .iter()
+        .flat_map(|record| &record.values)
+        .flatten()
+        .all(|value| value.is_finite()));
+
+    let (observation, forecast) = (&records[0], &records[24]);
+    assert_eq!(observation.valid_date, "2026-10-17T13:00:00Z");
+    assert_eq!(
+        (observation.kind, &observation.cycle_date),
+        ("observation", &None)
+    );
+    assert_eq!(forecast.kind, "forecast");
+    assert_eq!(forecast.cycle_date.as_deref(), Some("2026-10-18T11:00:00Z"));
+
+    // Forecast-only fields are missing from observations and the other way round
+    assert_eq!(observation.values[column("wbgt_f")], None);
+    assert!(forecast.values[column("wbgt_f")].is_some());
+    assert!(observation.values[column("pressure_hpa")].is_some());
+    assert_eq!(forecast.values[column("pressure_hpa")], None);
+}
+
+#[test]
+fn csv_header_block_and_columns() {
+    let csv = data_export::to_csv(&header(), &records());
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[..6],
+        [
+            "# location: Denver, CO",
+            "# latitude: 39.74",
+            "# longitude: -104.99",
+            "# provider: api.ottoweather.com",
+            "# forecast_cycle: 2026-10-18T11:00:00Z",
+            "# exported_at: 2026-10-18T12:30:00+00:00",
+        ]
+    );
+    assert!(lines[6].starts_with("# units: valid_date=UTC, temperature_f=°F, dewpoint_f=°F, "));
+    assert!(lines[6].ends_with(", wind_chill_f=°F"));
+
+    let columns: Vec<&str> = lines[7].split(',').collect();
+    assert_eq!(
+        columns[..4],
+        ["valid_date", "kind", "cycle_date", "temperature_f"]
+    );
+    assert_eq!(columns.len(), 3 + FIELDS.len());
+    assert_eq!(lines.len(), 8 + 96);
+    assert!(csv.ends_with('\n'));
+}
+
+#[test]
+fn missing_values_are_empty_cells_and_nulls() {
+    let records = records();
+    let csv = data_export::to_csv(&header(), &records);
+    let row: Vec<&str> = csv.lines().nth(8).unwrap().split(',').collect();
+    assert_eq!(row.len(), 3 + FIELDS.len());
+    assert_eq!(row[..3], ["2026-10-17T13:00:00Z", "observation", ""]);
+    assert_eq!(row[3 + column("wbgt_f")], "");
+
+    let ndjson = data_export::to_ndjson(&header(), &records);
+    let first: Value = serde_json::from_str(ndjson.lines().nth(1).unwrap()).unwrap();
+    assert_eq!(first["cycle_date"], Value::Null);
+    assert_eq!(first["wbgt_f"], Value::Null);
+    // Every field is present, null or not
+    assert_eq!(first.as_object().unwrap().len(), 3 + FIELDS.len());
+}
+
+#[test]
+fn ndjson_header_line() {
+    let ndjson = data_export::to_ndjson(&header(), &records());
+    let lines: Vec<Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 1 + 96);
+    assert_eq!(lines[0]["kind"], "header");
+    assert_eq!(lines[0]["location"], "Denver, CO");
+    assert_eq!(lines[0]["forecast_cycle"], "2026-10-18T11:00:00Z");
+    assert_eq!(lines[0]["units"]["visibility_mi"], "mi");
+    assert_eq!(lines[0]["units"].as_object().unwrap().len(), FIELDS.len());
+}
+
+// Both formats carry the same rounded values for every record
+#[test]
+fn csv_and_ndjson_agree() {
+    let records = records();
+    let csv = data_export::to_csv(&header(), &records);
+    let ndjson = data_export::to_ndjson(&header(), &records);
+
+    let rows = csv.lines().skip(8);
+    let objects = ndjson.lines().skip(1);
+    for ((row, object), record) in rows.zip(objects).zip(&records) {
+        let cells: Vec<&str> = row.split(',').collect();
+        let object: Value = serde_json::from_str(object).unwrap();
+        assert_eq!(cells[0], object["valid_date"]);
+        assert_eq!(cells[1], object["kind"]);
+        assert_eq!(cells[2], object["cycle_date"].as_str().unwrap_or(""));
+        for (i, (key, _)) in FIELDS.iter().enumerate() {
+            let from_csv = Some(cells[3 + i])
+                .filter(|cell| !cell.is_empty())
+                .map(|cell| cell.parse::<f64>().unwrap());
+            assert_eq!(from_csv, object[key].as_f64(), "{} at {}", key, cells[0]);
+            let rounded = record.values[i].map(|v| (v * 100.0).round() / 100.0);
+            assert_eq!(from_csv, rounded, "{} at {}", key, cells[0]);
+        }
+    }
+}
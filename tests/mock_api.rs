@@ -0,0 +1,190 @@
+// Fetch and timeline construction against the mock API, with the recorded Denver fixture
+// (24 hourly observations, newest first, and 72 hourly forecasts)
+
+mod common;
+
+use chrono::{DateTime, Utc};
+use common::FIXTURE;
+use rusty_wx::element::WeatherElement;
+use rusty_wx::mock::{MockResponse, MockServer};
+use rusty_wx::timeline::{self, element_value};
+use rusty_wx::weather::{self, WeatherDataPoint};
+use std::time::{Duration, Instant};
+
+async fn serve(response: MockResponse) -> MockServer {
+    MockServer::start(response).await.expect("mock server")
+}
+
+async fn fetch(server: &MockServer) -> Result<weather::ApiWeatherData, String> {
+    weather::fetch_weather_from(&server.base_url(), 39.74, -104.99).await
+}
+
+#[tokio::test]
+async fn fetches_fixture() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    let data = fetch(&server).await.unwrap();
+
+    assert_eq!(data.latitude, 39.74);
+    assert_eq!(data.observations_instant.len(), 24);
+    assert_eq!(data.forecasts_instant.len(), 72);
+    assert_eq!(data.forecasts_precip_1hr.len(), 24);
+    assert_eq!(data.forecasts_precip_6hr.len(), 8);
+    assert_eq!(server.requests(), ["/weather?lat=39.74&lon=-104.99"]);
+}
+
+#[tokio::test]
+async fn base_url_trailing_slash() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    let base_url = format!("{}/", server.base_url());
+    weather::fetch_weather_from(&base_url, 1.0, 2.0)
+        .await
+        .unwrap();
+    assert_eq!(server.requests(), ["/weather?lat=1&lon=2"]);
+}
+
+#[tokio::test]
+async fn timeline_is_chronological() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    let data = fetch(&server).await.unwrap();
+    let timeline = timeline::build_timeline(&data);
+
+    assert_eq!(timeline.len(), 96);
+    assert!(matches!(timeline[0], WeatherDataPoint::Observation(_)));
+    assert!(matches!(timeline[23], WeatherDataPoint::Observation(_)));
+    assert!(matches!(timeline[24], WeatherDataPoint::Forecast(_)));
+    let times: Vec<DateTime<Utc>> = timeline
+        .iter()
+        .map(|point| point.valid_date().parse().unwrap())
+        .collect();
+    assert!(times.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // Selection snaps to the nearest point, and to the ends outside the timeline
+    let first_forecast = times[24];
+    assert_eq!(
+        timeline::closest_index(&timeline, first_forecast + chrono::Duration::minutes(20)),
+        24
+    );
+    assert_eq!(
+        timeline::closest_index(&timeline, times[0] - chrono::Duration::days(3)),
+        0
+    );
+    assert_eq!(
+        timeline::closest_index(&timeline, times[95] + chrono::Duration::days(3)),
+        95
+    );
+}
+
+#[tokio::test]
+async fn derived_values() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    let data = fetch(&server).await.unwrap();
+    let timeline = timeline::build_timeline(&data);
+    let observation = &timeline[23];
+    let forecast = &timeline[24];
+
+    // Only forecasts carry WBGT, thunderstorm chances and CAPE; only observations pressure
+    assert!(element_value(&data, observation, &WeatherElement::WBGT).is_none());
+    assert!(element_value(&data, forecast, &WeatherElement::WBGT).is_some());
+    assert!(element_value(&data, observation, &WeatherElement::Pressure).is_some());
+    assert!(element_value(&data, forecast, &WeatherElement::Pressure).is_none());
+
+    let humidity = element_value(&data, forecast, &WeatherElement::Humidity).unwrap();
+    assert!((0.0..=100.0).contains(&humidity));
+    let temperature = forecast.temperature().unwrap();
+    let dewpoint = element_value(&data, forecast, &WeatherElement::Dewpoint).unwrap();
+    assert!(dewpoint <= temperature);
+}
+
+#[tokio::test]
+async fn error_status() {
+    let server = serve(MockResponse::status(503)).await;
+    let error = fetch(&server).await.unwrap_err();
+    assert!(error.contains("503"), "{}", error);
+}
+
+#[tokio::test]
+async fn truncated_json() {
+    let server = serve(MockResponse::raw(FIXTURE).truncated()).await;
+    assert!(fetch(&server).await.is_err());
+}
+
+#[tokio::test]
+async fn invalid_json() {
+    let server = serve(MockResponse::raw("<html>Bad gateway</html>")).await;
+    assert!(fetch(&server).await.is_err());
+}
+
+#[tokio::test]
+async fn missing_optional_fields() {
+    let server = serve(MockResponse::raw(FIXTURE).without_fields(&[
+        "dewpoint2mF",
+        "wbgTemp2mF",
+        "forecastsPrecip6hr",
+    ]))
+    .await;
+    let data = fetch(&server).await.unwrap();
+    assert!(data.forecasts_precip_6hr.is_empty());
+
+    let timeline = timeline::build_timeline(&data);
+    assert_eq!(timeline.len(), 96);
+    assert!(timeline.iter().all(|point| point.temperature().is_some()));
+    let forecasts: Vec<&WeatherDataPoint> = timeline
+        .iter()
+        .filter(|point| matches!(point, WeatherDataPoint::Forecast(_)))
+        .collect();
+    // Forecast humidity is derived from the dewpoint
+    for element in [
+        WeatherElement::WBGT,
+        WeatherElement::Dewpoint,
+        WeatherElement::Humidity,
+    ] {
+        assert!(forecasts
+            .iter()
+            .all(|point| element_value(&data, point, &element).is_none()));
+    }
+}
+
+#[tokio::test]
+async fn missing_required_fields() {
+    let server = serve(MockResponse::raw(FIXTURE).without_fields(&["forecastsInstant"])).await;
+    let error = fetch(&server).await.unwrap_err();
+    assert!(error.contains("decoding"), "{}", error);
+}
+
+#[tokio::test]
+async fn delayed_response() {
+    let server = serve(MockResponse::raw(FIXTURE).with_delay(Duration::from_millis(200))).await;
+    let started = Instant::now();
+    fetch(&server).await.unwrap();
+    assert!(started.elapsed() >= Duration::from_millis(200));
+}
+
+#[tokio::test]
+async fn queued_responses() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    server.push_response(MockResponse::status(500));
+    server.push_response(MockResponse::raw(FIXTURE).truncated());
+
+    assert!(fetch(&server).await.is_err());
+    assert!(fetch(&server).await.is_err());
+    assert!(fetch(&server).await.is_ok());
+    assert!(fetch(&server).await.is_ok());
+    assert_eq!(server.requests().len(), 4);
+}
+
+#[tokio::test]
+async fn unknown_path() {
+    let server = serve(MockResponse::raw(FIXTURE)).await;
+    let response = reqwest::get(format!("{}/forecast", server.base_url()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+}
+
+#[test]
+fn base_url_from_environment() {
+    std::env::set_var("RUSTY_WX_API_URL", "http://127.0.0.1:9999");
+    assert_eq!(weather::base_url(), "http://127.0.0.1:9999");
+    std::env::remove_var("RUSTY_WX_API_URL");
+    assert_eq!(weather::base_url(), weather::DEFAULT_BASE_URL);
+}
@@ -0,0 +1,126 @@
+// Observation quality checks against the recorded Denver fixture with faults injected.
+// Observations in the fixture are newest first, hourly from 2026-10-17T13Z to 2026-10-18T12Z.
+
+mod common;
+
+use common::fixture;
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::{self, QcCheck, QcFlag, QcRules};
+use rusty_wx::timeline;
+use rusty_wx::weather::{ApiWeatherData, ObservationInstant};
+
+// `hours_ago` from the latest observation
+fn observation(data: &mut ApiWeatherData, hours_ago: usize) -> &mut ObservationInstant {
+    &mut data.observations_instant[hours_ago]
+}
+
+fn check(data: &ApiWeatherData) -> Vec<QcFlag> {
+    qc::check_observations(data, &QcRules::default())
+}
+
+fn summary(flags: &[QcFlag]) -> Vec<(&str, WeatherElement, QcCheck)> {
+    flags
+        .iter()
+        .map(|flag| (flag.valid_date.as_str(), flag.element.clone(), flag.check))
+        .collect()
+}
+
+#[test]
+fn clean_fixture_has_no_flags() {
+    assert_eq!(check(&fixture()), []);
+}
+
+#[test]
+fn temperature_spike() {
+    let mut data = fixture();
+    let spike = observation(&mut data, 5);
+    let time = spike.valid_date.clone();
+    spike.temperature_2m_f = spike.temperature_2m_f.map(|t| t + 25.0);
+    // The neighbours are compared against the last good value, so only the spike is flagged
+    let flags = check(&data);
+
+    assert_eq!(
+        summary(&flags),
+        [(time.as_str(), WeatherElement::Temperature, QcCheck::Step)]
+    );
+    assert!(
+        flags[0].reason.starts_with("changed +"),
+        "{}",
+        flags[0].reason
+    );
+}
+
+#[test]
+fn out_of_range() {
+    let mut data = fixture();
+    observation(&mut data, 2).pressure_h_pa = Some(101.2);
+    observation(&mut data, 3).cloud_cover_pct = Some(140.0);
+    let flags = check(&data);
+
+    let checks: Vec<_> = flags.iter().map(|f| (f.element.clone(), f.check)).collect();
+    assert_eq!(
+        checks,
+        [
+            (WeatherElement::CloudCover, QcCheck::Range),
+            (WeatherElement::Pressure, QcCheck::Range),
+        ]
+    );
+}
+
+#[test]
+fn dewpoint_above_temperature() {
+    let mut data = fixture();
+    let point = observation(&mut data, 10);
+    point.dewpoint_2m_f = point.temperature_2m_f.map(|t| t + 4.0);
+    let flags = check(&data);
+
+    assert!(flags
+        .iter()
+        .any(|f| f.element == WeatherElement::Dewpoint && f.check == QcCheck::Consistency));
+    assert!(flags
+        .iter()
+        .all(|f| f.element != WeatherElement::Temperature));
+}
+
+#[test]
+fn stuck_sensor() {
+    let mut data = fixture();
+    for hours_ago in 4..=11 {
+        observation(&mut data, hours_ago).pressure_h_pa = Some(1013.0);
+    }
+    let flags = check(&data);
+    let stuck: Vec<&QcFlag> = flags
+        .iter()
+        .filter(|f| f.element == WeatherElement::Pressure)
+        .collect();
+
+    // 8 readings spanning 7 hours
+    assert_eq!(stuck.len(), 8);
+    assert!(stuck.iter().all(|f| f.check == QcCheck::Stuck));
+    assert_eq!(stuck[0].reason, "unchanged for 7 h");
+
+    // Shorter than the limit is fine
+    let rules = QcRules {
+        stuck_hours: 8.0,
+        ..QcRules::default()
+    };
+    assert!(qc::check_observations(&data, &rules).is_empty());
+}
+
+#[test]
+fn flags_apply_to_observations_only() {
+    let mut data = fixture();
+    observation(&mut data, 0).temperature_2m_f = Some(150.0);
+    let flags = check(&data);
+    let points = timeline::build_timeline(&data);
+    let latest = &points[23];
+    let first_forecast = &points[24];
+
+    assert_eq!(latest.valid_date(), "2026-10-18T12:00:00Z");
+    let flag = qc::flag_for(&flags, latest, &WeatherElement::Temperature).unwrap();
+    assert_eq!(flag.check, QcCheck::Range);
+    // Derived from the flagged temperature
+    assert!(qc::flag_for(&flags, latest, &WeatherElement::ApparentTemperature).is_some());
+    assert!(qc::flag_for(&flags, latest, &WeatherElement::WindSpeed).is_none());
+    assert!(qc::flag_for(&flags, first_forecast, &WeatherElement::Temperature).is_none());
+}
@@ -0,0 +1,103 @@
+// Run-to-run changes between the recorded Denver fixture and altered copies of it standing in
+// for an earlier run, with days in UTC.
+
+mod common;
+
+use chrono::{NaiveDate, Utc};
+use common::{date, fixture};
+use rusty_wx::run_changes::{self, DailyValue, RunChange};
+use rusty_wx::weather::{ApiWeatherData, ForecastInstant};
+
+// Change the forecasts valid on 2026-10-`day`
+fn alter(data: &mut ApiWeatherData, day: u32, change: impl Fn(&mut ForecastInstant)) {
+    let prefix = format!("2026-10-{}", day);
+    data.forecasts_instant
+        .iter_mut()
+        .filter(|fc| fc.valid_date.starts_with(&prefix))
+        .for_each(change);
+}
+
+#[test]
+fn same_run_has_no_changes() {
+    assert!(run_changes::compare(&fixture(), &fixture(), &Utc).is_empty());
+}
+
+#[test]
+fn reports_changes_past_the_thresholds() {
+    let current = fixture();
+    let mut previous = fixture();
+    alter(&mut previous, 19, |fc| {
+        fc.temperature_2m_f = fc.temperature_2m_f.map(|t| t - 4.0)
+    });
+    alter(&mut previous, 20, |fc| {
+        fc.wind_gust_10m_mph = fc.wind_gust_10m_mph.map(|g| g + 10.0)
+    });
+    // Too small to mention
+    alter(&mut previous, 21, |fc| {
+        fc.temperature_2m_f = fc.temperature_2m_f.map(|t| t + 1.0)
+    });
+
+    let changes = run_changes::compare(&previous, &current, &Utc);
+    let summary: Vec<(NaiveDate, DailyValue)> = changes.iter().map(|c| (c.date, c.value)).collect();
+    assert_eq!(
+        summary,
+        [
+            (date(19), DailyValue::High),
+            (date(19), DailyValue::Low),
+            (date(20), DailyValue::PeakGust)
+        ]
+    );
+
+    let today = date(18);
+    assert_eq!(
+        changes[0].describe(today),
+        "Tomorrow's high up 4° (68° → 72°)"
+    );
+    assert_eq!(
+        changes[2].describe(today),
+        "Tuesday's peak gusts down 10 mph (46 mph → 36 mph)"
+    );
+}
+
+#[test]
+fn precipitation_chance() {
+    let current = fixture();
+    let mut previous = fixture();
+    for precip in &mut previous.forecasts_precip_1hr {
+        precip.prob_precip_pct = (precip.prob_precip_pct - 30.0).max(0.0);
+    }
+
+    let changes = run_changes::compare(&previous, &current, &Utc);
+    assert_eq!(
+        changes,
+        [RunChange {
+            date: date(18),
+            value: DailyValue::PrecipitationChance,
+            previous: 51.0,
+            current: 81.0,
+        }]
+    );
+    assert_eq!(
+        changes[0].describe(date(18)),
+        "Today's chance of precipitation up 30% (51% → 81%)"
+    );
+}
+
+#[test]
+fn compares_only_common_hours() {
+    let current = fixture();
+    let mut previous = fixture();
+    for fc in &mut previous.forecasts_instant {
+        fc.temperature_2m_f = fc.temperature_2m_f.map(|t| t - 5.0);
+    }
+    // The earlier run only overlaps the last 4 hours of the 19th
+    previous
+        .forecasts_instant
+        .retain(|fc| fc.valid_date.as_str() >= "2026-10-19T20:00:00Z");
+
+    let days: Vec<NaiveDate> = run_changes::compare(&previous, &current, &Utc)
+        .iter()
+        .map(|c| c.date)
+        .collect();
+    assert_eq!(days, [date(20), date(20), date(21), date(21)]);
+}
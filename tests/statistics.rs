@@ -0,0 +1,114 @@
+// Statistics of the recorded Denver fixture's observations and its one normal temperature, with
+// days in UTC.
+
+mod common;
+
+use chrono::Utc;
+use common::{date, fixture};
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::{self, QcRules};
+use rusty_wx::statistics::{self, SUMMARIZED_ELEMENTS};
+
+#[test]
+fn statistics_of_each_element() {
+    let stats = statistics::statistics(&fixture(), &[]);
+    let elements: Vec<WeatherElement> = stats.iter().map(|s| s.element.clone()).collect();
+    assert_eq!(elements, SUMMARIZED_ELEMENTS);
+    assert!(stats.iter().all(|s| s.count == 24));
+
+    let temperature = &stats[0];
+    assert_eq!((temperature.min, temperature.max), (43.0, 67.0));
+    assert!((temperature.mean - 55.0).abs() < 1e-9);
+
+    let cloud_cover = stats
+        .iter()
+        .find(|s| s.element == WeatherElement::CloudCover)
+        .unwrap();
+    assert_eq!(
+        (cloud_cover.min, cloud_cover.mean, cloud_cover.max),
+        (40.0, 40.0, 40.0)
+    );
+}
+
+#[test]
+fn flagged_values_are_left_out() {
+    let mut data = fixture();
+    // The newest observation
+    data.observations_instant[0].temperature_2m_f = Some(150.0);
+    let flags = qc::check_observations(&data, &QcRules::default());
+    assert!(!flags.is_empty());
+
+    let unchecked = statistics::statistics(&data, &[]);
+    assert_eq!(unchecked[0].max, 150.0);
+
+    let stats = statistics::statistics(&data, &flags);
+    assert_eq!((stats[0].count, stats[0].max), (23, 67.0));
+    let days = statistics::daily_temperatures(&data, &flags, &Utc);
+    assert_eq!(days[1].count, 12);
+}
+
+#[test]
+fn daily_temperatures_against_the_normal() {
+    let days = statistics::daily_temperatures(&fixture(), &[], &Utc);
+    assert_eq!(days.len(), 2);
+
+    assert_eq!(days[0].date, date(17));
+    assert_eq!(days[0].count, 11);
+    assert_eq!((days[0].high, days[0].low), (67.0, 49.0));
+    assert_eq!(days[0].normal, None);
+    assert_eq!(days[0].departure(), None);
+
+    assert_eq!(days[1].date, date(18));
+    assert_eq!(days[1].count, 13);
+    assert_eq!(days[1].low, 43.0);
+    assert_eq!(days[1].normal, Some(60.0));
+    let departure = days[1].departure().unwrap();
+    assert!((departure - (days[1].mean - 60.0)).abs() < 1e-9);
+    assert!(departure < -9.0);
+
+    // Only the 18th has a normal
+    assert_eq!(statistics::departure_from_normal(&days), Some(departure));
+}
+
+#[test]
+fn each_days_low_and_high() {
+    let data = fixture();
+    let extremes = statistics::daily_extremes(&data, &[], &Utc);
+    let days = statistics::daily_temperatures(&data, &[], &Utc);
+    assert_eq!(extremes.len(), 4);
+
+    // Newest first, like the feed, and one low and one high from each day
+    let times: Vec<&str> = extremes.iter().map(|obs| obs.valid_date.as_str()).collect();
+    let mut sorted = times.clone();
+    sorted.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(times, sorted);
+    for (day, pair) in days.iter().rev().zip(extremes.chunks(2)) {
+        let mut temperatures: Vec<f64> =
+            pair.iter().filter_map(|obs| obs.temperature_2m_f).collect();
+        temperatures.sort_by(f64::total_cmp);
+        assert_eq!(temperatures, [day.low, day.high]);
+    }
+
+    // A flagged spike isn't the day's high
+    let mut data = fixture();
+    data.observations_instant[0].temperature_2m_f = Some(150.0);
+    let flags = qc::check_observations(&data, &QcRules::default());
+    let extremes = statistics::daily_extremes(&data, &flags, &Utc);
+    assert!(extremes
+        .iter()
+        .all(|obs| obs.temperature_2m_f != Some(150.0)));
+}
+
+#[test]
+fn nothing_to_summarize() {
+    let mut data = fixture();
+    data.normal_temperatures.clear();
+    let days = statistics::daily_temperatures(&data, &[], &Utc);
+    assert!(days.iter().all(|day| day.normal.is_none()));
+    assert_eq!(statistics::departure_from_normal(&days), None);
+
+    data.observations_instant.clear();
+    assert!(statistics::statistics(&data, &[]).is_empty());
+    assert!(statistics::daily_temperatures(&data, &[], &Utc).is_empty());
+    assert!(statistics::daily_extremes(&data, &[], &Utc).is_empty());
+}
@@ -0,0 +1,91 @@
+// The plain-language summary of the recorded Denver fixture, whose forecasts run from
+// 2026-10-18T13Z to 2026-10-21T12Z, in a fixed time zone whatever the machine's.
+
+mod common;
+
+use chrono::{DateTime, FixedOffset, Utc};
+use common::fixture;
+use rusty_wx::{summary, timeline};
+
+fn summary_at(now: &str, utc_offset_hours: i32) -> Vec<String> {
+    let data = fixture();
+    let now = DateTime::parse_from_rfc3339(now)
+        .unwrap()
+        .with_timezone(&Utc);
+    let tz = FixedOffset::east_opt(utc_offset_hours * 3600).unwrap();
+    summary::forecast_summary(
+        &timeline::build_timeline(&data),
+        &data.forecasts_precip_1hr,
+        &data.forecasts_precip_6hr,
+        now,
+        &tz,
+    )
+}
+
+fn period_names(lines: &[String]) -> Vec<&str> {
+    lines
+        .iter()
+        .map(|line| line.split(':').next().unwrap())
+        .collect()
+}
+
+#[test]
+fn periods_in_utc() {
+    let lines = summary_at("2026-10-18T12:30:00Z", 0);
+    assert_eq!(
+        period_names(&lines),
+        [
+            "This afternoon",
+            "Tonight",
+            "Tomorrow",
+            "Tomorrow night",
+            "Tuesday",
+            "Tuesday night",
+            "Wednesday"
+        ]
+    );
+    assert_eq!(
+        lines[0],
+        "This afternoon: Sunny, high 72°, winds NE 9 gusting 16, \
+         27% chance of precipitation after 5 PM."
+    );
+    assert_eq!(
+        lines[1],
+        "Tonight: Partly cloudy, low 44°, winds S 21 gusting 28, \
+         99% chance of precipitation, 36% chance of thunderstorms."
+    );
+}
+
+// The same instant is early morning in Denver, so the day has only begun and the forecast
+// ends on Tuesday night there
+#[test]
+fn periods_follow_the_time_zone() {
+    let lines = summary_at("2026-10-18T12:30:00Z", -6);
+    assert_eq!(
+        period_names(&lines),
+        [
+            "Today",
+            "Tonight",
+            "Tomorrow",
+            "Tomorrow night",
+            "Tuesday",
+            "Tuesday night"
+        ]
+    );
+    assert!(
+        lines[0].ends_with("81% chance of precipitation after 11 AM, 36% chance of thunderstorms.")
+    );
+}
+
+#[test]
+fn past_periods_are_left_out() {
+    let lines = summary_at("2026-10-18T19:00:00Z", 0);
+    assert_eq!(period_names(&lines)[0], "Tonight");
+
+    // Only the last forecast hour is left, still worth a line since it's today
+    let lines = summary_at("2026-10-21T12:00:00Z", 0);
+    assert_eq!(
+        lines,
+        ["This afternoon: Cloudy, high 68°, winds N 16 gusting 33, 98% chance of precipitation."]
+    );
+}
@@ -0,0 +1,155 @@
+// Forecast verification against observations made up from the recorded Denver fixture's own
+// forecasts. The fixture's run is cycled at 2026-10-18T11Z with hourly forecasts from 13Z.
+
+mod common;
+
+use common::fixture;
+use rusty_wx::element::WeatherElement;
+use rusty_wx::qc::{self, QcRules};
+use rusty_wx::verification::{self, ForecastRun, LeadTime, Occurrence, Scores, LEAD_TIMES};
+use rusty_wx::weather::{ApiWeatherData, ForecastInstant, ObservationInstant};
+
+fn run() -> ForecastRun {
+    ForecastRun::from_weather(&fixture()).unwrap()
+}
+
+// An observation at the forecast's valid time with only temperature and wind reported
+fn observation(
+    fc: &ForecastInstant,
+    temperature_error: f64,
+    wind_error: f64,
+) -> ObservationInstant {
+    ObservationInstant {
+        valid_date: fc.valid_date.clone(),
+        temperature_2m_f: fc.temperature_2m_f.map(|t| t - temperature_error),
+        dewpoint_2m_f: None,
+        specific_humidity_2m_dg_kg: None,
+        cloud_cover_pct: None,
+        cloud_ceiling_m: None,
+        visibility_m: None,
+        pressure_h_pa: None,
+        wind_dir_10m_deg_fm_n: None,
+        wind_spd_10m_mph: fc.wind_spd_10m_mph.map(|w| w - wind_error),
+        wind_gust_10m_mph: None,
+        solar_flux_w_m2: None,
+    }
+}
+
+// The first 30 forecast hours observed, newest first as in the feed: temperatures 2°F colder
+// than forecast and winds off by 3 mph either way
+fn observed() -> ApiWeatherData {
+    let mut data = fixture();
+    let forecasts = std::mem::take(&mut data.forecasts_instant);
+    data.observations_instant = forecasts[..30]
+        .iter()
+        .enumerate()
+        .map(|(i, fc)| observation(fc, 2.0, if i % 2 == 0 { 3.0 } else { -3.0 }))
+        .rev()
+        .collect();
+    data
+}
+
+fn scores_for(scores: &[Scores], element: WeatherElement) -> Vec<&Scores> {
+    scores.iter().filter(|s| s.element == element).collect()
+}
+
+#[test]
+fn run_keeps_forecasts_only() {
+    let run = run();
+    assert_eq!(run.cycle_date, "2026-10-18T11:00:00Z");
+    assert_eq!(run.weather.forecasts_instant.len(), 72);
+    assert_eq!(run.weather.forecasts_precip_1hr.len(), 24);
+    assert!(run.weather.observations_instant.is_empty());
+}
+
+#[test]
+fn scores_by_lead_time() {
+    let scores = verification::verify(&[run()], &observed(), &[]);
+
+    let temperature = scores_for(&scores, WeatherElement::Temperature);
+    let leads: Vec<(LeadTime, usize)> = temperature.iter().map(|s| (s.lead, s.count)).collect();
+    // Lead times 2 to 31 hours
+    assert_eq!(
+        leads,
+        [
+            (LEAD_TIMES[0], 5),
+            (LEAD_TIMES[1], 6),
+            (LEAD_TIMES[2], 12),
+            (LEAD_TIMES[3], 7)
+        ]
+    );
+    for s in &temperature {
+        assert!((s.bias - 2.0).abs() < 1e-9);
+        assert!((s.mae - 2.0).abs() < 1e-9);
+        assert!((s.rmse - 2.0).abs() < 1e-9);
+    }
+
+    // Errors that cancel out: no bias, but still 3 mph off every hour
+    let wind = scores_for(&scores, WeatherElement::WindSpeed);
+    assert_eq!(wind[2].count, 12);
+    assert!(wind[2].bias.abs() < 1e-9);
+    assert!((wind[2].mae - 3.0).abs() < 1e-9);
+    assert!((wind[2].rmse - 3.0).abs() < 1e-9);
+
+    // Not reported, so not scored
+    assert!(scores_for(&scores, WeatherElement::CloudCover).is_empty());
+}
+
+#[test]
+fn flagged_observations_are_not_scored() {
+    let mut observed = observed();
+    // 10 hours into the forecast, newest first
+    observed.observations_instant[19].temperature_2m_f = Some(150.0);
+    let flags = qc::check_observations(&observed, &QcRules::default());
+    assert_eq!(flags.len(), 1);
+
+    let unchecked = verification::verify(&[run()], &observed, &[]);
+    assert!(scores_for(&unchecked, WeatherElement::Temperature)[1].bias < -10.0);
+
+    let scores = verification::verify(&[run()], &observed, &flags);
+    let temperature = scores_for(&scores, WeatherElement::Temperature);
+    assert_eq!(temperature[1].count, 5);
+    assert!((temperature[1].bias - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn nothing_observed_yet() {
+    let mut observed = observed();
+    observed.observations_instant.clear();
+    assert!(verification::verify(&[run()], &observed, &[]).is_empty());
+    assert!(verification::verify(&[], &fixture(), &[]).is_empty());
+}
+
+#[test]
+fn brier_score() {
+    assert_eq!(verification::brier_score(&[]), None);
+    assert_eq!(
+        verification::brier_score(&[(100.0, true), (0.0, false)]),
+        Some(0.0)
+    );
+    let brier = verification::brier_score(&[(70.0, true), (20.0, false)]).unwrap();
+    assert!((brier - 0.065).abs() < 1e-9);
+}
+
+#[test]
+fn brier_scores_by_lead_time() {
+    // Chances of 9, 18, 27 and 36% at 15Z to 18Z; it rained from 16Z
+    let occurrences: Vec<Occurrence> = [("15", false), ("16", true), ("17", true), ("18", true)]
+        .into_iter()
+        .map(|(hour, occurred)| Occurrence {
+            valid_date: format!("2026-10-18T{}:00:00Z", hour),
+            element: WeatherElement::PrecipitationProbability,
+            occurred,
+        })
+        .collect();
+    let scores = verification::brier_scores(&[run()], &occurrences);
+
+    assert_eq!(scores.len(), 2);
+    assert_eq!((scores[0].lead, scores[0].count), (LEAD_TIMES[0], 3));
+    assert!(
+        (scores[0].brier - (0.09f64.powi(2) + 0.82f64.powi(2) + 0.73f64.powi(2)) / 3.0).abs()
+            < 1e-9
+    );
+    assert_eq!((scores[1].lead, scores[1].count), (LEAD_TIMES[1], 1));
+    assert!((scores[1].brier - 0.64f64.powi(2)).abs() < 1e-9);
+}